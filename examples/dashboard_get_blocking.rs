@@ -0,0 +1,16 @@
+use grafana::{Auth, BlockingClient, Result};
+
+fn main() -> Result<()> {
+    let base_url = std::env::var("GRAFANA_URL").expect("GRAFANA_URL is required");
+    let token = std::env::var("GRAFANA_TOKEN").expect("GRAFANA_TOKEN is required");
+    let dashboard_uid =
+        std::env::var("GRAFANA_DASHBOARD_UID").expect("GRAFANA_DASHBOARD_UID is required");
+
+    let client = BlockingClient::builder(base_url)?
+        .auth(Auth::bearer(token))
+        .build()?;
+
+    let dashboard = client.dashboards().get_by_uid(dashboard_uid)?;
+    println!("{}", dashboard.dashboard);
+    Ok(())
+}
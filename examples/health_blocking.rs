@@ -0,0 +1,17 @@
+use grafana::{Auth, BlockingClient, Result};
+
+fn main() -> Result<()> {
+    let base_url = std::env::var("GRAFANA_URL").expect("GRAFANA_URL is required");
+    let token = std::env::var("GRAFANA_TOKEN").ok();
+
+    let mut builder =
+        BlockingClient::builder(base_url)?.timeout(std::time::Duration::from_secs(30));
+    if let Some(token) = token {
+        builder = builder.auth(Auth::bearer(token));
+    }
+
+    let client = builder.build()?;
+    let health = client.health().get()?;
+    println!("{health:?}");
+    Ok(())
+}
@@ -0,0 +1,88 @@
+use std::time::Duration;
+
+use crate::{
+    BlockingClient, Error, Result,
+    types::{
+        AlertInstance, AlertInstances, ManagedRuleState, PrometheusResponse, RuleDiscovery,
+        RuleStateFilter, StateHistoryFrame, StateHistoryQuery, StateTransition, merge_transitions,
+    },
+};
+
+/// Evaluation state of Grafana-managed rules through the Prometheus-compatible API
+/// (`/prometheus/grafana/api/v1/...`), and their state history.
+#[derive(Clone)]
+pub struct BlockingAlertStateService {
+    client: BlockingClient,
+}
+
+impl BlockingAlertStateService {
+    pub(crate) fn new(client: BlockingClient) -> Self {
+        Self { client }
+    }
+
+    pub fn rules(&self, filter: &RuleStateFilter) -> Result<RuleDiscovery> {
+        let segments = ["prometheus", "grafana", "api", "v1", "rules"];
+        let response: PrometheusResponse<RuleDiscovery> = self
+            .client
+            .get_json(&segments, Some(filter.query().as_slice()))?;
+        Ok(response.data)
+    }
+
+    /// Every alert instance that is pending or firing.
+    pub fn alerts(&self) -> Result<Vec<AlertInstance>> {
+        let segments = ["prometheus", "grafana", "api", "v1", "alerts"];
+        let response: PrometheusResponse<AlertInstances> =
+            self.client.get_json(&segments, Option::<&()>::None)?;
+        Ok(response.data.alerts)
+    }
+
+    /// [`rules`](Self::rules) joined by UID with their provisioning definitions. Fetches the one
+    /// definition when the filter has a `rule_uid`, and all of them otherwise.
+    pub fn rules_with_definitions(
+        &self,
+        filter: &RuleStateFilter,
+    ) -> Result<Vec<ManagedRuleState>> {
+        let discovery = self.rules(filter)?;
+        let alerting = self.client.alerting();
+        let definitions = match &filter.rule_uid {
+            None => alerting.alert_rules()?,
+            Some(_) if discovery.rules().next().is_none() => Vec::new(),
+            Some(uid) => match alerting.alert_rule(uid) {
+                Ok(rule) => vec![rule],
+                // Deleted since its last evaluation.
+                Err(Error::NotFound(_)) => Vec::new(),
+                Err(err) => return Err(err),
+            },
+        };
+        Ok(discovery.with_definitions(definitions))
+    }
+
+    /// State transitions from `/v1/rules/history`, as Grafana returns them.
+    pub fn history(&self, query: &StateHistoryQuery) -> Result<Vec<StateTransition>> {
+        let segments = ["v1", "rules", "history"];
+        let frame: StateHistoryFrame = self
+            .client
+            .get_json(&segments, Some(query.query().as_slice()))?;
+        Ok(frame.0)
+    }
+
+    /// All transitions from `query.from` to `query.to` (or now), fetched in windows of at most
+    /// `chunk` and returned oldest first. A window that returns `query.limit` transitions (1000
+    /// when unset, and never 0) is split in half and fetched again, down to one-second windows.
+    pub fn history_in_chunks(
+        &self,
+        query: &StateHistoryQuery,
+        chunk: Duration,
+    ) -> Result<Vec<StateTransition>> {
+        let mut windows = query.windows(chunk)?;
+        let mut transitions = Vec::new();
+        while let Some(window) = windows.pop() {
+            let page = self.history(&window)?;
+            match window.split(&page) {
+                Some(halves) => windows.extend(halves),
+                None => transitions.extend(page),
+            }
+        }
+        Ok(merge_transitions(transitions))
+    }
+}
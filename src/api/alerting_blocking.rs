@@ -0,0 +1,252 @@
+use http::{HeaderName, HeaderValue, Method};
+
+use crate::{
+    BlockingClient, RequestOptions, Result,
+    api::BlockingOpenApi,
+    types::{
+        AlertRuleGroup, ContactPoint, MuteTimeInterval, NotificationTemplate, ProvisionedAlertRule,
+        Route, openapi::RouteGetContactpointsQuery,
+    },
+};
+
+/// Typed alerting provisioning API (`/v1/provisioning/...`).
+///
+/// Reads go through the [`BlockingOpenApi`] `route_*` methods. Creates, updates and deletes use the
+/// client directly, because the generated routes take no [`RequestOptions`] to carry
+/// `X-Disable-Provenance`.
+#[derive(Clone)]
+pub struct BlockingAlertingService {
+    client: BlockingClient,
+    disable_provenance: bool,
+}
+
+impl BlockingAlertingService {
+    pub(crate) fn new(client: BlockingClient) -> Self {
+        Self {
+            client,
+            disable_provenance: false,
+        }
+    }
+
+    /// Sends `X-Disable-Provenance: true` on creates, updates and deletes, so the resources stay
+    /// editable in the UI and can be removed the same way they were provisioned.
+    pub fn disable_provenance(mut self) -> Self {
+        self.disable_provenance = true;
+        self
+    }
+
+    pub fn alert_rules(&self) -> Result<Vec<ProvisionedAlertRule>> {
+        self.routes().route_get_alert_rules()
+    }
+
+    pub fn alert_rule(&self, uid: impl AsRef<str>) -> Result<ProvisionedAlertRule> {
+        self.routes().route_get_alert_rule(uid)
+    }
+
+    pub fn create_alert_rule(&self, rule: &ProvisionedAlertRule) -> Result<ProvisionedAlertRule> {
+        let segments = ["v1", "provisioning", "alert-rules"];
+        self.write(Method::POST, &segments, rule)
+    }
+
+    pub fn update_alert_rule(
+        &self,
+        uid: impl AsRef<str>,
+        rule: &ProvisionedAlertRule,
+    ) -> Result<ProvisionedAlertRule> {
+        let segments = ["v1", "provisioning", "alert-rules", uid.as_ref()];
+        self.write(Method::PUT, &segments, rule)
+    }
+
+    pub fn delete_alert_rule(&self, uid: impl AsRef<str>) -> Result<()> {
+        let segments = ["v1", "provisioning", "alert-rules", uid.as_ref()];
+        self.delete(&segments, None)
+    }
+
+    pub fn alert_rule_group(
+        &self,
+        folder_uid: impl AsRef<str>,
+        group: impl AsRef<str>,
+    ) -> Result<AlertRuleGroup> {
+        self.routes().route_get_alert_rule_group(folder_uid, group)
+    }
+
+    /// Creates or replaces the group: rules missing from `group.rules` are deleted.
+    pub fn put_alert_rule_group(&self, group: &AlertRuleGroup) -> Result<AlertRuleGroup> {
+        let segments = [
+            "v1",
+            "provisioning",
+            "folder",
+            &group.folder_uid,
+            "rule-groups",
+            &group.title,
+        ];
+        self.write(Method::PUT, &segments, group)
+    }
+
+    pub fn delete_alert_rule_group(
+        &self,
+        folder_uid: impl AsRef<str>,
+        group: impl AsRef<str>,
+    ) -> Result<()> {
+        let segments = [
+            "v1",
+            "provisioning",
+            "folder",
+            folder_uid.as_ref(),
+            "rule-groups",
+            group.as_ref(),
+        ];
+        self.delete(&segments, None)
+    }
+
+    /// All contact points, or those named `name`.
+    pub fn contact_points(&self, name: Option<&str>) -> Result<Vec<ContactPoint>> {
+        let query = RouteGetContactpointsQuery {
+            name: name.map(str::to_owned),
+        };
+        self.routes().route_get_contactpoints(Some(&query))
+    }
+
+    pub fn create_contact_point(&self, contact_point: &ContactPoint) -> Result<ContactPoint> {
+        let segments = ["v1", "provisioning", "contact-points"];
+        self.write(Method::POST, &segments, contact_point)
+    }
+
+    pub fn update_contact_point(
+        &self,
+        uid: impl AsRef<str>,
+        contact_point: &ContactPoint,
+    ) -> Result<()> {
+        let segments = ["v1", "provisioning", "contact-points", uid.as_ref()];
+        self.write_ack(Method::PUT, &segments, contact_point)
+    }
+
+    pub fn delete_contact_point(&self, uid: impl AsRef<str>) -> Result<()> {
+        let segments = ["v1", "provisioning", "contact-points", uid.as_ref()];
+        self.delete(&segments, None)
+    }
+
+    pub fn policy_tree(&self) -> Result<Route> {
+        self.routes().route_get_policy_tree()
+    }
+
+    /// Replaces the whole notification policy tree.
+    pub fn update_policy_tree(&self, root: &Route) -> Result<()> {
+        let segments = ["v1", "provisioning", "policies"];
+        self.write_ack(Method::PUT, &segments, root)
+    }
+
+    /// Restores the default policy tree.
+    pub fn reset_policy_tree(&self) -> Result<()> {
+        self.delete(&["v1", "provisioning", "policies"], None)
+    }
+
+    pub fn mute_timings(&self) -> Result<Vec<MuteTimeInterval>> {
+        let timings: Option<Vec<MuteTimeInterval>> = self.routes().route_get_mute_timings()?;
+        Ok(timings.unwrap_or_default())
+    }
+
+    pub fn mute_timing(&self, name: impl AsRef<str>) -> Result<MuteTimeInterval> {
+        self.routes().route_get_mute_timing(name)
+    }
+
+    pub fn create_mute_timing(&self, timing: &MuteTimeInterval) -> Result<MuteTimeInterval> {
+        let segments = ["v1", "provisioning", "mute-timings"];
+        self.write(Method::POST, &segments, timing)
+    }
+
+    /// Updates `timing.name`. With `timing.version` set (as returned by a read), the update fails
+    /// with [`Error::Conflict`](crate::Error::Conflict) if someone changed it in between.
+    pub fn update_mute_timing(&self, timing: &MuteTimeInterval) -> Result<MuteTimeInterval> {
+        let segments = ["v1", "provisioning", "mute-timings", &timing.name];
+        self.write(Method::PUT, &segments, timing)
+    }
+
+    /// Deletes the mute timing, only if it is still at `version` when one is given.
+    pub fn delete_mute_timing(&self, name: impl AsRef<str>, version: Option<&str>) -> Result<()> {
+        let segments = ["v1", "provisioning", "mute-timings", name.as_ref()];
+        self.delete(&segments, version)
+    }
+
+    pub fn templates(&self) -> Result<Vec<NotificationTemplate>> {
+        let templates: Option<Vec<NotificationTemplate>> = self.routes().route_get_templates()?;
+        Ok(templates.unwrap_or_default())
+    }
+
+    pub fn template(&self, name: impl AsRef<str>) -> Result<NotificationTemplate> {
+        self.routes().route_get_template(name)
+    }
+
+    /// Creates or updates `template.name`. With `template.version` set, the update fails with
+    /// [`Error::Conflict`](crate::Error::Conflict) if someone changed it in between.
+    pub fn put_template(&self, template: &NotificationTemplate) -> Result<NotificationTemplate> {
+        let segments = ["v1", "provisioning", "templates", &template.name];
+        self.write(Method::PUT, &segments, &template.content())
+    }
+
+    /// Deletes the template, only if it is still at `version` when one is given.
+    pub fn delete_template(&self, name: impl AsRef<str>, version: Option<&str>) -> Result<()> {
+        let segments = ["v1", "provisioning", "templates", name.as_ref()];
+        self.delete(&segments, version)
+    }
+
+    fn routes(&self) -> BlockingOpenApi {
+        self.client.openapi()
+    }
+
+    fn write<T, B>(&self, method: Method, segments: &[&str], body: &B) -> Result<T>
+    where
+        T: serde::de::DeserializeOwned,
+        B: serde::Serialize + ?Sized,
+    {
+        self.client.request_json_with_options::<T, (), B>(
+            method,
+            segments,
+            None,
+            Some(body),
+            &self.options(),
+        )
+    }
+
+    /// Writes whose response is only an acknowledgement.
+    fn write_ack<B>(&self, method: Method, segments: &[&str], body: &B) -> Result<()>
+    where
+        B: serde::Serialize + ?Sized,
+    {
+        self.client.request_bytes_with_options::<(), B>(
+            method,
+            segments,
+            None,
+            Some(body),
+            &self.options(),
+        )?;
+        Ok(())
+    }
+
+    /// Deletes, only at `version` when one is given.
+    fn delete(&self, segments: &[&str], version: Option<&str>) -> Result<()> {
+        let query = version.map(|version| ("version", version));
+        self.client
+            .request_bytes_with_options::<[(&str, &str)], ()>(
+                Method::DELETE,
+                segments,
+                query.as_ref().map(std::slice::from_ref),
+                None,
+                &self.options(),
+            )?;
+        Ok(())
+    }
+
+    /// Grafana decides provenance on every provisioning write, deletes included.
+    fn options(&self) -> RequestOptions {
+        let options = RequestOptions::new();
+        if self.disable_provenance {
+            options.header(
+                HeaderName::from_static("x-disable-provenance"),
+                HeaderValue::from_static("true"),
+            )
+        } else {
+            options
+        }
+    }
+}
@@ -0,0 +1,103 @@
+use http::Method;
+
+use crate::{
+    Client, Result,
+    types::{
+        AlertFilter, AlertGroup, GettableAlert, GettableSilence, Matcher, Receiver, Silence,
+        SilenceCreated, filter_query,
+    },
+};
+
+/// Alertmanager v2 API of Grafana's own Alertmanager, or of an Alertmanager datasource.
+#[derive(Clone)]
+pub struct AlertmanagerService {
+    client: Client,
+    alertmanager: String,
+}
+
+impl AlertmanagerService {
+    pub(crate) fn new(client: Client) -> Self {
+        Self {
+            client,
+            alertmanager: "grafana".to_owned(),
+        }
+    }
+
+    /// Targets the Alertmanager datasource `uid` instead of Grafana's own.
+    pub fn datasource(mut self, uid: impl Into<String>) -> Self {
+        self.alertmanager = uid.into();
+        self
+    }
+
+    pub async fn alerts(&self, filter: &AlertFilter) -> Result<Vec<GettableAlert>> {
+        let segments = self.segments(&["alerts"]);
+        self.client
+            .get_json(&segments, Some(filter.query().as_slice()))
+            .await
+    }
+
+    pub async fn alert_groups(&self, filter: &AlertFilter) -> Result<Vec<AlertGroup>> {
+        let segments = self.segments(&["alerts", "groups"]);
+        self.client
+            .get_json(&segments, Some(filter.query().as_slice()))
+            .await
+    }
+
+    /// Silences matching all of `matchers`, including expired ones.
+    pub async fn silences(&self, matchers: &[Matcher]) -> Result<Vec<GettableSilence>> {
+        let segments = self.segments(&["silences"]);
+        let query: Vec<_> = filter_query(matchers).collect();
+        self.client
+            .get_json(&segments, Some(query.as_slice()))
+            .await
+    }
+
+    pub async fn silence(&self, id: impl AsRef<str>) -> Result<GettableSilence> {
+        let segments = self.segments(&["silence", id.as_ref()]);
+        self.client.get_json(&segments, Option::<&()>::None).await
+    }
+
+    /// Creates a silence and returns its id.
+    pub async fn create_silence(&self, silence: &Silence) -> Result<String> {
+        self.post_silence(&Silence {
+            id: None,
+            ..silence.clone()
+        })
+        .await
+    }
+
+    /// Replaces silence `id` and returns the id it has now, which differs from `id` when
+    /// Alertmanager had to expire the old silence.
+    pub async fn update_silence(&self, id: impl Into<String>, silence: &Silence) -> Result<String> {
+        self.post_silence(&Silence {
+            id: Some(id.into()),
+            ..silence.clone()
+        })
+        .await
+    }
+
+    pub async fn expire_silence(&self, id: impl AsRef<str>) -> Result<()> {
+        let segments = self.segments(&["silence", id.as_ref()]);
+        self.client
+            .request_bytes::<(), ()>(Method::DELETE, &segments, None, None)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn receivers(&self) -> Result<Vec<Receiver>> {
+        let segments = self.segments(&["receivers"]);
+        self.client.get_json(&segments, Option::<&()>::None).await
+    }
+
+    async fn post_silence(&self, silence: &Silence) -> Result<String> {
+        let segments = self.segments(&["silences"]);
+        let created: SilenceCreated = self.client.post_json(&segments, silence).await?;
+        Ok(created.silence_id)
+    }
+
+    fn segments<'a>(&'a self, path: &[&'a str]) -> Vec<&'a str> {
+        let mut segments = vec!["alertmanager", self.alertmanager.as_str(), "api", "v2"];
+        segments.extend_from_slice(path);
+        segments
+    }
+}
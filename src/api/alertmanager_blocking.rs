@@ -0,0 +1,96 @@
+use http::Method;
+
+use crate::{
+    BlockingClient, Result,
+    types::{
+        AlertFilter, AlertGroup, GettableAlert, GettableSilence, Matcher, Receiver, Silence,
+        SilenceCreated, filter_query,
+    },
+};
+
+/// Alertmanager v2 API of Grafana's own Alertmanager, or of an Alertmanager datasource.
+#[derive(Clone)]
+pub struct BlockingAlertmanagerService {
+    client: BlockingClient,
+    alertmanager: String,
+}
+
+impl BlockingAlertmanagerService {
+    pub(crate) fn new(client: BlockingClient) -> Self {
+        Self {
+            client,
+            alertmanager: "grafana".to_owned(),
+        }
+    }
+
+    /// Targets the Alertmanager datasource `uid` instead of Grafana's own.
+    pub fn datasource(mut self, uid: impl Into<String>) -> Self {
+        self.alertmanager = uid.into();
+        self
+    }
+
+    pub fn alerts(&self, filter: &AlertFilter) -> Result<Vec<GettableAlert>> {
+        let segments = self.segments(&["alerts"]);
+        self.client
+            .get_json(&segments, Some(filter.query().as_slice()))
+    }
+
+    pub fn alert_groups(&self, filter: &AlertFilter) -> Result<Vec<AlertGroup>> {
+        let segments = self.segments(&["alerts", "groups"]);
+        self.client
+            .get_json(&segments, Some(filter.query().as_slice()))
+    }
+
+    /// Silences matching all of `matchers`, including expired ones.
+    pub fn silences(&self, matchers: &[Matcher]) -> Result<Vec<GettableSilence>> {
+        let segments = self.segments(&["silences"]);
+        let query: Vec<_> = filter_query(matchers).collect();
+        self.client.get_json(&segments, Some(query.as_slice()))
+    }
+
+    pub fn silence(&self, id: impl AsRef<str>) -> Result<GettableSilence> {
+        let segments = self.segments(&["silence", id.as_ref()]);
+        self.client.get_json(&segments, Option::<&()>::None)
+    }
+
+    /// Creates a silence and returns its id.
+    pub fn create_silence(&self, silence: &Silence) -> Result<String> {
+        self.post_silence(&Silence {
+            id: None,
+            ..silence.clone()
+        })
+    }
+
+    /// Replaces silence `id` and returns the id it has now, which differs from `id` when
+    /// Alertmanager had to expire the old silence.
+    pub fn update_silence(&self, id: impl Into<String>, silence: &Silence) -> Result<String> {
+        self.post_silence(&Silence {
+            id: Some(id.into()),
+            ..silence.clone()
+        })
+    }
+
+    pub fn expire_silence(&self, id: impl AsRef<str>) -> Result<()> {
+        let segments = self.segments(&["silence", id.as_ref()]);
+        self.client
+            .request_bytes::<(), ()>(Method::DELETE, &segments, None, None)?;
+        Ok(())
+    }
+
+    pub fn receivers(&self) -> Result<Vec<Receiver>> {
+        let segments = self.segments(&["receivers"]);
+        self.client.get_json(&segments, Option::<&()>::None)
+    }
+
+    fn post_silence(&self, silence: &Silence) -> Result<String> {
+        let segments = self.segments(&["silences"]);
+        let created: SilenceCreated = self.client.post_json(&segments, silence)?;
+        Ok(created.silence_id)
+    }
+
+    fn segments<'a>(&'a self, path: &[&'a str]) -> Vec<&'a str> {
+        let mut segments = vec!["alertmanager", self.alertmanager.as_str(), "api", "v2"];
+        segments.extend_from_slice(path);
+        segments
+    }
+}
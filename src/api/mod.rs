@@ -1,5 +1,11 @@
 //! Grafana API endpoint groups (service layer).
 
+#[cfg(feature = "async")]
+mod alert_state;
+#[cfg(feature = "async")]
+mod alerting;
+#[cfg(feature = "async")]
+mod alertmanager;
 #[cfg(feature = "async")]
 mod dashboards;
 #[cfg(feature = "async")]
@@ -19,84 +25,130 @@ mod search;
 #[cfg(feature = "async")]
 mod service_accounts;
 #[cfg(feature = "async")]
+mod streams;
+#[cfg(feature = "async")]
 mod teams;
 #[cfg(feature = "async")]
+mod traits;
+#[cfg(feature = "async")]
 mod user;
 
-#[cfg(feature = "blocking")]
+#[cfg(feature = "blocking-core")]
+mod alert_state_blocking;
+#[cfg(feature = "blocking-core")]
+mod alerting_blocking;
+#[cfg(feature = "blocking-core")]
+mod alertmanager_blocking;
+#[cfg(feature = "blocking-core")]
 mod dashboards_blocking;
-#[cfg(feature = "blocking")]
+#[cfg(feature = "blocking-core")]
 mod datasources_blocking;
-#[cfg(feature = "blocking")]
+#[cfg(feature = "blocking-core")]
 mod folders_blocking;
-#[cfg(feature = "blocking")]
+#[cfg(feature = "blocking-core")]
 mod health_blocking;
-#[cfg(feature = "blocking")]
+#[cfg(feature = "blocking-core")]
 mod openapi_blocking;
-#[cfg(feature = "blocking")]
+#[cfg(feature = "blocking-core")]
 mod org_blocking;
-#[cfg(feature = "blocking")]
+#[cfg(feature = "blocking-core")]
 mod raw_blocking;
-#[cfg(feature = "blocking")]
+#[cfg(feature = "blocking-core")]
 mod search_blocking;
-#[cfg(feature = "blocking")]
+#[cfg(feature = "blocking-core")]
 mod service_accounts_blocking;
-#[cfg(feature = "blocking")]
+#[cfg(feature = "blocking-core")]
+mod streams_blocking;
+#[cfg(feature = "blocking-core")]
 mod teams_blocking;
-#[cfg(feature = "blocking")]
+#[cfg(feature = "blocking-core")]
+mod traits_blocking;
+#[cfg(feature = "blocking-core")]
 mod user_blocking;
 
+#[cfg(feature = "async")]
+pub use alert_state::AlertStateService;
+#[cfg(feature = "blocking-core")]
+pub use alert_state_blocking::BlockingAlertStateService;
+
+#[cfg(feature = "async")]
+pub use alerting::AlertingService;
+#[cfg(feature = "blocking-core")]
+pub use alerting_blocking::BlockingAlertingService;
+
+#[cfg(feature = "async")]
+pub use alertmanager::AlertmanagerService;
+#[cfg(feature = "blocking-core")]
+pub use alertmanager_blocking::BlockingAlertmanagerService;
+
 #[cfg(feature = "async")]
 pub use dashboards::DashboardsService;
-#[cfg(feature = "blocking")]
+#[cfg(feature = "blocking-core")]
 pub use dashboards_blocking::BlockingDashboardsService;
 
 #[cfg(feature = "async")]
 pub use datasources::DatasourcesService;
-#[cfg(feature = "blocking")]
+#[cfg(feature = "blocking-core")]
 pub use datasources_blocking::BlockingDatasourcesService;
 
 #[cfg(feature = "async")]
 pub use folders::FoldersService;
-#[cfg(feature = "blocking")]
+#[cfg(feature = "blocking-core")]
 pub use folders_blocking::BlockingFoldersService;
 
 #[cfg(feature = "async")]
 pub use health::HealthService;
-#[cfg(feature = "blocking")]
+#[cfg(feature = "blocking-core")]
 pub use health_blocking::BlockingHealthService;
 
 #[cfg(feature = "async")]
 pub use openapi::OpenApi;
-#[cfg(feature = "blocking")]
+#[cfg(feature = "blocking-core")]
 pub use openapi_blocking::BlockingOpenApi;
 
 #[cfg(feature = "async")]
 pub use org::OrgService;
-#[cfg(feature = "blocking")]
+#[cfg(feature = "blocking-core")]
 pub use org_blocking::BlockingOrgService;
 
 #[cfg(feature = "async")]
 pub use raw::RawService;
-#[cfg(feature = "blocking")]
+#[cfg(feature = "blocking-core")]
 pub use raw_blocking::BlockingRawService;
 
 #[cfg(feature = "async")]
 pub use search::SearchService;
-#[cfg(feature = "blocking")]
+#[cfg(feature = "blocking-core")]
 pub use search_blocking::BlockingSearchService;
 
 #[cfg(feature = "async")]
 pub use service_accounts::ServiceAccountsService;
-#[cfg(feature = "blocking")]
+#[cfg(feature = "blocking-core")]
 pub use service_accounts_blocking::BlockingServiceAccountsService;
 
+#[cfg(feature = "async")]
+pub use streams::StreamsService;
+#[cfg(feature = "blocking-core")]
+pub use streams_blocking::BlockingStreamsService;
+
 #[cfg(feature = "async")]
 pub use teams::TeamsService;
-#[cfg(feature = "blocking")]
+#[cfg(feature = "blocking-core")]
 pub use teams_blocking::BlockingTeamsService;
 
 #[cfg(feature = "async")]
 pub use user::UserService;
-#[cfg(feature = "blocking")]
+#[cfg(feature = "blocking-core")]
 pub use user_blocking::BlockingUserService;
+
+#[cfg(feature = "async")]
+pub use traits::{
+    DashboardsApi, DatasourcesApi, FoldersApi, GrafanaApi, HealthApi, OrgApi, SearchApi,
+    ServiceAccountsApi, TeamsApi, UserApi,
+};
+#[cfg(feature = "blocking-core")]
+pub use traits_blocking::{
+    BlockingDashboardsApi, BlockingDatasourcesApi, BlockingFoldersApi, BlockingGrafanaApi,
+    BlockingHealthApi, BlockingOrgApi, BlockingSearchApi, BlockingServiceAccountsApi,
+    BlockingTeamsApi, BlockingUserApi,
+};
@@ -1,7 +1,7 @@
 use http::Method;
 use serde::{Serialize, de::DeserializeOwned};
 
-use crate::{Client, RequestOptions, ResponseBytes, Result};
+use crate::{Client, RequestOptions, ResponseBytes, ResponseStream, Result};
 
 #[derive(Clone)]
 pub struct RawService {
@@ -148,4 +148,39 @@ impl RawService {
             .request_bytes_text_with_options(method, segments, query, body, content_type, options)
             .await
     }
+
+    /// Sends the request and returns the successful response without buffering its body.
+    /// The per-attempt timeout still applies until the body is read to the end.
+    pub async fn request_stream<Query, Body>(
+        &self,
+        method: Method,
+        segments: &[&str],
+        query: Option<&Query>,
+        body: Option<&Body>,
+    ) -> Result<ResponseStream>
+    where
+        Query: Serialize + ?Sized,
+        Body: Serialize + ?Sized,
+    {
+        self.client
+            .request_stream(method, segments, query, body, None)
+            .await
+    }
+
+    pub async fn request_stream_with_options<Query, Body>(
+        &self,
+        method: Method,
+        segments: &[&str],
+        query: Option<&Query>,
+        body: Option<&Body>,
+        options: &RequestOptions,
+    ) -> Result<ResponseStream>
+    where
+        Query: Serialize + ?Sized,
+        Body: Serialize + ?Sized,
+    {
+        self.client
+            .request_stream(method, segments, query, body, Some(options))
+            .await
+    }
 }
@@ -1,7 +1,7 @@
 use http::Method;
 use serde::{Serialize, de::DeserializeOwned};
 
-use crate::{BlockingClient, RequestOptions, ResponseBytes, Result};
+use crate::{BlockingClient, RequestOptions, ResponseBytes, ResponseReader, Result};
 
 #[derive(Clone)]
 pub struct BlockingRawService {
@@ -150,4 +150,37 @@ impl BlockingRawService {
             options,
         )
     }
+
+    /// Sends the request and returns the successful response without buffering its body.
+    /// The per-attempt timeout still applies until the body is read to the end.
+    pub fn request_stream<Query, Body>(
+        &self,
+        method: Method,
+        segments: &[&str],
+        query: Option<&Query>,
+        body: Option<&Body>,
+    ) -> Result<ResponseReader>
+    where
+        Query: Serialize + ?Sized,
+        Body: Serialize + ?Sized,
+    {
+        self.client
+            .request_stream(method, segments, query, body, None)
+    }
+
+    pub fn request_stream_with_options<Query, Body>(
+        &self,
+        method: Method,
+        segments: &[&str],
+        query: Option<&Query>,
+        body: Option<&Body>,
+        options: &RequestOptions,
+    ) -> Result<ResponseReader>
+    where
+        Query: Serialize + ?Sized,
+        Body: Serialize + ?Sized,
+    {
+        self.client
+            .request_stream(method, segments, query, body, Some(options))
+    }
 }
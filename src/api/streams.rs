@@ -0,0 +1,148 @@
+use http::Method;
+
+use crate::types::openapi as oas;
+use crate::{Client, ResponseStream, Result};
+
+/// Endpoints whose response bodies are read incrementally instead of buffered: exports,
+/// rendered reports and search. Each method is named after the
+/// [`OpenApi`](crate::api::OpenApi) operation it streams.
+#[derive(Clone)]
+pub struct StreamsService {
+    client: Client,
+}
+
+impl StreamsService {
+    pub(crate) fn new(client: Client) -> Self {
+        Self { client }
+    }
+
+    /// `GET /v1/provisioning/mute-timings/export`
+    pub async fn route_export_mute_timings(
+        &self,
+        query: Option<&oas::RouteExportMuteTimingsQuery>,
+    ) -> Result<ResponseStream> {
+        let segments = ["v1", "provisioning", "mute-timings", "export"];
+        self.client
+            .request_stream::<oas::RouteExportMuteTimingsQuery, ()>(
+                Method::GET,
+                &segments,
+                query,
+                None,
+                None,
+            )
+            .await
+    }
+
+    /// `GET /v1/provisioning/folder/{FolderUID}/rule-groups/{Group}/export`
+    pub async fn route_get_alert_rule_group_export(
+        &self,
+        folder_uid: impl AsRef<str>,
+        group: impl AsRef<str>,
+        query: Option<&oas::RouteGetAlertRuleGroupExportQuery>,
+    ) -> Result<ResponseStream> {
+        let folder_uid = folder_uid.as_ref();
+        let group = group.as_ref();
+        let segments = [
+            "v1",
+            "provisioning",
+            "folder",
+            folder_uid,
+            "rule-groups",
+            group,
+            "export",
+        ];
+        self.client
+            .request_stream::<oas::RouteGetAlertRuleGroupExportQuery, ()>(
+                Method::GET,
+                &segments,
+                query,
+                None,
+                None,
+            )
+            .await
+    }
+
+    /// `GET /v1/provisioning/alert-rules/export`
+    pub async fn route_get_alert_rules_export(
+        &self,
+        query: Option<&oas::RouteGetAlertRulesExportQuery>,
+    ) -> Result<ResponseStream> {
+        let segments = ["v1", "provisioning", "alert-rules", "export"];
+        self.client
+            .request_stream::<oas::RouteGetAlertRulesExportQuery, ()>(
+                Method::GET,
+                &segments,
+                query,
+                None,
+                None,
+            )
+            .await
+    }
+
+    /// `GET /v1/provisioning/contact-points/export`
+    pub async fn route_get_contactpoints_export(
+        &self,
+        query: Option<&oas::RouteGetContactpointsExportQuery>,
+    ) -> Result<ResponseStream> {
+        let segments = ["v1", "provisioning", "contact-points", "export"];
+        self.client
+            .request_stream::<oas::RouteGetContactpointsExportQuery, ()>(
+                Method::GET,
+                &segments,
+                query,
+                None,
+                None,
+            )
+            .await
+    }
+
+    /// `GET /v1/provisioning/policies/export`
+    pub async fn route_get_policy_tree_export(&self) -> Result<ResponseStream> {
+        let segments = ["v1", "provisioning", "policies", "export"];
+        self.client
+            .request_stream::<(), ()>(Method::GET, &segments, None, None, None)
+            .await
+    }
+
+    /// `GET /reports/render/csvs`
+    pub async fn render_report_cs_vs(
+        &self,
+        query: Option<&oas::RenderReportCsVsQuery>,
+    ) -> Result<ResponseStream> {
+        let segments = ["reports", "render", "csvs"];
+        self.client
+            .request_stream::<oas::RenderReportCsVsQuery, ()>(
+                Method::GET,
+                &segments,
+                query,
+                None,
+                None,
+            )
+            .await
+    }
+
+    /// `GET /reports/render/pdfs`
+    pub async fn render_report_pd_fs(
+        &self,
+        query: Option<&oas::RenderReportPdFsQuery>,
+    ) -> Result<ResponseStream> {
+        let segments = ["reports", "render", "pdfs"];
+        self.client
+            .request_stream::<oas::RenderReportPdFsQuery, ()>(
+                Method::GET,
+                &segments,
+                query,
+                None,
+                None,
+            )
+            .await
+    }
+
+    /// `GET /search`
+    pub async fn search(&self, query: Option<&oas::SearchQuery>) -> Result<ResponseStream> {
+        let segments = ["search"];
+        self.client
+            .request_stream::<oas::SearchQuery, ()>(Method::GET, &segments, query, None, None)
+            .await
+    }
+}
@@ -0,0 +1,145 @@
+use http::Method;
+
+use crate::types::openapi as oas;
+use crate::{BlockingClient, ResponseReader, Result};
+
+/// Endpoints whose response bodies are read incrementally instead of buffered: exports,
+/// rendered reports and search. Each method is named after the
+/// [`BlockingOpenApi`](crate::api::BlockingOpenApi) operation it streams.
+#[derive(Clone)]
+pub struct BlockingStreamsService {
+    client: BlockingClient,
+}
+
+impl BlockingStreamsService {
+    pub(crate) fn new(client: BlockingClient) -> Self {
+        Self { client }
+    }
+
+    /// `GET /v1/provisioning/mute-timings/export`
+    pub fn route_export_mute_timings(
+        &self,
+        query: Option<&oas::RouteExportMuteTimingsQuery>,
+    ) -> Result<ResponseReader> {
+        let segments = ["v1", "provisioning", "mute-timings", "export"];
+        self.client
+            .request_stream::<oas::RouteExportMuteTimingsQuery, ()>(
+                Method::GET,
+                &segments,
+                query,
+                None,
+                None,
+            )
+    }
+
+    /// `GET /v1/provisioning/folder/{FolderUID}/rule-groups/{Group}/export`
+    pub fn route_get_alert_rule_group_export(
+        &self,
+        folder_uid: impl AsRef<str>,
+        group: impl AsRef<str>,
+        query: Option<&oas::RouteGetAlertRuleGroupExportQuery>,
+    ) -> Result<ResponseReader> {
+        let folder_uid = folder_uid.as_ref();
+        let group = group.as_ref();
+        let segments = [
+            "v1",
+            "provisioning",
+            "folder",
+            folder_uid,
+            "rule-groups",
+            group,
+            "export",
+        ];
+        self.client
+            .request_stream::<oas::RouteGetAlertRuleGroupExportQuery, ()>(
+                Method::GET,
+                &segments,
+                query,
+                None,
+                None,
+            )
+    }
+
+    /// `GET /v1/provisioning/alert-rules/export`
+    pub fn route_get_alert_rules_export(
+        &self,
+        query: Option<&oas::RouteGetAlertRulesExportQuery>,
+    ) -> Result<ResponseReader> {
+        let segments = ["v1", "provisioning", "alert-rules", "export"];
+        self.client
+            .request_stream::<oas::RouteGetAlertRulesExportQuery, ()>(
+                Method::GET,
+                &segments,
+                query,
+                None,
+                None,
+            )
+    }
+
+    /// `GET /v1/provisioning/contact-points/export`
+    pub fn route_get_contactpoints_export(
+        &self,
+        query: Option<&oas::RouteGetContactpointsExportQuery>,
+    ) -> Result<ResponseReader> {
+        let segments = ["v1", "provisioning", "contact-points", "export"];
+        self.client
+            .request_stream::<oas::RouteGetContactpointsExportQuery, ()>(
+                Method::GET,
+                &segments,
+                query,
+                None,
+                None,
+            )
+    }
+
+    /// `GET /v1/provisioning/policies/export`
+    pub fn route_get_policy_tree_export(&self) -> Result<ResponseReader> {
+        let segments = ["v1", "provisioning", "policies", "export"];
+        self.client
+            .request_stream::<(), ()>(Method::GET, &segments, None, None, None)
+    }
+
+    /// `GET /reports/render/csvs`
+    pub fn render_report_cs_vs(
+        &self,
+        query: Option<&oas::RenderReportCsVsQuery>,
+    ) -> Result<ResponseReader> {
+        let segments = ["reports", "render", "csvs"];
+        self.client
+            .request_stream::<oas::RenderReportCsVsQuery, ()>(
+                Method::GET,
+                &segments,
+                query,
+                None,
+                None,
+            )
+    }
+
+    /// `GET /reports/render/pdfs`
+    pub fn render_report_pd_fs(
+        &self,
+        query: Option<&oas::RenderReportPdFsQuery>,
+    ) -> Result<ResponseReader> {
+        let segments = ["reports", "render", "pdfs"];
+        self.client
+            .request_stream::<oas::RenderReportPdFsQuery, ()>(
+                Method::GET,
+                &segments,
+                query,
+                None,
+                None,
+            )
+    }
+
+    /// `GET /search`
+    pub fn search(&self, query: Option<&oas::SearchQuery>) -> Result<ResponseReader> {
+        let segments = ["search"];
+        self.client.request_stream::<oas::SearchQuery, ()>(
+            Method::GET,
+            &segments,
+            query,
+            None,
+            None,
+        )
+    }
+}
@@ -0,0 +1,590 @@
+//! Traits over the typed services, so code can depend on `impl DashboardsApi` (or on the whole
+//! [`GrafanaApi`]) and tests can substitute a fake such as `testing::MockApi`.
+
+use std::future::Future;
+
+use crate::{
+    Client, Result,
+    api::{
+        DashboardsService, DatasourcesService, FoldersService, HealthService, OrgService,
+        SearchService, ServiceAccountsService, TeamsService, UserService,
+    },
+    types::{
+        AddOrgUserRequest, CreateFolderRequest, CreateServiceAccountRequest,
+        CreateServiceAccountTokenRequest, CreateTeamRequest, CreateTeamResponse, DashboardUid,
+        Datasource, DatasourceId, DeleteDashboardResponse, DeleteDatasourceResponse,
+        DeleteFolderResponse, Folder, FolderUid, GetDashboardResponse, HealthResponse, NewApiKey,
+        OrgDetails, OrgId, OrgUser, SaveDashboardRequest, SaveDashboardResponse, SearchParams,
+        SearchResult, ServiceAccount, ServiceAccountId, ServiceAccountSearchParams,
+        ServiceAccountSearchResult, SuccessResponse, Team, TeamId, TeamMember, TeamSearchParams,
+        TeamSearchResult, Token, TokenId, UpdateFolderRequest, UpdateOrgRequest,
+        UpdateOrgUserRequest, UpdateServiceAccountRequest, UpdateServiceAccountResponse,
+        UpdateTeamMemberRequest, UpdateTeamRequest, UpdateUserRequest, UserId, UserOrg,
+        UserProfile,
+    },
+};
+
+/// Entry point over every typed service group; implemented by [`Client`].
+pub trait GrafanaApi: Send + Sync {
+    type Health: HealthApi;
+    type Dashboards: DashboardsApi;
+    type Folders: FoldersApi;
+    type Datasources: DatasourcesApi;
+    type Search: SearchApi;
+    type Teams: TeamsApi;
+    type ServiceAccounts: ServiceAccountsApi;
+    type Org: OrgApi;
+    type User: UserApi;
+
+    fn health(&self) -> Self::Health;
+    fn dashboards(&self) -> Self::Dashboards;
+    fn folders(&self) -> Self::Folders;
+    fn datasources(&self) -> Self::Datasources;
+    fn search(&self) -> Self::Search;
+    fn teams(&self) -> Self::Teams;
+    fn service_accounts(&self) -> Self::ServiceAccounts;
+    fn org(&self) -> Self::Org;
+    fn user(&self) -> Self::User;
+}
+
+impl GrafanaApi for Client {
+    type Health = HealthService;
+    type Dashboards = DashboardsService;
+    type Folders = FoldersService;
+    type Datasources = DatasourcesService;
+    type Search = SearchService;
+    type Teams = TeamsService;
+    type ServiceAccounts = ServiceAccountsService;
+    type Org = OrgService;
+    type User = UserService;
+
+    fn health(&self) -> Self::Health {
+        Client::health(self)
+    }
+
+    fn dashboards(&self) -> Self::Dashboards {
+        Client::dashboards(self)
+    }
+
+    fn folders(&self) -> Self::Folders {
+        Client::folders(self)
+    }
+
+    fn datasources(&self) -> Self::Datasources {
+        Client::datasources(self)
+    }
+
+    fn search(&self) -> Self::Search {
+        Client::search(self)
+    }
+
+    fn teams(&self) -> Self::Teams {
+        Client::teams(self)
+    }
+
+    fn service_accounts(&self) -> Self::ServiceAccounts {
+        Client::service_accounts(self)
+    }
+
+    fn org(&self) -> Self::Org {
+        Client::org(self)
+    }
+
+    fn user(&self) -> Self::User {
+        Client::user(self)
+    }
+}
+
+pub trait HealthApi: Send + Sync {
+    fn get(&self) -> impl Future<Output = Result<HealthResponse>> + Send;
+}
+
+impl HealthApi for HealthService {
+    fn get(&self) -> impl Future<Output = Result<HealthResponse>> + Send {
+        HealthService::get(self)
+    }
+}
+
+pub trait DashboardsApi: Send + Sync {
+    fn get_by_uid(
+        &self,
+        uid: impl Into<DashboardUid> + Send,
+    ) -> impl Future<Output = Result<GetDashboardResponse>> + Send;
+
+    fn save(
+        &self,
+        request: &SaveDashboardRequest,
+    ) -> impl Future<Output = Result<SaveDashboardResponse>> + Send;
+
+    fn delete_by_uid(
+        &self,
+        uid: impl Into<DashboardUid> + Send,
+    ) -> impl Future<Output = Result<DeleteDashboardResponse>> + Send;
+}
+
+impl DashboardsApi for DashboardsService {
+    fn get_by_uid(
+        &self,
+        uid: impl Into<DashboardUid> + Send,
+    ) -> impl Future<Output = Result<GetDashboardResponse>> + Send {
+        DashboardsService::get_by_uid(self, uid)
+    }
+
+    fn save(
+        &self,
+        request: &SaveDashboardRequest,
+    ) -> impl Future<Output = Result<SaveDashboardResponse>> + Send {
+        DashboardsService::save(self, request)
+    }
+
+    fn delete_by_uid(
+        &self,
+        uid: impl Into<DashboardUid> + Send,
+    ) -> impl Future<Output = Result<DeleteDashboardResponse>> + Send {
+        DashboardsService::delete_by_uid(self, uid)
+    }
+}
+
+pub trait FoldersApi: Send + Sync {
+    fn list(&self) -> impl Future<Output = Result<Vec<Folder>>> + Send;
+
+    fn get_by_uid(
+        &self,
+        uid: impl Into<FolderUid> + Send,
+    ) -> impl Future<Output = Result<Folder>> + Send;
+
+    fn create(&self, request: &CreateFolderRequest) -> impl Future<Output = Result<Folder>> + Send;
+
+    fn update(
+        &self,
+        uid: impl Into<FolderUid> + Send,
+        request: &UpdateFolderRequest,
+    ) -> impl Future<Output = Result<Folder>> + Send;
+
+    fn delete_by_uid(
+        &self,
+        uid: impl Into<FolderUid> + Send,
+    ) -> impl Future<Output = Result<DeleteFolderResponse>> + Send;
+}
+
+impl FoldersApi for FoldersService {
+    fn list(&self) -> impl Future<Output = Result<Vec<Folder>>> + Send {
+        FoldersService::list(self)
+    }
+
+    fn get_by_uid(
+        &self,
+        uid: impl Into<FolderUid> + Send,
+    ) -> impl Future<Output = Result<Folder>> + Send {
+        FoldersService::get_by_uid(self, uid)
+    }
+
+    fn create(&self, request: &CreateFolderRequest) -> impl Future<Output = Result<Folder>> + Send {
+        FoldersService::create(self, request)
+    }
+
+    fn update(
+        &self,
+        uid: impl Into<FolderUid> + Send,
+        request: &UpdateFolderRequest,
+    ) -> impl Future<Output = Result<Folder>> + Send {
+        FoldersService::update(self, uid, request)
+    }
+
+    fn delete_by_uid(
+        &self,
+        uid: impl Into<FolderUid> + Send,
+    ) -> impl Future<Output = Result<DeleteFolderResponse>> + Send {
+        FoldersService::delete_by_uid(self, uid)
+    }
+}
+
+pub trait DatasourcesApi: Send + Sync {
+    fn list(&self) -> impl Future<Output = Result<Vec<Datasource>>> + Send;
+
+    fn get_by_id(
+        &self,
+        id: impl Into<DatasourceId> + Send,
+    ) -> impl Future<Output = Result<Datasource>> + Send;
+
+    fn delete_by_id(
+        &self,
+        id: impl Into<DatasourceId> + Send,
+    ) -> impl Future<Output = Result<DeleteDatasourceResponse>> + Send;
+}
+
+impl DatasourcesApi for DatasourcesService {
+    fn list(&self) -> impl Future<Output = Result<Vec<Datasource>>> + Send {
+        DatasourcesService::list(self)
+    }
+
+    fn get_by_id(
+        &self,
+        id: impl Into<DatasourceId> + Send,
+    ) -> impl Future<Output = Result<Datasource>> + Send {
+        DatasourcesService::get_by_id(self, id)
+    }
+
+    fn delete_by_id(
+        &self,
+        id: impl Into<DatasourceId> + Send,
+    ) -> impl Future<Output = Result<DeleteDatasourceResponse>> + Send {
+        DatasourcesService::delete_by_id(self, id)
+    }
+}
+
+pub trait SearchApi: Send + Sync {
+    fn search(
+        &self,
+        params: &SearchParams,
+    ) -> impl Future<Output = Result<Vec<SearchResult>>> + Send;
+}
+
+impl SearchApi for SearchService {
+    fn search(
+        &self,
+        params: &SearchParams,
+    ) -> impl Future<Output = Result<Vec<SearchResult>>> + Send {
+        SearchService::search(self, params)
+    }
+}
+
+pub trait TeamsApi: Send + Sync {
+    fn search(
+        &self,
+        params: &TeamSearchParams,
+    ) -> impl Future<Output = Result<TeamSearchResult>> + Send;
+
+    fn create(
+        &self,
+        request: &CreateTeamRequest,
+    ) -> impl Future<Output = Result<CreateTeamResponse>> + Send;
+
+    fn get_by_id(
+        &self,
+        team_id: impl Into<TeamId> + Send,
+    ) -> impl Future<Output = Result<Team>> + Send;
+
+    fn update(
+        &self,
+        team_id: impl Into<TeamId> + Send,
+        request: &UpdateTeamRequest,
+    ) -> impl Future<Output = Result<SuccessResponse>> + Send;
+
+    fn delete(
+        &self,
+        team_id: impl Into<TeamId> + Send,
+    ) -> impl Future<Output = Result<SuccessResponse>> + Send;
+
+    fn members(
+        &self,
+        team_id: impl Into<TeamId> + Send,
+    ) -> impl Future<Output = Result<Vec<TeamMember>>> + Send;
+
+    fn add_member(
+        &self,
+        team_id: impl Into<TeamId> + Send,
+        user_id: impl Into<UserId> + Send,
+    ) -> impl Future<Output = Result<SuccessResponse>> + Send;
+
+    fn update_member_permission(
+        &self,
+        team_id: impl Into<TeamId> + Send,
+        user_id: impl Into<UserId> + Send,
+        request: &UpdateTeamMemberRequest,
+    ) -> impl Future<Output = Result<SuccessResponse>> + Send;
+
+    fn remove_member(
+        &self,
+        team_id: impl Into<TeamId> + Send,
+        user_id: impl Into<UserId> + Send,
+    ) -> impl Future<Output = Result<SuccessResponse>> + Send;
+}
+
+impl TeamsApi for TeamsService {
+    fn search(
+        &self,
+        params: &TeamSearchParams,
+    ) -> impl Future<Output = Result<TeamSearchResult>> + Send {
+        TeamsService::search(self, params)
+    }
+
+    fn create(
+        &self,
+        request: &CreateTeamRequest,
+    ) -> impl Future<Output = Result<CreateTeamResponse>> + Send {
+        TeamsService::create(self, request)
+    }
+
+    fn get_by_id(
+        &self,
+        team_id: impl Into<TeamId> + Send,
+    ) -> impl Future<Output = Result<Team>> + Send {
+        TeamsService::get_by_id(self, team_id)
+    }
+
+    fn update(
+        &self,
+        team_id: impl Into<TeamId> + Send,
+        request: &UpdateTeamRequest,
+    ) -> impl Future<Output = Result<SuccessResponse>> + Send {
+        TeamsService::update(self, team_id, request)
+    }
+
+    fn delete(
+        &self,
+        team_id: impl Into<TeamId> + Send,
+    ) -> impl Future<Output = Result<SuccessResponse>> + Send {
+        TeamsService::delete(self, team_id)
+    }
+
+    fn members(
+        &self,
+        team_id: impl Into<TeamId> + Send,
+    ) -> impl Future<Output = Result<Vec<TeamMember>>> + Send {
+        TeamsService::members(self, team_id)
+    }
+
+    fn add_member(
+        &self,
+        team_id: impl Into<TeamId> + Send,
+        user_id: impl Into<UserId> + Send,
+    ) -> impl Future<Output = Result<SuccessResponse>> + Send {
+        TeamsService::add_member(self, team_id, user_id)
+    }
+
+    fn update_member_permission(
+        &self,
+        team_id: impl Into<TeamId> + Send,
+        user_id: impl Into<UserId> + Send,
+        request: &UpdateTeamMemberRequest,
+    ) -> impl Future<Output = Result<SuccessResponse>> + Send {
+        TeamsService::update_member_permission(self, team_id, user_id, request)
+    }
+
+    fn remove_member(
+        &self,
+        team_id: impl Into<TeamId> + Send,
+        user_id: impl Into<UserId> + Send,
+    ) -> impl Future<Output = Result<SuccessResponse>> + Send {
+        TeamsService::remove_member(self, team_id, user_id)
+    }
+}
+
+pub trait ServiceAccountsApi: Send + Sync {
+    fn search(
+        &self,
+        params: &ServiceAccountSearchParams,
+    ) -> impl Future<Output = Result<ServiceAccountSearchResult>> + Send;
+
+    fn create(
+        &self,
+        request: &CreateServiceAccountRequest,
+    ) -> impl Future<Output = Result<ServiceAccount>> + Send;
+
+    fn get_by_id(
+        &self,
+        service_account_id: impl Into<ServiceAccountId> + Send,
+    ) -> impl Future<Output = Result<ServiceAccount>> + Send;
+
+    fn update(
+        &self,
+        service_account_id: impl Into<ServiceAccountId> + Send,
+        request: &UpdateServiceAccountRequest,
+    ) -> impl Future<Output = Result<UpdateServiceAccountResponse>> + Send;
+
+    fn delete(
+        &self,
+        service_account_id: impl Into<ServiceAccountId> + Send,
+    ) -> impl Future<Output = Result<SuccessResponse>> + Send;
+
+    fn tokens(
+        &self,
+        service_account_id: impl Into<ServiceAccountId> + Send,
+    ) -> impl Future<Output = Result<Vec<Token>>> + Send;
+
+    fn create_token(
+        &self,
+        service_account_id: impl Into<ServiceAccountId> + Send,
+        request: &CreateServiceAccountTokenRequest,
+    ) -> impl Future<Output = Result<NewApiKey>> + Send;
+
+    fn delete_token(
+        &self,
+        service_account_id: impl Into<ServiceAccountId> + Send,
+        token_id: impl Into<TokenId> + Send,
+    ) -> impl Future<Output = Result<SuccessResponse>> + Send;
+}
+
+impl ServiceAccountsApi for ServiceAccountsService {
+    fn search(
+        &self,
+        params: &ServiceAccountSearchParams,
+    ) -> impl Future<Output = Result<ServiceAccountSearchResult>> + Send {
+        ServiceAccountsService::search(self, params)
+    }
+
+    fn create(
+        &self,
+        request: &CreateServiceAccountRequest,
+    ) -> impl Future<Output = Result<ServiceAccount>> + Send {
+        ServiceAccountsService::create(self, request)
+    }
+
+    fn get_by_id(
+        &self,
+        service_account_id: impl Into<ServiceAccountId> + Send,
+    ) -> impl Future<Output = Result<ServiceAccount>> + Send {
+        ServiceAccountsService::get_by_id(self, service_account_id)
+    }
+
+    fn update(
+        &self,
+        service_account_id: impl Into<ServiceAccountId> + Send,
+        request: &UpdateServiceAccountRequest,
+    ) -> impl Future<Output = Result<UpdateServiceAccountResponse>> + Send {
+        ServiceAccountsService::update(self, service_account_id, request)
+    }
+
+    fn delete(
+        &self,
+        service_account_id: impl Into<ServiceAccountId> + Send,
+    ) -> impl Future<Output = Result<SuccessResponse>> + Send {
+        ServiceAccountsService::delete(self, service_account_id)
+    }
+
+    fn tokens(
+        &self,
+        service_account_id: impl Into<ServiceAccountId> + Send,
+    ) -> impl Future<Output = Result<Vec<Token>>> + Send {
+        ServiceAccountsService::tokens(self, service_account_id)
+    }
+
+    fn create_token(
+        &self,
+        service_account_id: impl Into<ServiceAccountId> + Send,
+        request: &CreateServiceAccountTokenRequest,
+    ) -> impl Future<Output = Result<NewApiKey>> + Send {
+        ServiceAccountsService::create_token(self, service_account_id, request)
+    }
+
+    fn delete_token(
+        &self,
+        service_account_id: impl Into<ServiceAccountId> + Send,
+        token_id: impl Into<TokenId> + Send,
+    ) -> impl Future<Output = Result<SuccessResponse>> + Send {
+        ServiceAccountsService::delete_token(self, service_account_id, token_id)
+    }
+}
+
+pub trait OrgApi: Send + Sync {
+    fn get(&self) -> impl Future<Output = Result<OrgDetails>> + Send;
+
+    fn update(
+        &self,
+        request: &UpdateOrgRequest,
+    ) -> impl Future<Output = Result<SuccessResponse>> + Send;
+
+    fn users(&self) -> impl Future<Output = Result<Vec<OrgUser>>> + Send;
+
+    fn add_user(
+        &self,
+        request: &AddOrgUserRequest,
+    ) -> impl Future<Output = Result<SuccessResponse>> + Send;
+
+    fn update_user_role(
+        &self,
+        user_id: impl Into<UserId> + Send,
+        request: &UpdateOrgUserRequest,
+    ) -> impl Future<Output = Result<SuccessResponse>> + Send;
+
+    fn remove_user(
+        &self,
+        user_id: impl Into<UserId> + Send,
+    ) -> impl Future<Output = Result<SuccessResponse>> + Send;
+}
+
+impl OrgApi for OrgService {
+    fn get(&self) -> impl Future<Output = Result<OrgDetails>> + Send {
+        OrgService::get(self)
+    }
+
+    fn update(
+        &self,
+        request: &UpdateOrgRequest,
+    ) -> impl Future<Output = Result<SuccessResponse>> + Send {
+        OrgService::update(self, request)
+    }
+
+    fn users(&self) -> impl Future<Output = Result<Vec<OrgUser>>> + Send {
+        OrgService::users(self)
+    }
+
+    fn add_user(
+        &self,
+        request: &AddOrgUserRequest,
+    ) -> impl Future<Output = Result<SuccessResponse>> + Send {
+        OrgService::add_user(self, request)
+    }
+
+    fn update_user_role(
+        &self,
+        user_id: impl Into<UserId> + Send,
+        request: &UpdateOrgUserRequest,
+    ) -> impl Future<Output = Result<SuccessResponse>> + Send {
+        OrgService::update_user_role(self, user_id, request)
+    }
+
+    fn remove_user(
+        &self,
+        user_id: impl Into<UserId> + Send,
+    ) -> impl Future<Output = Result<SuccessResponse>> + Send {
+        OrgService::remove_user(self, user_id)
+    }
+}
+
+pub trait UserApi: Send + Sync {
+    fn get_profile(&self) -> impl Future<Output = Result<UserProfile>> + Send;
+
+    fn update_profile(
+        &self,
+        request: &UpdateUserRequest,
+    ) -> impl Future<Output = Result<SuccessResponse>> + Send;
+
+    fn orgs(&self) -> impl Future<Output = Result<Vec<UserOrg>>> + Send;
+
+    fn teams(&self) -> impl Future<Output = Result<Vec<Team>>> + Send;
+
+    fn switch_org(
+        &self,
+        org_id: impl Into<OrgId> + Send,
+    ) -> impl Future<Output = Result<SuccessResponse>> + Send;
+}
+
+impl UserApi for UserService {
+    fn get_profile(&self) -> impl Future<Output = Result<UserProfile>> + Send {
+        UserService::get_profile(self)
+    }
+
+    fn update_profile(
+        &self,
+        request: &UpdateUserRequest,
+    ) -> impl Future<Output = Result<SuccessResponse>> + Send {
+        UserService::update_profile(self, request)
+    }
+
+    fn orgs(&self) -> impl Future<Output = Result<Vec<UserOrg>>> + Send {
+        UserService::orgs(self)
+    }
+
+    fn teams(&self) -> impl Future<Output = Result<Vec<Team>>> + Send {
+        UserService::teams(self)
+    }
+
+    fn switch_org(
+        &self,
+        org_id: impl Into<OrgId> + Send,
+    ) -> impl Future<Output = Result<SuccessResponse>> + Send {
+        UserService::switch_org(self, org_id)
+    }
+}
@@ -0,0 +1,440 @@
+use crate::{
+    BlockingClient, Result,
+    api::{
+        BlockingDashboardsService, BlockingDatasourcesService, BlockingFoldersService,
+        BlockingHealthService, BlockingOrgService, BlockingSearchService,
+        BlockingServiceAccountsService, BlockingTeamsService, BlockingUserService,
+    },
+    types::{
+        AddOrgUserRequest, CreateFolderRequest, CreateServiceAccountRequest,
+        CreateServiceAccountTokenRequest, CreateTeamRequest, CreateTeamResponse, DashboardUid,
+        Datasource, DatasourceId, DeleteDashboardResponse, DeleteDatasourceResponse,
+        DeleteFolderResponse, Folder, FolderUid, GetDashboardResponse, HealthResponse, NewApiKey,
+        OrgDetails, OrgId, OrgUser, SaveDashboardRequest, SaveDashboardResponse, SearchParams,
+        SearchResult, ServiceAccount, ServiceAccountId, ServiceAccountSearchParams,
+        ServiceAccountSearchResult, SuccessResponse, Team, TeamId, TeamMember, TeamSearchParams,
+        TeamSearchResult, Token, TokenId, UpdateFolderRequest, UpdateOrgRequest,
+        UpdateOrgUserRequest, UpdateServiceAccountRequest, UpdateServiceAccountResponse,
+        UpdateTeamMemberRequest, UpdateTeamRequest, UpdateUserRequest, UserId, UserOrg,
+        UserProfile,
+    },
+};
+
+/// Entry point over every typed service group; implemented by [`BlockingClient`].
+pub trait BlockingGrafanaApi: Send + Sync {
+    type Health: BlockingHealthApi;
+    type Dashboards: BlockingDashboardsApi;
+    type Folders: BlockingFoldersApi;
+    type Datasources: BlockingDatasourcesApi;
+    type Search: BlockingSearchApi;
+    type Teams: BlockingTeamsApi;
+    type ServiceAccounts: BlockingServiceAccountsApi;
+    type Org: BlockingOrgApi;
+    type User: BlockingUserApi;
+
+    fn health(&self) -> Self::Health;
+    fn dashboards(&self) -> Self::Dashboards;
+    fn folders(&self) -> Self::Folders;
+    fn datasources(&self) -> Self::Datasources;
+    fn search(&self) -> Self::Search;
+    fn teams(&self) -> Self::Teams;
+    fn service_accounts(&self) -> Self::ServiceAccounts;
+    fn org(&self) -> Self::Org;
+    fn user(&self) -> Self::User;
+}
+
+impl BlockingGrafanaApi for BlockingClient {
+    type Health = BlockingHealthService;
+    type Dashboards = BlockingDashboardsService;
+    type Folders = BlockingFoldersService;
+    type Datasources = BlockingDatasourcesService;
+    type Search = BlockingSearchService;
+    type Teams = BlockingTeamsService;
+    type ServiceAccounts = BlockingServiceAccountsService;
+    type Org = BlockingOrgService;
+    type User = BlockingUserService;
+
+    fn health(&self) -> Self::Health {
+        BlockingClient::health(self)
+    }
+
+    fn dashboards(&self) -> Self::Dashboards {
+        BlockingClient::dashboards(self)
+    }
+
+    fn folders(&self) -> Self::Folders {
+        BlockingClient::folders(self)
+    }
+
+    fn datasources(&self) -> Self::Datasources {
+        BlockingClient::datasources(self)
+    }
+
+    fn search(&self) -> Self::Search {
+        BlockingClient::search(self)
+    }
+
+    fn teams(&self) -> Self::Teams {
+        BlockingClient::teams(self)
+    }
+
+    fn service_accounts(&self) -> Self::ServiceAccounts {
+        BlockingClient::service_accounts(self)
+    }
+
+    fn org(&self) -> Self::Org {
+        BlockingClient::org(self)
+    }
+
+    fn user(&self) -> Self::User {
+        BlockingClient::user(self)
+    }
+}
+
+pub trait BlockingHealthApi: Send + Sync {
+    fn get(&self) -> Result<HealthResponse>;
+}
+
+impl BlockingHealthApi for BlockingHealthService {
+    fn get(&self) -> Result<HealthResponse> {
+        BlockingHealthService::get(self)
+    }
+}
+
+pub trait BlockingDashboardsApi: Send + Sync {
+    fn get_by_uid(&self, uid: impl Into<DashboardUid>) -> Result<GetDashboardResponse>;
+
+    fn save(&self, request: &SaveDashboardRequest) -> Result<SaveDashboardResponse>;
+
+    fn delete_by_uid(&self, uid: impl Into<DashboardUid>) -> Result<DeleteDashboardResponse>;
+}
+
+impl BlockingDashboardsApi for BlockingDashboardsService {
+    fn get_by_uid(&self, uid: impl Into<DashboardUid>) -> Result<GetDashboardResponse> {
+        BlockingDashboardsService::get_by_uid(self, uid)
+    }
+
+    fn save(&self, request: &SaveDashboardRequest) -> Result<SaveDashboardResponse> {
+        BlockingDashboardsService::save(self, request)
+    }
+
+    fn delete_by_uid(&self, uid: impl Into<DashboardUid>) -> Result<DeleteDashboardResponse> {
+        BlockingDashboardsService::delete_by_uid(self, uid)
+    }
+}
+
+pub trait BlockingFoldersApi: Send + Sync {
+    fn list(&self) -> Result<Vec<Folder>>;
+
+    fn get_by_uid(&self, uid: impl Into<FolderUid>) -> Result<Folder>;
+
+    fn create(&self, request: &CreateFolderRequest) -> Result<Folder>;
+
+    fn update(&self, uid: impl Into<FolderUid>, request: &UpdateFolderRequest) -> Result<Folder>;
+
+    fn delete_by_uid(&self, uid: impl Into<FolderUid>) -> Result<DeleteFolderResponse>;
+}
+
+impl BlockingFoldersApi for BlockingFoldersService {
+    fn list(&self) -> Result<Vec<Folder>> {
+        BlockingFoldersService::list(self)
+    }
+
+    fn get_by_uid(&self, uid: impl Into<FolderUid>) -> Result<Folder> {
+        BlockingFoldersService::get_by_uid(self, uid)
+    }
+
+    fn create(&self, request: &CreateFolderRequest) -> Result<Folder> {
+        BlockingFoldersService::create(self, request)
+    }
+
+    fn update(&self, uid: impl Into<FolderUid>, request: &UpdateFolderRequest) -> Result<Folder> {
+        BlockingFoldersService::update(self, uid, request)
+    }
+
+    fn delete_by_uid(&self, uid: impl Into<FolderUid>) -> Result<DeleteFolderResponse> {
+        BlockingFoldersService::delete_by_uid(self, uid)
+    }
+}
+
+pub trait BlockingDatasourcesApi: Send + Sync {
+    fn list(&self) -> Result<Vec<Datasource>>;
+
+    fn get_by_id(&self, id: impl Into<DatasourceId>) -> Result<Datasource>;
+
+    fn delete_by_id(&self, id: impl Into<DatasourceId>) -> Result<DeleteDatasourceResponse>;
+}
+
+impl BlockingDatasourcesApi for BlockingDatasourcesService {
+    fn list(&self) -> Result<Vec<Datasource>> {
+        BlockingDatasourcesService::list(self)
+    }
+
+    fn get_by_id(&self, id: impl Into<DatasourceId>) -> Result<Datasource> {
+        BlockingDatasourcesService::get_by_id(self, id)
+    }
+
+    fn delete_by_id(&self, id: impl Into<DatasourceId>) -> Result<DeleteDatasourceResponse> {
+        BlockingDatasourcesService::delete_by_id(self, id)
+    }
+}
+
+pub trait BlockingSearchApi: Send + Sync {
+    fn search(&self, params: &SearchParams) -> Result<Vec<SearchResult>>;
+}
+
+impl BlockingSearchApi for BlockingSearchService {
+    fn search(&self, params: &SearchParams) -> Result<Vec<SearchResult>> {
+        BlockingSearchService::search(self, params)
+    }
+}
+
+pub trait BlockingTeamsApi: Send + Sync {
+    fn search(&self, params: &TeamSearchParams) -> Result<TeamSearchResult>;
+
+    fn create(&self, request: &CreateTeamRequest) -> Result<CreateTeamResponse>;
+
+    fn get_by_id(&self, team_id: impl Into<TeamId>) -> Result<Team>;
+
+    fn update(
+        &self,
+        team_id: impl Into<TeamId>,
+        request: &UpdateTeamRequest,
+    ) -> Result<SuccessResponse>;
+
+    fn delete(&self, team_id: impl Into<TeamId>) -> Result<SuccessResponse>;
+
+    fn members(&self, team_id: impl Into<TeamId>) -> Result<Vec<TeamMember>>;
+
+    fn add_member(
+        &self,
+        team_id: impl Into<TeamId>,
+        user_id: impl Into<UserId>,
+    ) -> Result<SuccessResponse>;
+
+    fn update_member_permission(
+        &self,
+        team_id: impl Into<TeamId>,
+        user_id: impl Into<UserId>,
+        request: &UpdateTeamMemberRequest,
+    ) -> Result<SuccessResponse>;
+
+    fn remove_member(
+        &self,
+        team_id: impl Into<TeamId>,
+        user_id: impl Into<UserId>,
+    ) -> Result<SuccessResponse>;
+}
+
+impl BlockingTeamsApi for BlockingTeamsService {
+    fn search(&self, params: &TeamSearchParams) -> Result<TeamSearchResult> {
+        BlockingTeamsService::search(self, params)
+    }
+
+    fn create(&self, request: &CreateTeamRequest) -> Result<CreateTeamResponse> {
+        BlockingTeamsService::create(self, request)
+    }
+
+    fn get_by_id(&self, team_id: impl Into<TeamId>) -> Result<Team> {
+        BlockingTeamsService::get_by_id(self, team_id)
+    }
+
+    fn update(
+        &self,
+        team_id: impl Into<TeamId>,
+        request: &UpdateTeamRequest,
+    ) -> Result<SuccessResponse> {
+        BlockingTeamsService::update(self, team_id, request)
+    }
+
+    fn delete(&self, team_id: impl Into<TeamId>) -> Result<SuccessResponse> {
+        BlockingTeamsService::delete(self, team_id)
+    }
+
+    fn members(&self, team_id: impl Into<TeamId>) -> Result<Vec<TeamMember>> {
+        BlockingTeamsService::members(self, team_id)
+    }
+
+    fn add_member(
+        &self,
+        team_id: impl Into<TeamId>,
+        user_id: impl Into<UserId>,
+    ) -> Result<SuccessResponse> {
+        BlockingTeamsService::add_member(self, team_id, user_id)
+    }
+
+    fn update_member_permission(
+        &self,
+        team_id: impl Into<TeamId>,
+        user_id: impl Into<UserId>,
+        request: &UpdateTeamMemberRequest,
+    ) -> Result<SuccessResponse> {
+        BlockingTeamsService::update_member_permission(self, team_id, user_id, request)
+    }
+
+    fn remove_member(
+        &self,
+        team_id: impl Into<TeamId>,
+        user_id: impl Into<UserId>,
+    ) -> Result<SuccessResponse> {
+        BlockingTeamsService::remove_member(self, team_id, user_id)
+    }
+}
+
+pub trait BlockingServiceAccountsApi: Send + Sync {
+    fn search(&self, params: &ServiceAccountSearchParams) -> Result<ServiceAccountSearchResult>;
+
+    fn create(&self, request: &CreateServiceAccountRequest) -> Result<ServiceAccount>;
+
+    fn get_by_id(&self, service_account_id: impl Into<ServiceAccountId>) -> Result<ServiceAccount>;
+
+    fn update(
+        &self,
+        service_account_id: impl Into<ServiceAccountId>,
+        request: &UpdateServiceAccountRequest,
+    ) -> Result<UpdateServiceAccountResponse>;
+
+    fn delete(&self, service_account_id: impl Into<ServiceAccountId>) -> Result<SuccessResponse>;
+
+    fn tokens(&self, service_account_id: impl Into<ServiceAccountId>) -> Result<Vec<Token>>;
+
+    fn create_token(
+        &self,
+        service_account_id: impl Into<ServiceAccountId>,
+        request: &CreateServiceAccountTokenRequest,
+    ) -> Result<NewApiKey>;
+
+    fn delete_token(
+        &self,
+        service_account_id: impl Into<ServiceAccountId>,
+        token_id: impl Into<TokenId>,
+    ) -> Result<SuccessResponse>;
+}
+
+impl BlockingServiceAccountsApi for BlockingServiceAccountsService {
+    fn search(&self, params: &ServiceAccountSearchParams) -> Result<ServiceAccountSearchResult> {
+        BlockingServiceAccountsService::search(self, params)
+    }
+
+    fn create(&self, request: &CreateServiceAccountRequest) -> Result<ServiceAccount> {
+        BlockingServiceAccountsService::create(self, request)
+    }
+
+    fn get_by_id(&self, service_account_id: impl Into<ServiceAccountId>) -> Result<ServiceAccount> {
+        BlockingServiceAccountsService::get_by_id(self, service_account_id)
+    }
+
+    fn update(
+        &self,
+        service_account_id: impl Into<ServiceAccountId>,
+        request: &UpdateServiceAccountRequest,
+    ) -> Result<UpdateServiceAccountResponse> {
+        BlockingServiceAccountsService::update(self, service_account_id, request)
+    }
+
+    fn delete(&self, service_account_id: impl Into<ServiceAccountId>) -> Result<SuccessResponse> {
+        BlockingServiceAccountsService::delete(self, service_account_id)
+    }
+
+    fn tokens(&self, service_account_id: impl Into<ServiceAccountId>) -> Result<Vec<Token>> {
+        BlockingServiceAccountsService::tokens(self, service_account_id)
+    }
+
+    fn create_token(
+        &self,
+        service_account_id: impl Into<ServiceAccountId>,
+        request: &CreateServiceAccountTokenRequest,
+    ) -> Result<NewApiKey> {
+        BlockingServiceAccountsService::create_token(self, service_account_id, request)
+    }
+
+    fn delete_token(
+        &self,
+        service_account_id: impl Into<ServiceAccountId>,
+        token_id: impl Into<TokenId>,
+    ) -> Result<SuccessResponse> {
+        BlockingServiceAccountsService::delete_token(self, service_account_id, token_id)
+    }
+}
+
+pub trait BlockingOrgApi: Send + Sync {
+    fn get(&self) -> Result<OrgDetails>;
+
+    fn update(&self, request: &UpdateOrgRequest) -> Result<SuccessResponse>;
+
+    fn users(&self) -> Result<Vec<OrgUser>>;
+
+    fn add_user(&self, request: &AddOrgUserRequest) -> Result<SuccessResponse>;
+
+    fn update_user_role(
+        &self,
+        user_id: impl Into<UserId>,
+        request: &UpdateOrgUserRequest,
+    ) -> Result<SuccessResponse>;
+
+    fn remove_user(&self, user_id: impl Into<UserId>) -> Result<SuccessResponse>;
+}
+
+impl BlockingOrgApi for BlockingOrgService {
+    fn get(&self) -> Result<OrgDetails> {
+        BlockingOrgService::get(self)
+    }
+
+    fn update(&self, request: &UpdateOrgRequest) -> Result<SuccessResponse> {
+        BlockingOrgService::update(self, request)
+    }
+
+    fn users(&self) -> Result<Vec<OrgUser>> {
+        BlockingOrgService::users(self)
+    }
+
+    fn add_user(&self, request: &AddOrgUserRequest) -> Result<SuccessResponse> {
+        BlockingOrgService::add_user(self, request)
+    }
+
+    fn update_user_role(
+        &self,
+        user_id: impl Into<UserId>,
+        request: &UpdateOrgUserRequest,
+    ) -> Result<SuccessResponse> {
+        BlockingOrgService::update_user_role(self, user_id, request)
+    }
+
+    fn remove_user(&self, user_id: impl Into<UserId>) -> Result<SuccessResponse> {
+        BlockingOrgService::remove_user(self, user_id)
+    }
+}
+
+pub trait BlockingUserApi: Send + Sync {
+    fn get_profile(&self) -> Result<UserProfile>;
+
+    fn update_profile(&self, request: &UpdateUserRequest) -> Result<SuccessResponse>;
+
+    fn orgs(&self) -> Result<Vec<UserOrg>>;
+
+    fn teams(&self) -> Result<Vec<Team>>;
+
+    fn switch_org(&self, org_id: impl Into<OrgId>) -> Result<SuccessResponse>;
+}
+
+impl BlockingUserApi for BlockingUserService {
+    fn get_profile(&self) -> Result<UserProfile> {
+        BlockingUserService::get_profile(self)
+    }
+
+    fn update_profile(&self, request: &UpdateUserRequest) -> Result<SuccessResponse> {
+        BlockingUserService::update_profile(self, request)
+    }
+
+    fn orgs(&self) -> Result<Vec<UserOrg>> {
+        BlockingUserService::orgs(self)
+    }
+
+    fn teams(&self) -> Result<Vec<Team>> {
+        BlockingUserService::teams(self)
+    }
+
+    fn switch_org(&self, org_id: impl Into<OrgId>) -> Result<SuccessResponse> {
+        BlockingUserService::switch_org(self, org_id)
+    }
+}
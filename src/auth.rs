@@ -1,15 +1,44 @@
-use std::fmt;
+use std::{fmt, sync::Arc};
 
 use base64::Engine;
-use http::{HeaderMap, HeaderValue};
+use http::{HeaderMap, HeaderName, HeaderValue};
 
-use crate::{Error, Result};
+#[cfg(feature = "async")]
+use crate::credentials::AsyncCredentialProvider;
+use crate::{
+    Error, Result,
+    credentials::CredentialProvider,
+    oauth2::ClientCredentials,
+    util::flight::{Cached, Flight},
+};
 
+/// How requests authenticate. Non-exhaustive: which variants exist depends on the enabled
+/// features.
 #[derive(Clone)]
+#[non_exhaustive]
 pub enum Auth {
     None,
     Bearer(String),
-    Basic { username: String, password: String },
+    Basic {
+        username: String,
+        password: String,
+    },
+    /// Identity asserted by a trusted proxy (Grafana `[auth.proxy]`).
+    AuthProxy(AuthProxy),
+    /// A JWT in a custom header (Grafana `[auth.jwt] header_name`).
+    Jwt {
+        header: String,
+        token: String,
+    },
+    /// Cookie session from `POST /login`; logs in again when a request gets a `401`.
+    Session(Session),
+    /// Bearer token from an OAuth2 token endpoint (client-credentials grant).
+    OAuth2(ClientCredentials),
+    /// Asks a [`CredentialProvider`] before every attempt.
+    Provider(Arc<dyn CredentialProvider>),
+    /// Asks an [`AsyncCredentialProvider`] before every attempt (async client only).
+    #[cfg(feature = "async")]
+    AsyncProvider(Arc<dyn AsyncCredentialProvider>),
 }
 
 impl Auth {
@@ -27,6 +56,48 @@ impl Auth {
             password: password.into(),
         }
     }
+
+    /// Auth proxy with the default `X-WEBAUTH-USER` header; see [`AuthProxy`] for more headers.
+    pub fn auth_proxy(user: impl Into<String>) -> Self {
+        Self::AuthProxy(AuthProxy::new(user))
+    }
+
+    /// JWT in `X-JWT-Assertion`, Grafana's default header.
+    pub fn jwt(token: impl Into<String>) -> Self {
+        Self::jwt_in_header("X-JWT-Assertion", token)
+    }
+
+    pub fn jwt_in_header(header: impl Into<String>, token: impl Into<String>) -> Self {
+        Self::Jwt {
+            header: header.into(),
+            token: token.into(),
+        }
+    }
+
+    /// Logs in with `POST /login` on first use and sends the `grafana_session` cookie after,
+    /// following Grafana when it rotates the cookie. Concurrent requests share one login.
+    pub fn session(username: impl Into<String>, password: impl Into<String>) -> Self {
+        Self::Session(Session {
+            inner: Arc::new(SessionInner {
+                username: username.into(),
+                password: password.into(),
+                cookie: Flight::default(),
+            }),
+        })
+    }
+
+    pub fn oauth2(credentials: ClientCredentials) -> Self {
+        Self::OAuth2(credentials)
+    }
+
+    pub fn provider(provider: impl CredentialProvider) -> Self {
+        Self::Provider(Arc::new(provider))
+    }
+
+    #[cfg(feature = "async")]
+    pub fn async_provider(provider: impl AsyncCredentialProvider) -> Self {
+        Self::AsyncProvider(Arc::new(provider))
+    }
 }
 
 impl fmt::Debug for Auth {
@@ -39,19 +110,102 @@ impl fmt::Debug for Auth {
                 .field("username", username)
                 .field("password", &"<redacted>")
                 .finish(),
+            Self::AuthProxy(proxy) => f.debug_tuple("AuthProxy").field(proxy).finish(),
+            Self::Jwt { header, .. } => f
+                .debug_struct("Jwt")
+                .field("header", header)
+                .field("token", &"<redacted>")
+                .finish(),
+            Self::Session(session) => f.debug_tuple("Session").field(session).finish(),
+            Self::OAuth2(credentials) => f.debug_tuple("OAuth2").field(credentials).finish(),
+            Self::Provider(_) => f.debug_tuple("Provider").finish_non_exhaustive(),
+            #[cfg(feature = "async")]
+            Self::AsyncProvider(_) => f.debug_tuple("AsyncProvider").finish_non_exhaustive(),
         }
     }
 }
 
 impl Auth {
-    pub(crate) fn apply(&self, headers: &mut HeaderMap) -> Result<()> {
+    /// Whether a `401` is worth one refresh-and-retry.
+    pub(crate) fn is_refreshable(&self) -> bool {
+        match self {
+            Self::Provider(_) | Self::Session(_) | Self::OAuth2(_) => true,
+            #[cfg(feature = "async")]
+            Self::AsyncProvider(_) => true,
+            _ => false,
+        }
+    }
+
+    /// Credentials a provider may hand out: anything applied without further requests.
+    fn is_static(&self) -> bool {
+        matches!(
+            self,
+            Self::None
+                | Self::Bearer(_)
+                | Self::Basic { .. }
+                | Self::AuthProxy(_)
+                | Self::Jwt { .. }
+        )
+    }
+
+    /// Asks an async provider for the credentials of the next attempt; other variants are
+    /// applied as they are.
+    #[cfg(feature = "async")]
+    pub(crate) async fn resolve(&self) -> Result<Option<Auth>> {
+        match self {
+            Self::AsyncProvider(provider) => match provider.credentials().await? {
+                auth if !auth.is_static() => Err(Error::invalid_config(
+                    "credential provider must return static credentials",
+                )),
+                auth => Ok(Some(auth)),
+            },
+            _ => Ok(None),
+        }
+    }
+
+    /// Drops the credentials a request with `sent` headers got a `401` for.
+    pub(crate) fn refresh(&self, sent: &HeaderMap) -> Result<()> {
+        match self {
+            Self::Provider(provider) => provider.refresh(),
+            Self::Session(session) => {
+                session.expire(sent);
+                Ok(())
+            }
+            Self::OAuth2(credentials) => {
+                credentials.clear();
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    #[cfg(feature = "async")]
+    pub(crate) async fn refresh_async(&self, sent: &HeaderMap) -> Result<()> {
+        match self {
+            Self::AsyncProvider(provider) => provider.refresh().await,
+            _ => self.refresh(sent),
+        }
+    }
+
+    /// Adds the credentials to `headers` and returns the names of the headers carrying them.
+    pub(crate) fn apply(&self, headers: &mut HeaderMap) -> Result<Vec<HeaderName>> {
         match self {
-            Self::None => Ok(()),
+            Self::None => Ok(Vec::new()),
+            Self::Provider(provider) => match provider.credentials()? {
+                auth if !auth.is_static() => Err(Error::invalid_config(
+                    "credential provider must return static credentials",
+                )),
+                auth => auth.apply(headers),
+            },
+            #[cfg(feature = "async")]
+            Self::AsyncProvider(_) => Err(Error::invalid_config(
+                "async credential providers need the async client",
+            )),
             Self::Bearer(token) => {
                 let value = HeaderValue::from_str(&format!("Bearer {token}"))
                     .map_err(|e| Error::invalid_config(format!("invalid bearer token: {e}")))?;
                 headers.insert(http::header::AUTHORIZATION, value);
-                Ok(())
+                Ok(vec![http::header::AUTHORIZATION])
             }
             Self::Basic { username, password } => {
                 let credentials = format!("{username}:{password}");
@@ -59,8 +213,189 @@ impl Auth {
                 let value = HeaderValue::from_str(&format!("Basic {encoded}"))
                     .map_err(|e| Error::invalid_config(format!("invalid basic auth: {e}")))?;
                 headers.insert(http::header::AUTHORIZATION, value);
-                Ok(())
+                Ok(vec![http::header::AUTHORIZATION])
+            }
+            Self::AuthProxy(proxy) => {
+                let user = HeaderValue::from_str(&proxy.user)
+                    .map_err(|e| Error::invalid_config(format!("invalid auth proxy user: {e}")))?;
+                headers.insert(proxy.user_header.clone(), user);
+                for (name, value) in &proxy.headers {
+                    headers.insert(name.clone(), value.clone());
+                }
+                Ok(std::iter::once(proxy.user_header.clone())
+                    .chain(proxy.headers.keys().cloned())
+                    .collect())
+            }
+            Self::Jwt { header, token } => {
+                let name = HeaderName::from_bytes(header.as_bytes())
+                    .map_err(|e| Error::invalid_config(format!("invalid JWT header name: {e}")))?;
+                let value = HeaderValue::from_str(token)
+                    .map_err(|e| Error::invalid_config(format!("invalid JWT: {e}")))?;
+                headers.insert(name.clone(), value);
+                Ok(vec![name])
             }
+            Self::Session(session) => {
+                if let Some(cookie) = session.cookie() {
+                    let value = HeaderValue::from_str(&session_header(&cookie)).map_err(|e| {
+                        Error::invalid_config(format!("invalid session cookie: {e}"))
+                    })?;
+                    headers.insert(http::header::COOKIE, value);
+                }
+                Ok(vec![http::header::COOKIE])
+            }
+            Self::OAuth2(credentials) => match credentials.cached() {
+                Cached::Fresh(token) => Self::Bearer(token).apply(headers),
+                Cached::Fetch { .. } | Cached::Wait(_) => {
+                    Err(Error::invalid_config("no OAuth2 token was fetched"))
+                }
+            },
+        }
+    }
+}
+
+/// Names of the headers [`Auth`] put on a request, kept in its extensions so that recorders can
+/// scrub credentials in headers they would not recognize by name.
+#[cfg(feature = "cassette")]
+#[derive(Clone, Debug, Default)]
+pub(crate) struct CredentialHeaders(pub(crate) Vec<HeaderName>);
+
+/// Headers for Grafana's auth proxy mode.
+#[derive(Clone)]
+pub struct AuthProxy {
+    user_header: HeaderName,
+    user: String,
+    headers: HeaderMap,
+}
+
+impl AuthProxy {
+    pub fn new(user: impl Into<String>) -> Self {
+        Self {
+            user_header: HeaderName::from_static("x-webauth-user"),
+            user: user.into(),
+            headers: HeaderMap::new(),
+        }
+    }
+
+    /// Header carrying the user, when Grafana's `header_name` is not `X-WEBAUTH-USER`.
+    pub fn user_header(mut self, name: impl AsRef<str>) -> Result<Self> {
+        self.user_header = HeaderName::from_bytes(name.as_ref().as_bytes())
+            .map_err(|e| Error::invalid_config(format!("invalid auth proxy header: {e}")))?;
+        Ok(self)
+    }
+
+    /// An extra header from Grafana's `headers` mapping, e.g. `X-WEBAUTH-EMAIL` or
+    /// `X-WEBAUTH-ROLE`.
+    pub fn header(mut self, name: impl AsRef<str>, value: impl AsRef<str>) -> Result<Self> {
+        let name = HeaderName::from_bytes(name.as_ref().as_bytes())
+            .map_err(|e| Error::invalid_config(format!("invalid auth proxy header: {e}")))?;
+        let value = HeaderValue::from_str(value.as_ref())
+            .map_err(|e| Error::invalid_config(format!("invalid auth proxy header value: {e}")))?;
+        self.headers.insert(name, value);
+        Ok(self)
+    }
+}
+
+impl From<ClientCredentials> for Auth {
+    fn from(credentials: ClientCredentials) -> Self {
+        Self::OAuth2(credentials)
+    }
+}
+
+impl From<AuthProxy> for Auth {
+    fn from(proxy: AuthProxy) -> Self {
+        Self::AuthProxy(proxy)
+    }
+}
+
+impl fmt::Debug for AuthProxy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AuthProxy")
+            .field("user_header", &self.user_header)
+            .field("user", &self.user)
+            .field("headers", &self.headers.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+pub(crate) const SESSION_COOKIE: &str = "grafana_session";
+
+/// Login state of [`Auth::session`], shared by every clone of the client.
+#[derive(Clone)]
+pub struct Session {
+    inner: Arc<SessionInner>,
+}
+
+struct SessionInner {
+    username: String,
+    password: String,
+    /// The `grafana_session` cookie value and the login that replaces it.
+    cookie: Flight<String>,
+}
+
+impl Session {
+    pub fn username(&self) -> &str {
+        &self.inner.username
+    }
+
+    pub fn is_logged_in(&self) -> bool {
+        self.cookie().is_some()
+    }
+
+    pub(crate) fn cookie(&self) -> Option<String> {
+        self.inner.cookie.lock().value.clone()
+    }
+
+    /// The cookie for the next attempt, a claim to log in, or the login to wait for.
+    pub(crate) fn cached(&self) -> Cached<'_, String> {
+        let mut state = self.inner.cookie.lock();
+        match state.value.clone() {
+            Some(cookie) => Cached::Fresh(cookie),
+            None => self.inner.cookie.fetch_or_wait(&mut state, None),
+        }
+    }
+
+    pub(crate) fn flight(&self) -> &Flight<String> {
+        &self.inner.cookie
+    }
+
+    pub(crate) fn set_cookie(&self, cookie: String) {
+        self.inner.cookie.lock().value = Some(cookie);
+    }
+
+    /// Drops the cookie a request with `sent` headers was rejected with. A cookie from a newer
+    /// login or rotation is kept, so concurrent `401`s lead to one login.
+    pub(crate) fn expire(&self, sent: &HeaderMap) {
+        let sent = sent
+            .get(http::header::COOKIE)
+            .and_then(|value| value.to_str().ok());
+        let mut state = self.inner.cookie.lock();
+        if state
+            .value
+            .as_deref()
+            .is_some_and(|cookie| sent == Some(session_header(cookie).as_str()))
+        {
+            state.value = None;
         }
     }
+
+    pub(crate) fn login_body(&self) -> serde_json::Value {
+        serde_json::json!({
+            "user": self.inner.username,
+            "password": self.inner.password,
+        })
+    }
+}
+
+fn session_header(cookie: &str) -> String {
+    format!("{SESSION_COOKIE}={cookie}")
+}
+
+impl fmt::Debug for Session {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Session")
+            .field("username", &self.inner.username)
+            .field("password", &"<redacted>")
+            .field("logged_in", &self.is_logged_in())
+            .finish()
+    }
 }
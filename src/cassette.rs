@@ -0,0 +1,364 @@
+//! Record/replay cassettes for offline tests.
+//!
+//! In [`CassetteMode::Record`] every request/response pair that goes over the wire is added to
+//! the cassette, which is written to its JSON file by [`Cassette::save`] and when the last clone
+//! is dropped. The file is replaced atomically, so a crash never leaves a truncated cassette.
+//!
+//! In [`CassetteMode::Replay`] nothing is sent: responses are served from the cassette, matched
+//! by method, path, query string and request body. Recorded interactions are consumed in order,
+//! so repeated calls to the same endpoint replay successive responses; once all matches are used
+//! the last one keeps being served.
+//!
+//! Credential headers (`Authorization`, `Cookie`, `Set-Cookie`, the JWT or auth proxy headers the
+//! client sets, ...) are scrubbed before they are written, and so are credential fields
+//! (`password`, `token`, `key`, ...) of JSON bodies, so cassettes can be committed alongside the
+//! tests that use them. Replayed responses carry `<redacted>` in those fields, and request bodies
+//! are matched after the same scrubbing. Non-JSON bodies are stored as they are.
+//!
+//! Session logins and OAuth2 token requests are never recorded; when replaying,
+//! [`Auth::session`](crate::Auth::session) and [`Auth::oauth2`](crate::Auth::oauth2) clients skip
+//! them and send no credentials.
+
+use std::{
+    fmt, fs,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, PoisonError},
+};
+
+use base64::Engine as _;
+use http::{HeaderMap, HeaderName, HeaderValue, StatusCode, request::Parts};
+use serde::{Deserialize, Serialize};
+
+use crate::{Error, Result, auth::CredentialHeaders, util::redact};
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CassetteMode {
+    Record,
+    Replay,
+}
+
+#[derive(Clone)]
+pub struct Cassette {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    mode: CassetteMode,
+    path: PathBuf,
+    state: Mutex<State>,
+}
+
+struct State {
+    interactions: Vec<Interaction>,
+    used: Vec<bool>,
+    /// Recorded interactions not yet written to the file.
+    unsaved: bool,
+}
+
+#[derive(Default, Deserialize, Serialize)]
+struct CassetteFile {
+    interactions: Vec<Interaction>,
+}
+
+#[derive(Clone, Deserialize, Serialize)]
+struct Interaction {
+    request: RecordedRequest,
+    response: RecordedResponse,
+}
+
+#[derive(Clone, Deserialize, Serialize)]
+struct RecordedRequest {
+    method: String,
+    path: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    query: Option<String>,
+    #[serde(default)]
+    headers: Vec<(String, String)>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    body: Option<RecordedBody>,
+}
+
+#[derive(Clone, Deserialize, Serialize)]
+struct RecordedResponse {
+    status: u16,
+    #[serde(default)]
+    headers: Vec<(String, String)>,
+    body: RecordedBody,
+}
+
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum RecordedBody {
+    Text(String),
+    Base64(String),
+}
+
+impl Cassette {
+    /// Starts a new recording at `path`, replacing any existing cassette.
+    pub fn record(path: impl Into<PathBuf>) -> Result<Self> {
+        let cassette = Self::new(CassetteMode::Record, path.into(), Vec::new());
+        write_file(&cassette.inner.path, &cassette.lock())?;
+        Ok(cassette)
+    }
+
+    /// Loads the cassette at `path` for replay.
+    pub fn replay(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let contents = fs::read(&path).map_err(|e| {
+            Error::invalid_config(format!("failed to read cassette {}: {e}", path.display()))
+        })?;
+        let file: CassetteFile = serde_json::from_slice(&contents).map_err(|e| {
+            Error::invalid_config(format!("invalid cassette {}: {e}", path.display()))
+        })?;
+        Ok(Self::new(CassetteMode::Replay, path, file.interactions))
+    }
+
+    fn new(mode: CassetteMode, path: PathBuf, interactions: Vec<Interaction>) -> Self {
+        let used = vec![false; interactions.len()];
+        Self {
+            inner: Arc::new(Inner {
+                mode,
+                path,
+                state: Mutex::new(State {
+                    interactions,
+                    used,
+                    unsaved: false,
+                }),
+            }),
+        }
+    }
+
+    pub fn mode(&self) -> CassetteMode {
+        self.inner.mode
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.inner.path
+    }
+
+    /// Number of interactions recorded so far (or loaded for replay).
+    pub fn len(&self) -> usize {
+        self.lock().interactions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Writes the interactions recorded so far to the cassette file. Does nothing when replaying
+    /// or when nothing was recorded since the last save.
+    pub fn save(&self) -> Result<()> {
+        let mut state = self.lock();
+        if self.inner.mode == CassetteMode::Record && state.unsaved {
+            write_file(&self.inner.path, &state)?;
+            state.unsaved = false;
+        }
+        Ok(())
+    }
+
+    /// Adds an interaction; it reaches the file on the next save.
+    pub(crate) fn record_interaction(
+        &self,
+        request: &Parts,
+        request_body: Option<&[u8]>,
+        status: StatusCode,
+        headers: &HeaderMap,
+        body: &[u8],
+    ) -> Result<()> {
+        let credential_headers = request
+            .extensions
+            .get::<CredentialHeaders>()
+            .map_or(&[][..], |headers| headers.0.as_slice());
+        let interaction = Interaction {
+            request: RecordedRequest {
+                method: request.method.as_str().to_owned(),
+                path: request.uri.path().to_owned(),
+                query: request.uri.query().map(str::to_owned),
+                headers: scrub_headers(&request.headers, credential_headers),
+                body: request_body.map(RecordedBody::scrubbed),
+            },
+            response: RecordedResponse {
+                status: status.as_u16(),
+                headers: scrub_headers(headers, &[]),
+                body: RecordedBody::scrubbed(body),
+            },
+        };
+
+        let mut state = self.lock();
+        state.interactions.push(interaction);
+        state.used.push(true);
+        state.unsaved = true;
+        Ok(())
+    }
+
+    /// Looks up the recorded response for a request.
+    pub(crate) fn replay_response(
+        &self,
+        request: &Parts,
+        request_body: Option<&[u8]>,
+    ) -> Result<http::Response<Vec<u8>>> {
+        let mut state = self.lock();
+        let matches = state
+            .interactions
+            .iter()
+            .enumerate()
+            .filter(|(_, interaction)| interaction.request.matches(request, request_body))
+            .map(|(index, _)| index)
+            .collect::<Vec<_>>();
+
+        let Some(index) = matches
+            .iter()
+            .copied()
+            .find(|&index| !state.used[index])
+            .or_else(|| matches.last().copied())
+        else {
+            return Err(Error::transport(
+                "no recorded interaction matches the request",
+                ReplayMiss {
+                    method: request.method.to_string(),
+                    path: request.uri.path().to_owned(),
+                    query: request.uri.query().map(str::to_owned),
+                },
+            ));
+        };
+        state.used[index] = true;
+
+        state.interactions[index].response.to_http()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, State> {
+        self.inner
+            .state
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl Drop for Inner {
+    fn drop(&mut self) {
+        let state = self.state.get_mut().unwrap_or_else(PoisonError::into_inner);
+        if self.mode == CassetteMode::Record && state.unsaved {
+            let _ = write_file(&self.path, state);
+        }
+    }
+}
+
+/// Writes next to the cassette and renames over it.
+fn write_file(path: &Path, state: &State) -> Result<()> {
+    let file = CassetteFile {
+        interactions: state.interactions.clone(),
+    };
+    let json = serde_json::to_vec_pretty(&file)
+        .map_err(|e| Error::transport("failed to encode cassette", e))?;
+    let mut temp = path.to_path_buf().into_os_string();
+    temp.push(".tmp");
+    fs::write(&temp, json).map_err(|e| Error::transport("failed to write cassette", e))?;
+    fs::rename(&temp, path).map_err(|e| Error::transport("failed to write cassette", e))
+}
+
+impl fmt::Debug for Cassette {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Cassette")
+            .field("mode", &self.inner.mode)
+            .field("path", &self.inner.path)
+            .field("interactions", &self.len())
+            .finish()
+    }
+}
+
+impl RecordedRequest {
+    fn matches(&self, request: &Parts, body: Option<&[u8]>) -> bool {
+        let recorded_body = self.body.as_ref().and_then(RecordedBody::decode);
+        let body = body.map(|body| redact::redact_json_body(body).unwrap_or_else(|| body.to_vec()));
+        self.method == request.method.as_str()
+            && self.path == request.uri.path()
+            && self.query.as_deref() == request.uri.query()
+            && recorded_body == body
+    }
+}
+
+impl RecordedResponse {
+    fn to_http(&self) -> Result<http::Response<Vec<u8>>> {
+        let status = StatusCode::from_u16(self.status)
+            .map_err(|e| Error::invalid_config(format!("invalid cassette status: {e}")))?;
+        let body = self
+            .body
+            .decode()
+            .ok_or_else(|| Error::invalid_config("invalid base64 body in cassette"))?;
+
+        let mut response = http::Response::new(body);
+        *response.status_mut() = status;
+        for (name, value) in &self.headers {
+            let name = HeaderName::from_bytes(name.as_bytes())
+                .map_err(|e| Error::invalid_config(format!("invalid cassette header: {e}")))?;
+            let value = HeaderValue::from_str(value)
+                .map_err(|e| Error::invalid_config(format!("invalid cassette header: {e}")))?;
+            response.headers_mut().append(name, value);
+        }
+        Ok(response)
+    }
+}
+
+impl RecordedBody {
+    /// Encodes `bytes` with JSON credential fields masked.
+    fn scrubbed(bytes: &[u8]) -> Self {
+        match redact::redact_json_body(bytes) {
+            Some(redacted) => Self::encode(&redacted),
+            None => Self::encode(bytes),
+        }
+    }
+
+    fn encode(bytes: &[u8]) -> Self {
+        match std::str::from_utf8(bytes) {
+            Ok(text) => Self::Text(text.to_owned()),
+            Err(_) => Self::Base64(base64::engine::general_purpose::STANDARD.encode(bytes)),
+        }
+    }
+
+    fn decode(&self) -> Option<Vec<u8>> {
+        match self {
+            Self::Text(text) => Some(text.as_bytes().to_vec()),
+            Self::Base64(encoded) => base64::engine::general_purpose::STANDARD
+                .decode(encoded)
+                .ok(),
+        }
+    }
+}
+
+/// Headers as written to disk. `credentials` are the headers the client's auth set (JWT, auth
+/// proxy, ...), which are replaced wholesale like `Authorization`.
+fn scrub_headers(headers: &HeaderMap, credentials: &[HeaderName]) -> Vec<(String, String)> {
+    headers
+        .iter()
+        .map(|(name, value)| {
+            let value = if credentials.contains(name) {
+                "<redacted>".to_owned()
+            } else {
+                redact::redact_header_value(
+                    name.as_str(),
+                    &String::from_utf8_lossy(value.as_bytes()),
+                )
+            };
+            (name.as_str().to_owned(), value)
+        })
+        .collect()
+}
+
+#[derive(Debug)]
+struct ReplayMiss {
+    method: String,
+    path: String,
+    query: Option<String>,
+}
+
+impl fmt::Display for ReplayMiss {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.method, self.path)?;
+        if let Some(query) = &self.query {
+            write!(f, "?{query}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ReplayMiss {}
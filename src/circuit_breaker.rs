@@ -0,0 +1,184 @@
+//! Circuit breaking for unhealthy Grafana instances.
+//!
+//! A [`CircuitBreaker`] counts consecutive transport errors and `5xx` responses. Once the count
+//! reaches the configured threshold the circuit opens and calls fail fast with
+//! [`Error::CircuitOpen`](crate::Error::CircuitOpen) instead of reaching the server. After the
+//! cooldown a single probe request is let through (half-open): success closes the circuit,
+//! failure opens it again.
+
+use std::{
+    fmt,
+    sync::{Arc, Mutex, PoisonError},
+    time::{Duration, Instant},
+};
+
+use crate::{Error, Result};
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CircuitState {
+    Closed,
+    Open,
+    HalfOpen,
+}
+
+#[derive(Clone)]
+pub struct CircuitBreaker {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    failure_threshold: u32,
+    cooldown: Duration,
+    state: Mutex<State>,
+}
+
+struct State {
+    circuit: CircuitState,
+    consecutive_failures: u32,
+    opened_at: Option<Instant>,
+    probe_started: Option<Instant>,
+}
+
+impl CircuitBreaker {
+    /// Opens after `failure_threshold` consecutive failures and half-opens after `cooldown`.
+    pub fn new(failure_threshold: u32, cooldown: Duration) -> Result<Self> {
+        if failure_threshold == 0 {
+            return Err(Error::invalid_config(
+                "circuit breaker failure threshold must be at least 1",
+            ));
+        }
+
+        Ok(Self {
+            inner: Arc::new(Inner {
+                failure_threshold,
+                cooldown,
+                state: Mutex::new(State {
+                    circuit: CircuitState::Closed,
+                    consecutive_failures: 0,
+                    opened_at: None,
+                    probe_started: None,
+                }),
+            }),
+        })
+    }
+
+    pub fn state(&self) -> CircuitState {
+        let mut state = self.lock();
+        self.advance(&mut state, Instant::now());
+        state.circuit
+    }
+
+    pub fn consecutive_failures(&self) -> u32 {
+        self.lock().consecutive_failures
+    }
+
+    pub fn failure_threshold(&self) -> u32 {
+        self.inner.failure_threshold
+    }
+
+    pub fn cooldown(&self) -> Duration {
+        self.inner.cooldown
+    }
+
+    /// Closes the circuit and clears the failure count.
+    pub fn reset(&self) {
+        let mut state = self.lock();
+        state.circuit = CircuitState::Closed;
+        state.consecutive_failures = 0;
+        state.opened_at = None;
+        state.probe_started = None;
+    }
+
+    /// Checks whether an attempt may be sent, claiming the probe slot when half-open.
+    pub(crate) fn acquire(&self) -> Result<()> {
+        let now = Instant::now();
+        let mut state = self.lock();
+        self.advance(&mut state, now);
+
+        match state.circuit {
+            CircuitState::Closed => Ok(()),
+            CircuitState::Open => {
+                let opened_at = state.opened_at.unwrap_or(now);
+                let retry_in = self
+                    .inner
+                    .cooldown
+                    .saturating_sub(now.saturating_duration_since(opened_at));
+                Err(Error::CircuitOpen { retry_in })
+            }
+            CircuitState::HalfOpen => {
+                let probe_active = state.probe_started.is_some_and(|started| {
+                    now.saturating_duration_since(started) < self.inner.cooldown
+                });
+                if probe_active {
+                    return Err(Error::CircuitOpen {
+                        retry_in: Duration::ZERO,
+                    });
+                }
+                state.probe_started = Some(now);
+                Ok(())
+            }
+        }
+    }
+
+    pub(crate) fn record_success(&self) {
+        self.reset();
+    }
+
+    /// An attempt whose failure does not count, e.g. because failover routes around the
+    /// replica. Frees the half-open probe slot for the next attempt.
+    pub(crate) fn record_skipped(&self) {
+        self.lock().probe_started = None;
+    }
+
+    pub(crate) fn record_failure(&self) {
+        let now = Instant::now();
+        let mut state = self.lock();
+        state.consecutive_failures = state.consecutive_failures.saturating_add(1);
+
+        let trip = match state.circuit {
+            CircuitState::HalfOpen => true,
+            CircuitState::Closed => state.consecutive_failures >= self.inner.failure_threshold,
+            CircuitState::Open => false,
+        };
+        if trip {
+            #[cfg(feature = "tracing")]
+            tracing::warn!(
+                consecutive_failures = state.consecutive_failures,
+                "circuit breaker opened"
+            );
+
+            state.circuit = CircuitState::Open;
+            state.opened_at = Some(now);
+            state.probe_started = None;
+        }
+    }
+
+    fn advance(&self, state: &mut State, now: Instant) {
+        if state.circuit == CircuitState::Open
+            && state.opened_at.is_none_or(|opened_at| {
+                now.saturating_duration_since(opened_at) >= self.inner.cooldown
+            })
+        {
+            state.circuit = CircuitState::HalfOpen;
+            state.probe_started = None;
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, State> {
+        self.inner
+            .state
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl fmt::Debug for CircuitBreaker {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CircuitBreaker")
+            .field("state", &self.state())
+            .field("consecutive_failures", &self.consecutive_failures())
+            .field("failure_threshold", &self.inner.failure_threshold)
+            .field("cooldown", &self.inner.cooldown)
+            .finish()
+    }
+}
@@ -1,3 +1,5 @@
+#[cfg(unix)]
+use std::path::PathBuf;
 use std::{sync::Arc, time::Duration};
 
 use http::{HeaderMap, HeaderName, HeaderValue, Method};
@@ -5,23 +7,48 @@ use serde::{Serialize, de::DeserializeOwned};
 use url::Url;
 
 use crate::{
-    Auth, Error, RequestOptions, ResponseBytes, Result, api,
-    transport::{AsyncTransport, BodySnippetConfig, RequestContext, RetryConfig, TransportConfig},
+    Auth, Error, Interceptor, Proxy, RequestOptions, ResponseBytes, ResponseStream, Result, api,
+    circuit_breaker::CircuitBreaker,
+    config::{Config, Context},
+    failover::Failover,
+    rate_limit::RateLimiter,
+    retry::{ExponentialBackoff, RetryPolicy},
+    transport::{
+        AsyncTransport, BodySnippetConfig, HttpTransport, RequestContext, ReqwestTransport,
+        TransportConfig,
+    },
+    types::{OrgId, OrgSummary, openapi::SearchOrgsQuery},
     util,
 };
 
+#[cfg(feature = "cassette")]
+use crate::cassette::Cassette;
+#[cfg(any(feature = "rustls", feature = "native-tls"))]
+use crate::transport::TlsConfig;
+#[cfg(feature = "tower")]
+use crate::{
+    error::BoxError,
+    transport::{BoxLayer, ResponseBody, TransportService},
+};
+
 #[derive(Clone)]
 pub struct Client {
     inner: Arc<Inner>,
+    default_headers: Arc<HeaderMap>,
 }
 
 struct Inner {
     api_base_url: Url,
     auth: Auth,
-    default_headers: HeaderMap,
     transport: AsyncTransport,
-    retry: RetryConfig,
+    retry: Arc<dyn RetryPolicy>,
     body_snippet: BodySnippetConfig,
+    interceptors: Vec<Arc<dyn Interceptor>>,
+    rate_limiter: Option<RateLimiter>,
+    circuit_breaker: Option<CircuitBreaker>,
+    failover: Option<Failover>,
+    #[cfg(feature = "cassette")]
+    cassette: Option<Cassette>,
 }
 
 pub struct ClientBuilder {
@@ -29,31 +56,23 @@ pub struct ClientBuilder {
     auth: Auth,
     default_headers: HeaderMap,
     transport: TransportConfig,
-    retry: RetryConfig,
+    retry: ExponentialBackoff,
+    retry_policy: Option<Arc<dyn RetryPolicy>>,
     body_snippet: BodySnippetConfig,
+    interceptors: Vec<Arc<dyn Interceptor>>,
+    rate_limiter: Option<RateLimiter>,
+    circuit_breaker: Option<CircuitBreaker>,
+    failover: Option<Failover>,
+    http_transport: Option<Arc<dyn HttpTransport>>,
+    #[cfg(feature = "tower")]
+    layers: Vec<BoxLayer>,
+    #[cfg(feature = "cassette")]
+    cassette: Option<Cassette>,
 }
 
 impl Client {
     pub fn builder(base_url: impl AsRef<str>) -> Result<ClientBuilder> {
-        let base_url = Url::parse(base_url.as_ref())
-            .map_err(|e| Error::invalid_config(format!("invalid base_url: {e}")))?;
-
-        if base_url.cannot_be_a_base() {
-            return Err(Error::invalid_config("base_url must be hierarchical"));
-        }
-        if !matches!(base_url.scheme(), "http" | "https") {
-            return Err(Error::invalid_config(
-                "base_url scheme must be http or https",
-            ));
-        }
-        if base_url.host_str().is_none() {
-            return Err(Error::invalid_config("base_url must include a host"));
-        }
-        if base_url.query().is_some() || base_url.fragment().is_some() {
-            return Err(Error::invalid_config(
-                "base_url must not include query or fragment",
-            ));
-        }
+        let base_url = util::url::parse_base_url(base_url.as_ref())?;
 
         let mut default_headers = HeaderMap::new();
         default_headers.insert(
@@ -68,19 +87,74 @@ impl Client {
             transport: TransportConfig {
                 timeout: Duration::from_secs(30),
                 connect_timeout: Duration::from_secs(10),
+                total_timeout: None,
+                proxies: Vec::new(),
+                proxy_from_env: false,
+                #[cfg(unix)]
+                unix_socket: None,
+                #[cfg(any(feature = "rustls", feature = "native-tls"))]
+                tls: TlsConfig::default(),
             },
-            retry: RetryConfig {
-                max_retries: 3,
-                base_delay: Duration::from_millis(200),
-                max_delay: Duration::from_secs(2),
-            },
+            retry: ExponentialBackoff::default(),
+            retry_policy: None,
             body_snippet: BodySnippetConfig {
                 enabled: true,
                 byte_limit: 4096,
             },
+            interceptors: Vec::new(),
+            rate_limiter: None,
+            circuit_breaker: None,
+            failover: None,
+            http_transport: None,
+            #[cfg(feature = "tower")]
+            layers: Vec::new(),
+            #[cfg(feature = "cassette")]
+            cassette: None,
         })
     }
 
+    /// Builder for a Grafana that serves on a unix socket (`protocol = socket`). Use
+    /// [`ClientBuilder::unix_socket`] instead when Grafana is served from a sub path.
+    #[cfg(unix)]
+    pub fn builder_unix_socket(path: impl Into<PathBuf>) -> Result<ClientBuilder> {
+        Ok(Self::builder("http://localhost")?.unix_socket(path))
+    }
+
+    /// Client from `GRAFANA_BASE_URL` and related variables; see [`Context::from_env`].
+    pub fn from_env() -> Result<Client> {
+        Self::builder_from_context(&Context::from_env()?)?.build()
+    }
+
+    /// Client for the current context of the config file; see [`Config::load`].
+    pub fn from_config() -> Result<Client> {
+        Self::builder_from_context(Config::load()?.current()?)?.build()
+    }
+
+    /// Builder with a context's settings applied, for further changes before `build`.
+    pub fn builder_from_context(context: &Context) -> Result<ClientBuilder> {
+        let mut builder = Self::builder(&context.base_url)?.auth(context.auth()?);
+        context.configure_transport(&mut builder.transport)?;
+        if let Some(org_id) = context.org_id {
+            builder = builder.org_id(org_id)?;
+        }
+        if let Some(max_retries) = context.max_retries {
+            builder = builder.max_retries(max_retries);
+        }
+        Ok(builder)
+    }
+
+    /// Builder that spreads calls over several replicas of the same Grafana and fails over
+    /// between them; see [`Failover`].
+    ///
+    /// A [`circuit_breaker`](ClientBuilder::circuit_breaker) on this builder only counts a
+    /// replica's failure while no other replica is healthy, so one dead replica does not open
+    /// the circuit for the whole client.
+    pub fn builder_failover(failover: Failover) -> Result<ClientBuilder> {
+        let mut builder = Self::builder(failover.primary().as_str())?;
+        builder.failover = Some(failover);
+        Ok(builder)
+    }
+
     pub fn health(&self) -> api::HealthService {
         api::HealthService::new(self.clone())
     }
@@ -117,6 +191,19 @@ impl Client {
         api::ServiceAccountsService::new(self.clone())
     }
 
+    pub fn alerting(&self) -> api::AlertingService {
+        api::AlertingService::new(self.clone())
+    }
+
+    pub fn alert_state(&self) -> api::AlertStateService {
+        api::AlertStateService::new(self.clone())
+    }
+
+    /// Grafana's own Alertmanager; use `.datasource(uid)` for an Alertmanager datasource.
+    pub fn alertmanager(&self) -> api::AlertmanagerService {
+        api::AlertmanagerService::new(self.clone())
+    }
+
     pub fn openapi(&self) -> api::OpenApi {
         api::OpenApi::new(self.clone())
     }
@@ -125,6 +212,102 @@ impl Client {
         api::RawService::new(self.clone())
     }
 
+    /// Endpoints with large bodies (exports, reports, search), returned without buffering.
+    pub fn streams(&self) -> api::StreamsService {
+        api::StreamsService::new(self.clone())
+    }
+
+    /// A view of this client that sends `X-Grafana-Org-Id: org_id`. It shares the connection
+    /// pool and every other setting, so it is cheap to create per call.
+    pub fn with_org(&self, org_id: impl Into<OrgId>) -> Client {
+        let mut default_headers = HeaderMap::clone(&self.default_headers);
+        default_headers.insert(
+            HeaderName::from_static("x-grafana-org-id"),
+            HeaderValue::from(org_id.into().0),
+        );
+        Client {
+            inner: Arc::clone(&self.inner),
+            default_headers: Arc::new(default_headers),
+        }
+    }
+
+    /// Lists every org (`GET /orgs`, server admin only) and runs `f` against an org-scoped view
+    /// of each, at most `concurrency` at a time. Results keep the order Grafana listed the orgs
+    /// in; a failing org does not stop the others.
+    pub async fn for_each_org<F, Fut, T>(
+        &self,
+        concurrency: usize,
+        f: F,
+    ) -> Result<Vec<(OrgSummary, Result<T>)>>
+    where
+        F: Fn(Client, OrgSummary) -> Fut,
+        Fut: Future<Output = Result<T>> + Send + 'static,
+        T: Send + 'static,
+    {
+        let orgs = self.all_orgs().await?;
+        let mut results: Vec<Option<Result<T>>> = orgs.iter().map(|_| None).collect();
+        let mut pending = orgs.iter().cloned().enumerate();
+        let mut tasks = tokio::task::JoinSet::new();
+        loop {
+            while tasks.len() < concurrency.max(1)
+                && let Some((index, org)) = pending.next()
+            {
+                let run = f(self.with_org(org.id), org);
+                tasks.spawn(async move { (index, run.await) });
+            }
+            let (index, result) = match tasks.join_next().await {
+                Some(Ok(done)) => done,
+                Some(Err(e)) if e.is_panic() => std::panic::resume_unwind(e.into_panic()),
+                Some(Err(e)) => return Err(Error::transport("org task was cancelled", e)),
+                None => break,
+            };
+            results[index] = Some(result);
+        }
+
+        Ok(orgs
+            .into_iter()
+            .zip(results)
+            .filter_map(|(org, result)| Some((org, result?)))
+            .collect())
+    }
+
+    async fn all_orgs(&self) -> Result<Vec<OrgSummary>> {
+        const PER_PAGE: usize = 1000;
+
+        let mut orgs = Vec::new();
+        let mut page = 1;
+        loop {
+            let query = SearchOrgsQuery {
+                page: Some(page),
+                perpage: Some(PER_PAGE as i64),
+                ..Default::default()
+            };
+            let batch: Vec<OrgSummary> = self.openapi().search_orgs(Some(&query)).await?;
+            let last = batch.len() < PER_PAGE;
+            orgs.extend(batch);
+            if last {
+                return Ok(orgs);
+            }
+            page += 1;
+        }
+    }
+
+    fn context(&self) -> RequestContext<'_> {
+        RequestContext {
+            base_url: &self.inner.api_base_url,
+            auth: &self.inner.auth,
+            default_headers: &self.default_headers,
+            retry: self.inner.retry.as_ref(),
+            body_snippet: &self.inner.body_snippet,
+            interceptors: &self.inner.interceptors,
+            rate_limiter: self.inner.rate_limiter.as_ref(),
+            circuit_breaker: self.inner.circuit_breaker.as_ref(),
+            failover: self.inner.failover.as_ref(),
+            #[cfg(feature = "cassette")]
+            cassette: self.inner.cassette.as_ref(),
+        }
+    }
+
     pub(crate) async fn request_json<Response, Query, Body>(
         &self,
         method: Method,
@@ -137,13 +320,7 @@ impl Client {
         Query: Serialize + ?Sized,
         Body: Serialize + ?Sized,
     {
-        let ctx = RequestContext {
-            base_url: &self.inner.api_base_url,
-            auth: &self.inner.auth,
-            default_headers: &self.inner.default_headers,
-            retry: &self.inner.retry,
-            body_snippet: &self.inner.body_snippet,
-        };
+        let ctx = self.context();
 
         self.inner
             .transport
@@ -164,13 +341,7 @@ impl Client {
         Query: Serialize + ?Sized,
         Body: Serialize + ?Sized,
     {
-        let ctx = RequestContext {
-            base_url: &self.inner.api_base_url,
-            auth: &self.inner.auth,
-            default_headers: &self.inner.default_headers,
-            retry: &self.inner.retry,
-            body_snippet: &self.inner.body_snippet,
-        };
+        let ctx = self.context();
 
         self.inner
             .transport
@@ -189,13 +360,7 @@ impl Client {
         Query: Serialize + ?Sized,
         Body: Serialize + ?Sized,
     {
-        let ctx = RequestContext {
-            base_url: &self.inner.api_base_url,
-            auth: &self.inner.auth,
-            default_headers: &self.inner.default_headers,
-            retry: &self.inner.retry,
-            body_snippet: &self.inner.body_snippet,
-        };
+        let ctx = self.context();
 
         self.inner
             .transport
@@ -215,13 +380,7 @@ impl Client {
         Query: Serialize + ?Sized,
         Body: Serialize + ?Sized,
     {
-        let ctx = RequestContext {
-            base_url: &self.inner.api_base_url,
-            auth: &self.inner.auth,
-            default_headers: &self.inner.default_headers,
-            retry: &self.inner.retry,
-            body_snippet: &self.inner.body_snippet,
-        };
+        let ctx = self.context();
 
         self.inner
             .transport
@@ -241,13 +400,7 @@ impl Client {
         Response: DeserializeOwned,
         Query: Serialize + ?Sized,
     {
-        let ctx = RequestContext {
-            base_url: &self.inner.api_base_url,
-            auth: &self.inner.auth,
-            default_headers: &self.inner.default_headers,
-            retry: &self.inner.retry,
-            body_snippet: &self.inner.body_snippet,
-        };
+        let ctx = self.context();
 
         self.inner
             .transport
@@ -268,13 +421,7 @@ impl Client {
         Response: DeserializeOwned,
         Query: Serialize + ?Sized,
     {
-        let ctx = RequestContext {
-            base_url: &self.inner.api_base_url,
-            auth: &self.inner.auth,
-            default_headers: &self.inner.default_headers,
-            retry: &self.inner.retry,
-            body_snippet: &self.inner.body_snippet,
-        };
+        let ctx = self.context();
 
         self.inner
             .transport
@@ -301,13 +448,7 @@ impl Client {
     where
         Query: Serialize + ?Sized,
     {
-        let ctx = RequestContext {
-            base_url: &self.inner.api_base_url,
-            auth: &self.inner.auth,
-            default_headers: &self.inner.default_headers,
-            retry: &self.inner.retry,
-            body_snippet: &self.inner.body_snippet,
-        };
+        let ctx = self.context();
 
         self.inner
             .transport
@@ -327,13 +468,7 @@ impl Client {
     where
         Query: Serialize + ?Sized,
     {
-        let ctx = RequestContext {
-            base_url: &self.inner.api_base_url,
-            auth: &self.inner.auth,
-            default_headers: &self.inner.default_headers,
-            retry: &self.inner.retry,
-            body_snippet: &self.inner.body_snippet,
-        };
+        let ctx = self.context();
 
         self.inner
             .transport
@@ -349,6 +484,26 @@ impl Client {
             .await
     }
 
+    pub(crate) async fn request_stream<Query, Body>(
+        &self,
+        method: Method,
+        segments: &[&str],
+        query: Option<&Query>,
+        body: Option<&Body>,
+        options: Option<&RequestOptions>,
+    ) -> Result<ResponseStream>
+    where
+        Query: Serialize + ?Sized,
+        Body: Serialize + ?Sized,
+    {
+        let ctx = self.context();
+
+        self.inner
+            .transport
+            .send_stream(ctx, method, segments, query, body, options)
+            .await
+    }
+
     pub(crate) async fn get_json<Response, Query>(
         &self,
         segments: &[&str],
@@ -439,18 +594,144 @@ impl ClientBuilder {
         self
     }
 
+    /// Budget for each call across all attempts and retry delays; calls that run out fail
+    /// with [`Error::Timeout`]. Per-request options take precedence.
+    pub fn total_timeout(mut self, timeout: Duration) -> Self {
+        self.transport.total_timeout = Some(timeout);
+        self
+    }
+
+    /// Sends requests through a custom backend instead of the bundled reqwest one. Connect
+    /// timeout, TLS, proxy and unix socket settings are then up to the backend.
+    pub fn transport(mut self, transport: impl HttpTransport) -> Self {
+        self.http_transport = Some(Arc::new(transport));
+        self
+    }
+
+    /// Sends requests through a preconfigured `reqwest::Client`; start from
+    /// [`ReqwestTransport::client_builder`] so the TLS backend is ready.
+    pub fn reqwest_client(self, client: reqwest::Client) -> Self {
+        self.transport(ReqwestTransport::new(client))
+    }
+
+    /// Wraps the transport in a `tower` layer (or a `ServiceBuilder` stack). Layers see each
+    /// attempt, so retries, auth and error mapping still happen around them; the first layer
+    /// added is the outermost.
+    #[cfg(feature = "tower")]
+    pub fn layer<L>(mut self, layer: L) -> Self
+    where
+        L: tower::Layer<TransportService> + Send + 'static,
+        L::Service: tower::Service<http::Request<bytes::Bytes>, Response = http::Response<ResponseBody>>
+            + Clone
+            + Send
+            + 'static,
+        <L::Service as tower::Service<http::Request<bytes::Bytes>>>::Error: Into<BoxError>,
+        <L::Service as tower::Service<http::Request<bytes::Bytes>>>::Future: Send,
+    {
+        self.layers.push(crate::transport::box_layer(layer));
+        self
+    }
+
+    /// Connects over a unix socket instead of TCP. The base URL still provides the path prefix
+    /// and the `Host` header; proxy settings are ignored.
+    #[cfg(unix)]
+    pub fn unix_socket(mut self, path: impl Into<PathBuf>) -> Self {
+        self.transport.unix_socket = Some(path.into());
+        self
+    }
+
+    /// Routes requests through a proxy. Proxies are consulted in the order they were added;
+    /// the first whose scope matches and whose bypass list does not is used.
+    pub fn proxy(mut self, proxy: Proxy) -> Self {
+        self.transport.proxies.push(proxy);
+        self
+    }
+
+    /// Also honors `HTTP_PROXY`, `HTTPS_PROXY`, `ALL_PROXY` and `NO_PROXY`, read at `build`
+    /// time. Off by default, so the environment never silently reroutes traffic.
+    pub fn proxy_from_env(mut self, enabled: bool) -> Self {
+        self.transport.proxy_from_env = enabled;
+        self
+    }
+
+    /// Trusts the certificates in a PEM bundle in addition to (or, with
+    /// `tls_built_in_roots(false)`, instead of) the platform roots.
+    #[cfg(any(feature = "rustls", feature = "native-tls"))]
+    pub fn add_root_certificate_pem(mut self, pem: impl AsRef<[u8]>) -> Result<Self> {
+        self.transport.tls.add_root_certificate_pem(pem.as_ref())?;
+        Ok(self)
+    }
+
+    /// Presents a client certificate for mutual TLS. With `native-tls` the key must be PKCS#8.
+    #[cfg(any(feature = "rustls", feature = "native-tls"))]
+    pub fn client_identity_pem(
+        mut self,
+        cert_chain: impl AsRef<[u8]>,
+        private_key: impl AsRef<[u8]>,
+    ) -> Result<Self> {
+        self.transport
+            .tls
+            .set_client_identity_pem(cert_chain.as_ref(), private_key.as_ref())?;
+        Ok(self)
+    }
+
+    #[cfg(any(feature = "rustls", feature = "native-tls"))]
+    pub fn tls_built_in_roots(mut self, enabled: bool) -> Self {
+        self.transport.tls.set_built_in_roots(enabled);
+        self
+    }
+
+    /// Pins the server to the base64 SHA-256 digest of a DER-encoded SPKI, optionally written
+    /// `sha256//<base64>` as for `curl --pinnedpubkey`. The chain is still validated, and one of
+    /// its certificates must carry a pinned key.
+    ///
+    /// Requires `rustls`: with only `native-tls`, `build` fails, because native-tls cannot check
+    /// the key during the handshake, before credentials are sent.
+    #[cfg(any(feature = "rustls", feature = "native-tls"))]
+    pub fn pin_spki_sha256(mut self, pin: impl AsRef<str>) -> Result<Self> {
+        self.transport.tls.add_spki_pin(pin.as_ref())?;
+        Ok(self)
+    }
+
     pub fn max_retries(mut self, max_retries: usize) -> Self {
-        self.retry.max_retries = max_retries;
+        self.retry = self.retry.max_retries(max_retries);
         self
     }
 
     pub fn retry_base_delay(mut self, delay: Duration) -> Self {
-        self.retry.base_delay = delay;
+        self.retry = self.retry.base_delay(delay);
         self
     }
 
     pub fn retry_max_delay(mut self, delay: Duration) -> Self {
-        self.retry.max_delay = delay;
+        self.retry = self.retry.max_delay(delay);
+        self
+    }
+
+    /// Replaces the retry policy. `max_retries` and the `retry_*_delay` settings only
+    /// configure the default [`ExponentialBackoff`] policy and are ignored once this is set.
+    pub fn retry_policy(mut self, policy: impl RetryPolicy) -> Self {
+        self.retry_policy = Some(Arc::new(policy));
+        self
+    }
+
+    /// Paces requests through a token bucket shared by every clone of the client.
+    pub fn rate_limiter(mut self, limiter: RateLimiter) -> Self {
+        self.rate_limiter = Some(limiter);
+        self
+    }
+
+    /// Fails fast with [`Error::CircuitOpen`] while the breaker is open.
+    pub fn circuit_breaker(mut self, breaker: CircuitBreaker) -> Self {
+        self.circuit_breaker = Some(breaker);
+        self
+    }
+
+    /// Records every request/response pair to the cassette, or serves responses from it
+    /// without touching the network, depending on the cassette's mode.
+    #[cfg(feature = "cassette")]
+    pub fn cassette(mut self, cassette: Cassette) -> Self {
+        self.cassette = Some(cassette);
         self
     }
 
@@ -464,27 +745,36 @@ impl ClientBuilder {
         self
     }
 
+    /// Appends an interceptor; interceptors run in the order they were added.
+    pub fn interceptor(mut self, interceptor: impl Interceptor) -> Self {
+        self.interceptors.push(Arc::new(interceptor));
+        self
+    }
+
     pub fn build(self) -> Result<Client> {
-        let api_base_url = match self
-            .base_url
-            .path_segments()
-            .and_then(|mut segs| segs.rfind(|s| !s.is_empty()))
-        {
-            Some("api") => self.base_url,
-            _ => util::url::endpoint(&self.base_url, &["api"])?,
-        };
+        let api_base_url = util::url::api_base_url(&self.base_url)?;
 
-        let transport = AsyncTransport::new(&self.transport)?;
+        let http_transport = self.http_transport;
+        #[cfg(feature = "tower")]
+        let http_transport =
+            crate::transport::apply_layers(&self.transport, http_transport, self.layers)?;
+        let transport = AsyncTransport::new(&self.transport, http_transport)?;
 
         Ok(Client {
             inner: Arc::new(Inner {
                 api_base_url,
                 auth: self.auth,
-                default_headers: self.default_headers,
                 transport,
-                retry: self.retry,
+                retry: self.retry_policy.unwrap_or_else(|| Arc::new(self.retry)),
                 body_snippet: self.body_snippet,
+                interceptors: self.interceptors,
+                rate_limiter: self.rate_limiter,
+                circuit_breaker: self.circuit_breaker,
+                failover: self.failover,
+                #[cfg(feature = "cassette")]
+                cassette: self.cassette,
             }),
+            default_headers: Arc::new(self.default_headers),
         })
     }
 }
@@ -1,9 +1,9 @@
 #[cfg(feature = "async")]
 mod async_client;
-#[cfg(feature = "blocking")]
+#[cfg(feature = "blocking-core")]
 mod blocking_client;
 
 #[cfg(feature = "async")]
 pub use async_client::{Client, ClientBuilder};
-#[cfg(feature = "blocking")]
+#[cfg(feature = "blocking-core")]
 pub use blocking_client::{BlockingClient, BlockingClientBuilder};
@@ -0,0 +1,375 @@
+//! Client settings from the environment or a config file with named contexts.
+//!
+//! The config file is JSON, laid out like a kubeconfig:
+//!
+//! ```json
+//! {
+//!   "current-context": "prod",
+//!   "contexts": [
+//!     {
+//!       "name": "prod",
+//!       "context": {
+//!         "base-url": "https://grafana.example.com",
+//!         "auth": { "type": "bearer", "token": { "env": "GRAFANA_PROD_TOKEN" } },
+//!         "org-id": 2,
+//!         "timeout-secs": 30,
+//!         "max-retries": 3,
+//!         "tls": { "ca-file": "certs/ca.pem" }
+//!       }
+//!     }
+//!   ]
+//! }
+//! ```
+//!
+//! [`Config::load`] reads `$GRAFANA_CONFIG`, or `grafana/config.json` under the user's config
+//! directory. `$GRAFANA_CONTEXT` overrides `current-context`. Relative paths in the file are
+//! resolved against the file's directory.
+
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use serde::Deserialize;
+
+use crate::{Auth, Error, Result, oauth2::ClientCredentials, transport::TransportConfig};
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Config {
+    pub current_context: Option<String>,
+    #[serde(default)]
+    pub contexts: Vec<NamedContext>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct NamedContext {
+    pub name: String,
+    pub context: Context,
+}
+
+/// Everything needed to build a client for one Grafana.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Context {
+    pub base_url: String,
+    #[serde(default)]
+    pub auth: AuthConfig,
+    pub org_id: Option<i64>,
+    pub timeout_secs: Option<u64>,
+    pub connect_timeout_secs: Option<u64>,
+    pub total_timeout_secs: Option<u64>,
+    pub max_retries: Option<usize>,
+    #[serde(default)]
+    pub tls: TlsSettings,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(
+    tag = "type",
+    rename_all = "kebab-case",
+    rename_all_fields = "kebab-case"
+)]
+pub enum AuthConfig {
+    #[default]
+    None,
+    Bearer {
+        token: Secret,
+    },
+    Basic {
+        username: String,
+        password: Secret,
+    },
+    Session {
+        username: String,
+        password: Secret,
+    },
+    #[serde(rename = "oauth2")]
+    OAuth2 {
+        token_url: String,
+        client_id: String,
+        client_secret: Secret,
+        #[serde(default)]
+        scopes: Vec<String>,
+    },
+}
+
+/// A secret written inline, or read from `{"env": "VAR"}` or `{"file": "path"}` when the client
+/// is built. Values read from files are trimmed.
+#[derive(Clone, Deserialize)]
+#[serde(untagged)]
+pub enum Secret {
+    Value(String),
+    Env { env: String },
+    File { file: PathBuf },
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct TlsSettings {
+    /// PEM bundle trusted in addition to the platform roots.
+    pub ca_file: Option<PathBuf>,
+    pub client_cert_file: Option<PathBuf>,
+    pub client_key_file: Option<PathBuf>,
+    pub built_in_roots: Option<bool>,
+    #[serde(default)]
+    pub pinned_spki_sha256: Vec<String>,
+}
+
+impl Config {
+    /// Reads the config file named by `$GRAFANA_CONFIG`, or the default one.
+    pub fn load() -> Result<Self> {
+        match std::env::var_os("GRAFANA_CONFIG") {
+            Some(path) => Self::from_path(path),
+            None => {
+                Self::from_path(Self::default_path().ok_or_else(|| {
+                    Error::invalid_config("no config directory; set GRAFANA_CONFIG")
+                })?)
+            }
+        }
+    }
+
+    /// `grafana/config.json` under `$XDG_CONFIG_HOME`, `~/.config` or, on Windows, `%APPDATA%`.
+    pub fn default_path() -> Option<PathBuf> {
+        let dir = std::env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| Path::new(&home).join(".config")))
+            .or_else(|| std::env::var_os("APPDATA").map(PathBuf::from))?;
+        Some(dir.join("grafana").join("config.json"))
+    }
+
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let json = std::fs::read_to_string(path).map_err(|e| {
+            Error::invalid_config(format!("failed to read config {}: {e}", path.display()))
+        })?;
+        let mut config: Self = serde_json::from_str(&json).map_err(|e| {
+            Error::invalid_config(format!("invalid config {}: {e}", path.display()))
+        })?;
+        if let Some(dir) = path.parent() {
+            for named in &mut config.contexts {
+                named.context.resolve_paths(dir);
+            }
+        }
+        Ok(config)
+    }
+
+    pub fn from_json(json: &str) -> Result<Self> {
+        serde_json::from_str(json)
+            .map_err(|e| Error::invalid_config(format!("invalid config: {e}")))
+    }
+
+    pub fn context(&self, name: &str) -> Result<&Context> {
+        self.contexts
+            .iter()
+            .find(|named| named.name == name)
+            .map(|named| &named.context)
+            .ok_or_else(|| Error::invalid_config(format!("no context named {name:?} in config")))
+    }
+
+    /// The context named by `$GRAFANA_CONTEXT`, or else by `current-context`.
+    pub fn current(&self) -> Result<&Context> {
+        match std::env::var("GRAFANA_CONTEXT") {
+            Ok(name) => self.context(&name),
+            Err(_) => match &self.current_context {
+                Some(name) => self.context(name),
+                None => Err(Error::invalid_config("config has no current-context")),
+            },
+        }
+    }
+}
+
+impl Context {
+    /// Reads `GRAFANA_BASE_URL` (required), `GRAFANA_TOKEN` or
+    /// `GRAFANA_USERNAME`/`GRAFANA_PASSWORD`, and `GRAFANA_ORG_ID`.
+    pub fn from_env() -> Result<Self> {
+        fn var(name: &str) -> Option<String> {
+            std::env::var(name).ok().filter(|value| !value.is_empty())
+        }
+
+        let base_url = var("GRAFANA_BASE_URL")
+            .ok_or_else(|| Error::invalid_config("GRAFANA_BASE_URL is not set"))?;
+        let auth = match (
+            var("GRAFANA_TOKEN"),
+            var("GRAFANA_USERNAME"),
+            var("GRAFANA_PASSWORD"),
+        ) {
+            (Some(token), _, _) => AuthConfig::Bearer {
+                token: Secret::Value(token),
+            },
+            (None, Some(username), Some(password)) => AuthConfig::Basic {
+                username,
+                password: Secret::Value(password),
+            },
+            (None, Some(_), None) => {
+                return Err(Error::invalid_config(
+                    "GRAFANA_USERNAME is set but GRAFANA_PASSWORD is not",
+                ));
+            }
+            _ => AuthConfig::None,
+        };
+        let org_id = var("GRAFANA_ORG_ID")
+            .map(|org_id| {
+                org_id
+                    .parse()
+                    .map_err(|e| Error::invalid_config(format!("invalid GRAFANA_ORG_ID: {e}")))
+            })
+            .transpose()?;
+
+        Ok(Self {
+            base_url,
+            auth,
+            org_id,
+            ..Self::default()
+        })
+    }
+
+    pub(crate) fn auth(&self) -> Result<Auth> {
+        Ok(match &self.auth {
+            AuthConfig::None => Auth::none(),
+            AuthConfig::Bearer { token } => Auth::bearer(token.read()?),
+            AuthConfig::Basic { username, password } => Auth::basic(username, password.read()?),
+            AuthConfig::Session { username, password } => Auth::session(username, password.read()?),
+            AuthConfig::OAuth2 {
+                token_url,
+                client_id,
+                client_secret,
+                scopes,
+            } => Auth::oauth2(scopes.iter().fold(
+                ClientCredentials::new(token_url, client_id, client_secret.read()?)?,
+                |credentials, scope| credentials.scope(scope),
+            )),
+        })
+    }
+
+    /// Applies the timeouts and TLS settings.
+    pub(crate) fn configure_transport(&self, transport: &mut TransportConfig) -> Result<()> {
+        if let Some(secs) = self.timeout_secs {
+            transport.timeout = Duration::from_secs(secs);
+        }
+        if let Some(secs) = self.connect_timeout_secs {
+            transport.connect_timeout = Duration::from_secs(secs);
+        }
+        if let Some(secs) = self.total_timeout_secs {
+            transport.total_timeout = Some(Duration::from_secs(secs));
+        }
+        self.tls.configure(transport)
+    }
+
+    fn resolve_paths(&mut self, dir: &Path) {
+        let resolve = |path: &mut PathBuf| {
+            if path.is_relative() {
+                *path = dir.join(&*path);
+            }
+        };
+        match &mut self.auth {
+            AuthConfig::Bearer { token: secret }
+            | AuthConfig::Basic {
+                password: secret, ..
+            }
+            | AuthConfig::Session {
+                password: secret, ..
+            }
+            | AuthConfig::OAuth2 {
+                client_secret: secret,
+                ..
+            } => {
+                if let Secret::File { file } = secret {
+                    resolve(file);
+                }
+            }
+            AuthConfig::None => {}
+        }
+        let tls = &mut self.tls;
+        for path in [
+            &mut tls.ca_file,
+            &mut tls.client_cert_file,
+            &mut tls.client_key_file,
+        ]
+        .into_iter()
+        .flatten()
+        {
+            resolve(path);
+        }
+    }
+}
+
+impl Secret {
+    pub fn read(&self) -> Result<String> {
+        match self {
+            Self::Value(value) => Ok(value.clone()),
+            Self::Env { env } => std::env::var(env)
+                .map_err(|e| Error::invalid_config(format!("secret variable {env}: {e}"))),
+            Self::File { file } => std::fs::read_to_string(file)
+                .map(|value| value.trim().to_owned())
+                .map_err(|e| {
+                    Error::invalid_config(format!("failed to read secret {}: {e}", file.display()))
+                }),
+        }
+    }
+}
+
+impl fmt::Debug for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Value(_) => f.debug_tuple("Value").field(&"<redacted>").finish(),
+            Self::Env { env } => f.debug_struct("Env").field("env", env).finish(),
+            Self::File { file } => f.debug_struct("File").field("file", file).finish(),
+        }
+    }
+}
+
+impl TlsSettings {
+    #[cfg(all(
+        any(feature = "rustls", feature = "native-tls"),
+        any(feature = "async", feature = "blocking")
+    ))]
+    fn configure(&self, transport: &mut TransportConfig) -> Result<()> {
+        fn read(path: &Path) -> Result<Vec<u8>> {
+            std::fs::read(path).map_err(|e| {
+                Error::invalid_config(format!("failed to read {}: {e}", path.display()))
+            })
+        }
+
+        let tls = &mut transport.tls;
+        if let Some(path) = &self.ca_file {
+            tls.add_root_certificate_pem(&read(path)?)?;
+        }
+        match (&self.client_cert_file, &self.client_key_file) {
+            (Some(cert), Some(key)) => tls.set_client_identity_pem(&read(cert)?, &read(key)?)?,
+            (None, None) => {}
+            _ => {
+                return Err(Error::invalid_config(
+                    "client-cert-file and client-key-file must be set together",
+                ));
+            }
+        }
+        if let Some(enabled) = self.built_in_roots {
+            tls.set_built_in_roots(enabled);
+        }
+        for pin in &self.pinned_spki_sha256 {
+            tls.add_spki_pin(pin)?;
+        }
+        Ok(())
+    }
+
+    #[cfg(not(all(
+        any(feature = "rustls", feature = "native-tls"),
+        any(feature = "async", feature = "blocking")
+    )))]
+    fn configure(&self, _transport: &mut TransportConfig) -> Result<()> {
+        let empty = self.ca_file.is_none()
+            && self.client_cert_file.is_none()
+            && self.client_key_file.is_none()
+            && self.built_in_roots.is_none()
+            && self.pinned_spki_sha256.is_empty();
+        if empty {
+            Ok(())
+        } else {
+            Err(Error::invalid_config(
+                "TLS settings need the rustls or native-tls feature",
+            ))
+        }
+    }
+}
@@ -0,0 +1,161 @@
+//! Credentials that can change while the client is running.
+//!
+//! A [`CredentialProvider`] is consulted before every attempt, so rotated tokens are picked up
+//! without rebuilding the client. When Grafana answers `401 Unauthorized`, the client calls
+//! [`CredentialProvider::refresh`] and retries the request once with the new credentials.
+
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+    sync::{Mutex, PoisonError},
+    time::SystemTime,
+};
+
+use crate::{Auth, Error, Result};
+
+#[cfg(feature = "async")]
+use crate::transport::BoxFuture;
+
+/// Supplies the credentials for each attempt. Implementations should cache: `credentials` runs
+/// on every attempt.
+pub trait CredentialProvider: Send + Sync + 'static {
+    /// Static credentials ([`Auth::bearer`], [`Auth::basic`] or [`Auth::none`]) for the next
+    /// attempt.
+    fn credentials(&self) -> Result<Auth>;
+
+    /// Drops whatever is cached; called after a `401` before the request is retried.
+    fn refresh(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Async counterpart of [`CredentialProvider`] for sources that need I/O, such as a secrets
+/// manager. Only the async client accepts it.
+#[cfg(feature = "async")]
+pub trait AsyncCredentialProvider: Send + Sync + 'static {
+    fn credentials(&self) -> BoxFuture<'_, Result<Auth>>;
+
+    fn refresh(&self) -> BoxFuture<'_, Result<()>> {
+        Box::pin(async { Ok(()) })
+    }
+}
+
+/// A bearer token kept in a file, such as a mounted Kubernetes secret. The file is read again
+/// whenever its modification time or size changes; surrounding whitespace is ignored.
+pub struct FileCredentials {
+    path: PathBuf,
+    cached: Mutex<Option<Cached>>,
+}
+
+struct Cached {
+    modified: Option<SystemTime>,
+    len: u64,
+    token: String,
+}
+
+impl FileCredentials {
+    pub fn bearer(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            cached: Mutex::new(None),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn read_error(&self, e: impl fmt::Display) -> Error {
+        Error::invalid_config(format!(
+            "failed to read credentials from {}: {e}",
+            self.path.display()
+        ))
+    }
+}
+
+impl CredentialProvider for FileCredentials {
+    fn credentials(&self) -> Result<Auth> {
+        let metadata = std::fs::metadata(&self.path).map_err(|e| self.read_error(e))?;
+        let modified = metadata.modified().ok();
+        let len = metadata.len();
+
+        let mut cached = self.cached.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Some(cached) = cached.as_ref()
+            && cached.modified == modified
+            && cached.len == len
+        {
+            return Ok(Auth::bearer(cached.token.clone()));
+        }
+
+        let token = std::fs::read_to_string(&self.path).map_err(|e| self.read_error(e))?;
+        let token = token.trim();
+        if token.is_empty() {
+            return Err(self.read_error("file is empty"));
+        }
+        *cached = Some(Cached {
+            modified,
+            len,
+            token: token.to_owned(),
+        });
+        Ok(Auth::bearer(token))
+    }
+
+    fn refresh(&self) -> Result<()> {
+        *self.cached.lock().unwrap_or_else(PoisonError::into_inner) = None;
+        Ok(())
+    }
+}
+
+impl fmt::Debug for FileCredentials {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FileCredentials")
+            .field("path", &self.path)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Credentials read from environment variables on every attempt.
+#[derive(Clone, Debug)]
+pub enum EnvCredentials {
+    Bearer {
+        token_var: String,
+    },
+    Basic {
+        username_var: String,
+        password_var: String,
+    },
+}
+
+impl EnvCredentials {
+    /// Bearer token from the variable `token_var`, e.g. `GRAFANA_TOKEN`.
+    pub fn bearer(token_var: impl Into<String>) -> Self {
+        Self::Bearer {
+            token_var: token_var.into(),
+        }
+    }
+
+    /// Basic auth from the variables `username_var` and `password_var`.
+    pub fn basic(username_var: impl Into<String>, password_var: impl Into<String>) -> Self {
+        Self::Basic {
+            username_var: username_var.into(),
+            password_var: password_var.into(),
+        }
+    }
+}
+
+impl CredentialProvider for EnvCredentials {
+    fn credentials(&self) -> Result<Auth> {
+        fn var(name: &str) -> Result<String> {
+            std::env::var(name)
+                .map_err(|e| Error::invalid_config(format!("credentials variable {name}: {e}")))
+        }
+
+        match self {
+            Self::Bearer { token_var } => Ok(Auth::bearer(var(token_var)?)),
+            Self::Basic {
+                username_var,
+                password_var,
+            } => Ok(Auth::basic(var(username_var)?, var(password_var)?)),
+        }
+    }
+}
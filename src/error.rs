@@ -146,6 +146,23 @@ pub enum Error {
 
     #[error("{0}")]
     Api(Box<HttpError>),
+
+    #[error(
+        "request timed out after {attempts} attempt(s) ({}ms elapsed)",
+        elapsed.as_millis()
+    )]
+    Timeout {
+        attempts: usize,
+        elapsed: Duration,
+        #[source]
+        last_error: Option<Box<Error>>,
+    },
+
+    #[error("request cancelled after {attempts} attempt(s)")]
+    Cancelled { attempts: usize },
+
+    #[error("circuit breaker is open (retry_in={}ms)", retry_in.as_millis())]
+    CircuitOpen { retry_in: Duration },
 }
 
 impl Error {
@@ -165,6 +182,10 @@ impl Error {
         }
     }
 
+    pub(crate) fn transport_boxed(message: &'static str, source: BoxError) -> Self {
+        Self::Transport { message, source }
+    }
+
     pub(crate) fn decode(
         http: HttpError,
         source: impl std::error::Error + Send + Sync + 'static,
@@ -192,6 +213,9 @@ impl Error {
         match self {
             Self::InvalidConfig { .. } => None,
             Self::Transport { .. } => None,
+            Self::Timeout { .. } => None,
+            Self::Cancelled { .. } => None,
+            Self::CircuitOpen { .. } => None,
             Self::Decode { http, .. } => http.status(),
             Self::Auth(http)
             | Self::NotFound(http)
@@ -205,6 +229,9 @@ impl Error {
         match self {
             Self::InvalidConfig { .. } => None,
             Self::Transport { .. } => None,
+            Self::Timeout { .. } => None,
+            Self::Cancelled { .. } => None,
+            Self::CircuitOpen { .. } => None,
             Self::Decode { http, .. } => http.request_id(),
             Self::Auth(http)
             | Self::NotFound(http)
@@ -218,6 +245,9 @@ impl Error {
         match self {
             Self::InvalidConfig { message } => Some(message.as_str()),
             Self::Transport { .. } => None,
+            Self::Timeout { .. } => None,
+            Self::Cancelled { .. } => None,
+            Self::CircuitOpen { .. } => None,
             Self::Decode { http, .. } => http.message(),
             Self::Auth(http)
             | Self::NotFound(http)
@@ -231,6 +261,9 @@ impl Error {
         match self {
             Self::InvalidConfig { .. } => None,
             Self::Transport { .. } => None,
+            Self::Timeout { .. } => None,
+            Self::Cancelled { .. } => None,
+            Self::CircuitOpen { .. } => None,
             Self::Decode { http, .. } => http.body_snippet(),
             Self::Auth(http)
             | Self::NotFound(http)
@@ -240,6 +273,34 @@ impl Error {
         }
     }
 
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            Self::InvalidConfig { .. } => None,
+            Self::Transport { .. } => None,
+            Self::Timeout { .. } => None,
+            Self::Cancelled { .. } => None,
+            Self::CircuitOpen { .. } => None,
+            Self::Decode { http, .. } => http.retry_after(),
+            Self::Auth(http)
+            | Self::NotFound(http)
+            | Self::Conflict(http)
+            | Self::RateLimited(http)
+            | Self::Api(http) => http.retry_after(),
+        }
+    }
+
+    pub fn is_timeout(&self) -> bool {
+        matches!(self, Self::Timeout { .. })
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        matches!(self, Self::Cancelled { .. })
+    }
+
+    pub fn is_circuit_open(&self) -> bool {
+        matches!(self, Self::CircuitOpen { .. })
+    }
+
     pub fn is_auth_error(&self) -> bool {
         matches!(self, Self::Auth(_))
     }
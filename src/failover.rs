@@ -0,0 +1,306 @@
+//! Failover across several Grafana replicas.
+//!
+//! A [`Failover`] holds the base URLs of replicas serving the same Grafana. Each attempt of a
+//! call goes to one replica picked by the [`FailoverStrategy`]; when an attempt fails with a
+//! transport error or a `502`/`503`/`504`, the replica is marked unhealthy and the retry goes to
+//! the next one. Unhealthy replicas are skipped until `cooldown` has passed since their last
+//! failure, or until every replica is unhealthy.
+//!
+//! Failover rides on retries: a call only moves to another replica when the retry policy
+//! allows another attempt.
+//!
+//! With a [`CircuitBreaker`](crate::circuit_breaker::CircuitBreaker) on the client, a failure
+//! that marks a replica unhealthy only counts towards opening the circuit when no other replica
+//! is healthy; one dead replica does not cut the client off from the others.
+
+use std::{
+    sync::{
+        Arc, Mutex, PoisonError,
+        atomic::{AtomicUsize, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+use http::StatusCode;
+use url::Url;
+
+use crate::{Error, Result, util};
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum FailoverStrategy {
+    /// Spreads calls across healthy replicas in turn.
+    RoundRobin,
+    /// Sends everything to the first healthy replica in the order given.
+    PrimarySecondary,
+    /// Prefers replicas that never failed, then the one whose last failure is oldest.
+    LeastRecentlyFailed,
+}
+
+#[derive(Clone)]
+pub struct Failover {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    strategy: FailoverStrategy,
+    cooldown: Duration,
+    endpoints: Vec<Endpoint>,
+    next: AtomicUsize,
+    state: Mutex<Vec<State>>,
+}
+
+struct Endpoint {
+    base_url: Url,
+    api_base_url: Url,
+}
+
+#[derive(Clone, Copy, Default)]
+struct State {
+    consecutive_failures: u32,
+    last_failure: Option<Instant>,
+    last_success: Option<Instant>,
+}
+
+/// Snapshot of one replica's health.
+#[derive(Clone, Debug)]
+pub struct EndpointHealth {
+    base_url: Url,
+    consecutive_failures: u32,
+    last_failure: Option<Instant>,
+    last_success: Option<Instant>,
+}
+
+impl EndpointHealth {
+    pub fn base_url(&self) -> &Url {
+        &self.base_url
+    }
+
+    /// `true` until the replica fails, and again after its next success.
+    pub fn is_healthy(&self) -> bool {
+        self.consecutive_failures == 0
+    }
+
+    pub fn consecutive_failures(&self) -> u32 {
+        self.consecutive_failures
+    }
+
+    pub fn last_failure(&self) -> Option<Instant> {
+        self.last_failure
+    }
+
+    pub fn last_success(&self) -> Option<Instant> {
+        self.last_success
+    }
+}
+
+impl Failover {
+    /// Replicas in priority order; the first one also names the client's base URL.
+    pub fn new<I, S>(base_urls: I, strategy: FailoverStrategy, cooldown: Duration) -> Result<Self>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let endpoints = base_urls
+            .into_iter()
+            .map(|base_url| {
+                let base_url = util::url::parse_base_url(base_url.as_ref())?;
+                let api_base_url = util::url::api_base_url(&base_url)?;
+                Ok(Endpoint {
+                    base_url,
+                    api_base_url,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        if endpoints.is_empty() {
+            return Err(Error::invalid_config(
+                "failover needs at least one base URL",
+            ));
+        }
+
+        Ok(Self {
+            inner: Arc::new(Inner {
+                strategy,
+                cooldown,
+                state: Mutex::new(vec![State::default(); endpoints.len()]),
+                endpoints,
+                next: AtomicUsize::new(0),
+            }),
+        })
+    }
+
+    pub fn strategy(&self) -> FailoverStrategy {
+        self.inner.strategy
+    }
+
+    pub fn cooldown(&self) -> Duration {
+        self.inner.cooldown
+    }
+
+    /// Health of every replica, in the order they were given.
+    pub fn endpoints(&self) -> Vec<EndpointHealth> {
+        let state = self.lock();
+        self.inner
+            .endpoints
+            .iter()
+            .zip(state.iter())
+            .map(|(endpoint, state)| EndpointHealth {
+                base_url: endpoint.base_url.clone(),
+                consecutive_failures: state.consecutive_failures,
+                last_failure: state.last_failure,
+                last_success: state.last_success,
+            })
+            .collect()
+    }
+
+    /// Marks every replica healthy again.
+    pub fn reset(&self) {
+        self.lock().fill(State::default());
+    }
+
+    pub(crate) fn primary(&self) -> &Url {
+        &self.inner.endpoints[0].base_url
+    }
+
+    /// Starts routing one call.
+    pub(crate) fn route(&self) -> Route<'_> {
+        let start = match self.inner.strategy {
+            FailoverStrategy::RoundRobin => {
+                self.inner.next.fetch_add(1, Ordering::Relaxed) % self.inner.endpoints.len()
+            }
+            FailoverStrategy::PrimarySecondary | FailoverStrategy::LeastRecentlyFailed => 0,
+        };
+        Route {
+            failover: self,
+            start,
+            tried: Vec::new(),
+            current: None,
+        }
+    }
+
+    fn pick(&self, start: usize, tried: &[usize]) -> usize {
+        let count = self.inner.endpoints.len();
+        let now = Instant::now();
+        let state = self.lock();
+
+        let mut candidates: Vec<usize> = (0..count)
+            .map(|offset| (start + offset) % count)
+            .filter(|index| !tried.contains(index))
+            .collect();
+        if candidates.is_empty() {
+            candidates = (0..count).map(|offset| (start + offset) % count).collect();
+        }
+        let available = |index: &usize| self.is_available(&state[*index], now);
+        if candidates.iter().any(available) {
+            candidates.retain(available);
+        }
+
+        match self.inner.strategy {
+            FailoverStrategy::RoundRobin | FailoverStrategy::PrimarySecondary => candidates[0],
+            FailoverStrategy::LeastRecentlyFailed => candidates
+                .into_iter()
+                .min_by_key(|index| state[*index].last_failure)
+                .unwrap_or(start),
+        }
+    }
+
+    /// Whether replica `index` is marked unhealthy and another replica can take its calls.
+    fn routes_around(&self, index: usize) -> bool {
+        let now = Instant::now();
+        let state = self.lock();
+        state[index].consecutive_failures > 0
+            && state
+                .iter()
+                .enumerate()
+                .any(|(other, state)| other != index && self.is_available(state, now))
+    }
+
+    fn is_available(&self, state: &State, now: Instant) -> bool {
+        state.consecutive_failures == 0
+            || state
+                .last_failure
+                .is_none_or(|at| now.saturating_duration_since(at) >= self.inner.cooldown)
+    }
+
+    fn record(&self, index: usize, failed: bool) {
+        let now = Instant::now();
+        let mut state = self.lock();
+        let state = &mut state[index];
+        if failed {
+            #[cfg(feature = "tracing")]
+            tracing::warn!(
+                endpoint = %self.inner.endpoints[index].base_url,
+                "failover endpoint marked unhealthy"
+            );
+
+            state.consecutive_failures = state.consecutive_failures.saturating_add(1);
+            state.last_failure = Some(now);
+        } else {
+            state.consecutive_failures = 0;
+            state.last_success = Some(now);
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<State>> {
+        self.inner
+            .state
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl std::fmt::Debug for Failover {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Failover")
+            .field("strategy", &self.inner.strategy)
+            .field("cooldown", &self.inner.cooldown)
+            .field("endpoints", &self.endpoints())
+            .finish()
+    }
+}
+
+/// Replica selection for the attempts of one call.
+pub(crate) struct Route<'a> {
+    failover: &'a Failover,
+    start: usize,
+    tried: Vec<usize>,
+    current: Option<usize>,
+}
+
+impl<'a> Route<'a> {
+    /// API base URL for the next attempt.
+    pub(crate) fn next(&mut self) -> &'a Url {
+        if self.tried.len() == self.failover.inner.endpoints.len() {
+            self.tried.clear();
+        }
+        let index = self.failover.pick(self.start, &self.tried);
+        self.tried.push(index);
+        self.current = Some(index);
+        &self.failover.inner.endpoints[index].api_base_url
+    }
+
+    /// A response counts against the replica only for `502`, `503` and `504`.
+    pub(crate) fn record_status(&self, status: StatusCode) {
+        let failed = matches!(
+            status,
+            StatusCode::BAD_GATEWAY | StatusCode::SERVICE_UNAVAILABLE | StatusCode::GATEWAY_TIMEOUT
+        );
+        self.record(failed);
+    }
+
+    pub(crate) fn record_transport_error(&self) {
+        self.record(true);
+    }
+
+    /// Whether the failure just recorded stays with the current replica because another one is
+    /// healthy; the circuit breaker then leaves it out of its count.
+    pub(crate) fn routes_around(&self) -> bool {
+        self.current
+            .is_some_and(|index| self.failover.routes_around(index))
+    }
+
+    fn record(&self, failed: bool) {
+        if let Some(index) = self.current {
+            self.failover.record(index, failed);
+        }
+    }
+}
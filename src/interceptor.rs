@@ -0,0 +1,52 @@
+//! Request/response interceptors.
+//!
+//! Interceptors see every attempt the transport makes, including retries. They run in
+//! registration order before an attempt and in reverse order after it, so the first
+//! interceptor registered is the outermost layer.
+
+use std::sync::Arc;
+
+use http::request::Parts;
+
+use crate::{Error, ResponseBytes, Result};
+
+pub trait Interceptor: Send + Sync + 'static {
+    /// Called before each attempt, after default headers and auth have been applied.
+    ///
+    /// Returning an error aborts the call without sending the request.
+    fn on_request(&self, request: &mut Parts) -> Result<()> {
+        let _ = request;
+        Ok(())
+    }
+
+    /// Called after an attempt that produced a successful response.
+    ///
+    /// For streamed calls (`ResponseStream` and the blocking `ResponseReader`) this runs before
+    /// the body is read, so `response` carries the status and headers with an empty body; use
+    /// `Content-Length`, when present, for the size.
+    fn on_response(&self, request: &Parts, response: &ResponseBytes) {
+        let _ = (request, response);
+    }
+
+    /// Called after an attempt that failed, whether or not it will be retried.
+    fn on_error(&self, request: &Parts, error: &Error) {
+        let _ = (request, error);
+    }
+}
+
+impl<T> Interceptor for Arc<T>
+where
+    T: Interceptor + ?Sized,
+{
+    fn on_request(&self, request: &mut Parts) -> Result<()> {
+        (**self).on_request(request)
+    }
+
+    fn on_response(&self, request: &Parts, response: &ResponseBytes) {
+        (**self).on_response(request, response);
+    }
+
+    fn on_error(&self, request: &Parts, error: &Error) {
+        (**self).on_error(request, error);
+    }
+}
@@ -37,30 +37,64 @@
 #[cfg(all(feature = "rustls", feature = "native-tls", not(feature = "multi-tls")))]
 compile_error!("Enable only one of: rustls, native-tls");
 
-#[cfg(not(any(feature = "async", feature = "blocking")))]
-compile_error!("Enable at least one of: async, blocking");
+#[cfg(not(any(feature = "async", feature = "blocking-core")))]
+compile_error!("Enable at least one of: async, blocking, blocking-core");
 
-#[cfg(any(feature = "async", feature = "blocking"))]
+#[cfg(any(feature = "async", feature = "blocking-core"))]
 pub mod api;
 pub mod auth;
-#[cfg(any(feature = "async", feature = "blocking"))]
+#[cfg(all(
+    feature = "cassette",
+    any(feature = "async", feature = "blocking-core")
+))]
+pub mod cassette;
+#[cfg(any(feature = "async", feature = "blocking-core"))]
+pub mod circuit_breaker;
+#[cfg(any(feature = "async", feature = "blocking-core"))]
 pub mod client;
+#[cfg(any(feature = "async", feature = "blocking-core"))]
+pub mod config;
+pub mod credentials;
 pub mod error;
+#[cfg(any(feature = "async", feature = "blocking-core"))]
+pub mod failover;
+pub mod interceptor;
+pub mod oauth2;
+#[cfg(feature = "prometheus-rules")]
+pub mod prometheus_rules;
+#[cfg(any(feature = "async", feature = "blocking"))]
+pub mod proxy;
+#[cfg(any(feature = "async", feature = "blocking-core"))]
+pub mod rate_limit;
 pub mod request_options;
 pub mod response;
+pub mod retry;
+#[cfg(feature = "testing")]
+pub mod testing;
+#[cfg(any(feature = "async", feature = "blocking-core"))]
+pub mod transport;
 pub mod types;
 
-#[cfg(any(feature = "async", feature = "blocking"))]
-mod transport;
 mod util;
 
 pub use auth::Auth;
-#[cfg(feature = "blocking")]
+#[cfg(feature = "blocking-core")]
 pub use client::BlockingClient;
 #[cfg(feature = "async")]
 pub use client::Client;
+pub use credentials::CredentialProvider;
 pub use error::Error;
+pub use interceptor::Interceptor;
+#[cfg(any(feature = "async", feature = "blocking"))]
+pub use proxy::Proxy;
 pub use request_options::RequestOptions;
 pub use response::ResponseBytes;
+#[cfg(feature = "blocking-core")]
+pub use response::ResponseReader;
+#[cfg(feature = "async")]
+pub use response::ResponseStream;
+pub use retry::RetryPolicy;
+#[cfg(feature = "async")]
+pub use tokio_util::sync::CancellationToken;
 
 pub type Result<T> = std::result::Result<T, Error>;
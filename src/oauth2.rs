@@ -0,0 +1,207 @@
+//! OAuth2 client-credentials tokens for Grafana behind an OAuth2-protected gateway.
+//!
+//! [`ClientCredentials`] fetches an access token from the token endpoint through the client's own
+//! transport and sends it as a bearer token. The token is cached until it expires. Within
+//! [`refresh_before`](ClientCredentials::refresh_before) of expiry one request fetches a new token
+//! while the others keep using the current one; without a usable token, one request fetches it
+//! and the others wait for its result. A `401` from Grafana drops the cached token and the request
+//! is retried once with a new one.
+
+use std::{
+    fmt,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use serde::Deserialize;
+use url::Url;
+
+use crate::{
+    Error, Result,
+    util::flight::{Cached, Flight},
+};
+
+const DEFAULT_REFRESH_BEFORE: Duration = Duration::from_secs(60);
+
+#[derive(Clone)]
+pub struct ClientCredentials {
+    token_url: Url,
+    client_id: String,
+    client_secret: String,
+    scopes: Vec<String>,
+    params: Vec<(String, String)>,
+    credentials_in_header: bool,
+    refresh_before: Duration,
+    flight: Arc<Flight<Token>>,
+}
+
+#[derive(Clone)]
+pub(crate) struct Token {
+    access_token: String,
+    expires_at: Option<Instant>,
+}
+
+impl ClientCredentials {
+    pub fn new(
+        token_url: impl AsRef<str>,
+        client_id: impl Into<String>,
+        client_secret: impl Into<String>,
+    ) -> Result<Self> {
+        let token_url = Url::parse(token_url.as_ref())
+            .map_err(|e| Error::invalid_config(format!("invalid OAuth2 token URL: {e}")))?;
+        if !matches!(token_url.scheme(), "http" | "https") {
+            return Err(Error::invalid_config(
+                "OAuth2 token URL scheme must be http or https",
+            ));
+        }
+        Ok(Self {
+            token_url,
+            client_id: client_id.into(),
+            client_secret: client_secret.into(),
+            scopes: Vec::new(),
+            params: Vec::new(),
+            credentials_in_header: false,
+            refresh_before: DEFAULT_REFRESH_BEFORE,
+            flight: Arc::default(),
+        })
+    }
+
+    /// Adds a scope; scopes are sent space-separated.
+    pub fn scope(mut self, scope: impl Into<String>) -> Self {
+        self.scopes.push(scope.into());
+        self
+    }
+
+    /// An extra form parameter for the token request, such as `audience`.
+    pub fn param(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.params.push((name.into(), value.into()));
+        self
+    }
+
+    /// Sends the client id and secret as HTTP basic auth (`client_secret_basic`) instead of in
+    /// the form body.
+    pub fn credentials_in_header(mut self) -> Self {
+        self.credentials_in_header = true;
+        self
+    }
+
+    /// How long before expiry a new token is fetched. Defaults to one minute.
+    pub fn refresh_before(mut self, refresh_before: Duration) -> Self {
+        self.refresh_before = refresh_before;
+        self
+    }
+
+    pub fn token_url(&self) -> &Url {
+        &self.token_url
+    }
+
+    pub fn client_id(&self) -> &str {
+        &self.client_id
+    }
+
+    /// Drops the cached token so the next request fetches a new one.
+    pub fn clear(&self) {
+        self.flight.lock().value = None;
+    }
+
+    /// The cached token for the next attempt, a claim to fetch a new one, or the fetch to wait
+    /// for.
+    pub(crate) fn cached(&self) -> Cached<'_, Token> {
+        let now = Instant::now();
+        let mut state = self.flight.lock();
+        let token = state
+            .value
+            .clone()
+            .filter(|token| token.expires_at.is_none_or(|at| now < at));
+        let due = token.as_ref().is_none_or(|token| {
+            token
+                .expires_at
+                .is_some_and(|at| at.saturating_duration_since(now) <= self.refresh_before)
+        });
+        match token {
+            Some(token) if !due || state.is_fetching() => Cached::Fresh(token.access_token),
+            current => self
+                .flight
+                .fetch_or_wait(&mut state, current.map(|token| token.access_token)),
+        }
+    }
+
+    pub(crate) fn flight(&self) -> &Flight<Token> {
+        &self.flight
+    }
+
+    /// Form body of the token request.
+    pub(crate) fn token_form(&self) -> Result<String> {
+        let scope = self.scopes.join(" ");
+        let mut form: Vec<(&str, &str)> = vec![("grant_type", "client_credentials")];
+        if !self.credentials_in_header {
+            form.push(("client_id", &self.client_id));
+            form.push(("client_secret", &self.client_secret));
+        }
+        if !scope.is_empty() {
+            form.push(("scope", &scope));
+        }
+        form.extend(
+            self.params
+                .iter()
+                .map(|(name, value)| (name.as_str(), value.as_str())),
+        );
+        serde_urlencoded::to_string(form)
+            .map_err(|e| Error::invalid_config(format!("invalid OAuth2 parameters: {e}")))
+    }
+
+    pub(crate) fn basic_credentials(&self) -> Option<(&str, &str)> {
+        self.credentials_in_header
+            .then_some((self.client_id.as_str(), self.client_secret.as_str()))
+    }
+
+    /// Caches a token fetched at `requested_at` and returns it.
+    pub(crate) fn store(&self, response: TokenResponse, requested_at: Instant) -> String {
+        let expires_at = response
+            .expires_in
+            .map(|secs| requested_at + Duration::from_secs(secs));
+        self.flight.lock().value = Some(Token {
+            access_token: response.access_token.clone(),
+            expires_at,
+        });
+        response.access_token
+    }
+}
+
+impl fmt::Debug for ClientCredentials {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ClientCredentials")
+            .field("token_url", &self.token_url.as_str())
+            .field("client_id", &self.client_id)
+            .field("client_secret", &"<redacted>")
+            .field("scopes", &self.scopes)
+            .field("refresh_before", &self.refresh_before)
+            .finish_non_exhaustive()
+    }
+}
+
+#[derive(Deserialize)]
+pub(crate) struct TokenResponse {
+    access_token: String,
+    #[serde(default, deserialize_with = "seconds")]
+    expires_in: Option<u64>,
+}
+
+/// Some providers send `expires_in` as a string.
+fn seconds<'de, D>(deserializer: D) -> std::result::Result<Option<u64>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Seconds {
+        Number(u64),
+        Text(String),
+    }
+
+    Ok(match Option::<Seconds>::deserialize(deserializer)? {
+        Some(Seconds::Number(secs)) => Some(secs),
+        Some(Seconds::Text(secs)) => secs.trim().parse().ok(),
+        None => None,
+    })
+}
@@ -0,0 +1,531 @@
+//! Offline conversion of Prometheus/Mimir rule files into Grafana-managed alert rules.
+//!
+//! [`RuleFile`] reads the `groups:` YAML that Prometheus, Mimir and Cortex load, and a
+//! [`Converter`] turns it into [`AlertRuleGroup`]s for one Grafana folder and datasource, ready
+//! to review, commit or save with `AlertingService::put_alert_rule_group`. Nothing is sent to
+//! Grafana here.
+//!
+//! Every alerting rule becomes a query on the datasource plus the expressions Grafana itself uses
+//! for converted rules: the rule fires for every series the query returns, as in Prometheus.
+//! Recording rules write their result back to the datasource. Constructs without a Grafana
+//! equivalent are listed in [`Conversion::issues`].
+
+use std::{collections::BTreeMap, fmt, time::Duration};
+
+use serde::{Deserialize, Deserializer};
+use yaml_rust2::{Yaml, YamlLoader};
+
+use crate::{
+    Error, Result,
+    types::{
+        AlertQuery, AlertRuleGroup, ExecErrState, NoDataState, ProvisionedAlertRule, RecordingRule,
+        RelativeTimeRange,
+    },
+};
+
+const QUERY_REF_ID: &str = "query";
+const MATH_REF_ID: &str = "prometheus_math";
+const THRESHOLD_REF_ID: &str = "threshold";
+const EXPRESSION_DATASOURCE: &str = "__expr__";
+/// Grafana's scheduler evaluates rules at multiples of this interval.
+const BASE_INTERVAL_SECS: u64 = 10;
+
+/// A Prometheus rule file; a Mimir or Cortex namespace file has the same layout.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+pub struct RuleFile {
+    #[serde(default)]
+    pub namespace: Option<String>,
+    #[serde(default)]
+    pub groups: Vec<PrometheusRuleGroup>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+pub struct PrometheusRuleGroup {
+    pub name: String,
+    #[serde(default)]
+    pub interval: Option<String>,
+    #[serde(default)]
+    pub query_offset: Option<String>,
+    /// Alert or series limit per rule; Grafana has none.
+    #[serde(default)]
+    pub limit: Option<u64>,
+    /// Labels added to every rule of the group.
+    #[serde(default, deserialize_with = "string_map")]
+    pub labels: BTreeMap<String, String>,
+    /// Mimir federated rule groups; Grafana has none.
+    #[serde(default)]
+    pub source_tenants: Vec<String>,
+    #[serde(default)]
+    pub rules: Vec<PrometheusRule>,
+}
+
+/// An alerting rule (`alert`) or a recording rule (`record`).
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+pub struct PrometheusRule {
+    #[serde(default)]
+    pub alert: Option<String>,
+    #[serde(default)]
+    pub record: Option<String>,
+    pub expr: String,
+    #[serde(default, rename = "for")]
+    pub pending_period: Option<String>,
+    #[serde(default)]
+    pub keep_firing_for: Option<String>,
+    #[serde(default, deserialize_with = "string_map")]
+    pub labels: BTreeMap<String, String>,
+    #[serde(default, deserialize_with = "string_map")]
+    pub annotations: BTreeMap<String, String>,
+}
+
+impl RuleFile {
+    /// Parses a rule file; the groups of every document in a multi-document file are kept.
+    pub fn from_yaml(yaml: &str) -> Result<Self> {
+        let documents = YamlLoader::load_from_str(yaml)
+            .map_err(|e| Error::invalid_config(format!("invalid rule file: {e}")))?;
+        let mut file = Self::default();
+        for document in documents {
+            if matches!(document, Yaml::Null) {
+                continue;
+            }
+            let next: Self = serde_json::from_value(yaml_to_json(document)?)
+                .map_err(|e| Error::invalid_config(format!("invalid rule file: {e}")))?;
+            file.namespace = file.namespace.or(next.namespace);
+            file.groups.extend(next.groups);
+        }
+        Ok(file)
+    }
+}
+
+/// Settings shared by every converted rule.
+#[derive(Clone, Debug)]
+pub struct Converter {
+    folder_uid: String,
+    datasource_uid: String,
+    datasource_type: String,
+    target_datasource_uid: Option<String>,
+    default_interval: Duration,
+    query_range: Duration,
+    no_data_state: NoDataState,
+    exec_err_state: ExecErrState,
+}
+
+impl Converter {
+    /// Converts into rule groups of `folder_uid` that query the Prometheus datasource
+    /// `datasource_uid`.
+    pub fn new(folder_uid: impl Into<String>, datasource_uid: impl Into<String>) -> Self {
+        Self {
+            folder_uid: folder_uid.into(),
+            datasource_uid: datasource_uid.into(),
+            datasource_type: "prometheus".to_owned(),
+            target_datasource_uid: None,
+            default_interval: Duration::from_secs(60),
+            query_range: Duration::from_secs(600),
+            // A Prometheus rule that returns nothing is simply not firing.
+            no_data_state: NoDataState::Ok,
+            exec_err_state: ExecErrState::Error,
+        }
+    }
+
+    /// Type of the queried datasource, e.g. `loki` for Loki rule files. Defaults to
+    /// `prometheus`.
+    pub fn datasource_type(mut self, datasource_type: impl Into<String>) -> Self {
+        self.datasource_type = datasource_type.into();
+        self
+    }
+
+    /// Datasource recording rules write to. Defaults to the queried one.
+    pub fn target_datasource_uid(mut self, uid: impl Into<String>) -> Self {
+        self.target_datasource_uid = Some(uid.into());
+        self
+    }
+
+    /// Evaluation interval of groups without `interval`. Defaults to one minute.
+    pub fn default_interval(mut self, interval: Duration) -> Self {
+        self.default_interval = interval;
+        self
+    }
+
+    /// How far back each query looks. Defaults to ten minutes.
+    pub fn query_range(mut self, range: Duration) -> Self {
+        self.query_range = range;
+        self
+    }
+
+    pub fn no_data_state(mut self, state: NoDataState) -> Self {
+        self.no_data_state = state;
+        self
+    }
+
+    pub fn exec_err_state(mut self, state: ExecErrState) -> Self {
+        self.exec_err_state = state;
+        self
+    }
+
+    pub fn convert(&self, file: &RuleFile) -> Conversion {
+        let mut conversion = Conversion::default();
+        for group in &file.groups {
+            let converted = self.convert_group(group, &mut conversion.issues);
+            conversion.groups.push(converted);
+        }
+        conversion
+    }
+
+    fn convert_group(
+        &self,
+        group: &PrometheusRuleGroup,
+        issues: &mut Vec<ConversionIssue>,
+    ) -> AlertRuleGroup {
+        let mut issue = |rule: Option<&str>, message: String| {
+            issues.push(ConversionIssue {
+                group: group.name.clone(),
+                rule: rule.map(str::to_owned),
+                message,
+            });
+        };
+
+        let interval = match group.interval.as_deref() {
+            None => self.default_interval,
+            Some(interval) => parse_duration(interval).unwrap_or_else(|| {
+                issue(
+                    None,
+                    format!("invalid interval {interval:?}; using the default"),
+                );
+                self.default_interval
+            }),
+        };
+        let mut secs = interval.as_secs() + u64::from(interval.subsec_nanos() > 0);
+        if secs == 0 || secs % BASE_INTERVAL_SECS != 0 {
+            let rounded = secs.div_ceil(BASE_INTERVAL_SECS).max(1) * BASE_INTERVAL_SECS;
+            issue(
+                None,
+                format!(
+                    "interval {interval:?} is not a multiple of {BASE_INTERVAL_SECS}s; \
+                     rounded up to {rounded}s"
+                ),
+            );
+            secs = rounded;
+        }
+        let offset = match group.query_offset.as_deref() {
+            None => Duration::ZERO,
+            Some(offset) => parse_duration(offset).unwrap_or_else(|| {
+                issue(None, format!("invalid query_offset {offset:?}; ignored"));
+                Duration::ZERO
+            }),
+        };
+        if let Some(limit) = group.limit.filter(|&limit| limit > 0) {
+            issue(None, format!("limit {limit} is not supported; ignored"));
+        }
+        if !group.source_tenants.is_empty() {
+            issue(
+                None,
+                "source_tenants (federated rule groups) are not supported; ignored".to_owned(),
+            );
+        }
+
+        let mut converted = AlertRuleGroup::new(
+            &group.name,
+            &self.folder_uid,
+            i64::try_from(secs).unwrap_or(i64::MAX),
+        );
+        let time_range = RelativeTimeRange {
+            from: i64::try_from((self.query_range + offset).as_secs()).unwrap_or(i64::MAX),
+            to: i64::try_from(offset.as_secs()).unwrap_or(i64::MAX),
+        };
+        for rule in &group.rules {
+            let name = rule.alert.as_deref().or(rule.record.as_deref());
+            match self.convert_rule(group, rule, time_range) {
+                Ok(converted_rule) => converted.rules.push(converted_rule),
+                Err(message) => issue(name, format!("{message}; rule skipped")),
+            }
+        }
+        for (title, renamed) in unique_titles(&mut converted.rules) {
+            issue(
+                Some(&title),
+                format!("title is used by several rules of the group; renamed to {renamed:?}"),
+            );
+        }
+        converted
+    }
+
+    fn convert_rule(
+        &self,
+        group: &PrometheusRuleGroup,
+        rule: &PrometheusRule,
+        time_range: RelativeTimeRange,
+    ) -> std::result::Result<ProvisionedAlertRule, String> {
+        if rule.expr.trim().is_empty() {
+            return Err("empty expr".to_owned());
+        }
+        let mut query = AlertQuery::new(
+            QUERY_REF_ID,
+            &self.datasource_uid,
+            serde_json::json!({
+                "datasource": {"type": self.datasource_type, "uid": self.datasource_uid},
+                "expr": rule.expr,
+                "instant": true,
+                "range": false,
+                "refId": QUERY_REF_ID,
+            }),
+        );
+        query.relative_time_range = time_range;
+
+        let (title, data, condition, record) = match (&rule.alert, &rule.record) {
+            (Some(alert), None) => (
+                alert,
+                vec![query, math_node(), threshold_node()],
+                THRESHOLD_REF_ID,
+                None,
+            ),
+            (None, Some(record)) => {
+                if rule.pending_period.is_some() || rule.keep_firing_for.is_some() {
+                    return Err("recording rule with for or keep_firing_for".to_owned());
+                }
+                let target = self
+                    .target_datasource_uid
+                    .as_ref()
+                    .unwrap_or(&self.datasource_uid);
+                let recording = RecordingRule {
+                    metric: record.clone(),
+                    from: QUERY_REF_ID.to_owned(),
+                    target_datasource_uid: Some(target.clone()),
+                };
+                (record, vec![query], QUERY_REF_ID, Some(recording))
+            }
+            (Some(_), Some(_)) => return Err("both alert and record are set".to_owned()),
+            (None, None) => return Err("neither alert nor record is set".to_owned()),
+        };
+
+        let mut converted =
+            ProvisionedAlertRule::new(&self.folder_uid, &group.name, title, condition, data);
+        converted.no_data_state = self.no_data_state;
+        converted.exec_err_state = self.exec_err_state;
+        converted.pending_period = checked_duration("for", rule.pending_period.as_deref())?;
+        converted.keep_firing_for =
+            checked_duration("keep_firing_for", rule.keep_firing_for.as_deref())?;
+        converted.labels = group.labels.clone();
+        converted.labels.extend(rule.labels.clone());
+        converted.annotations = rule.annotations.clone();
+        converted.record = record;
+        Ok(converted)
+    }
+}
+
+/// Grafana rejects a group with two rules of the same title, yet Prometheus files often repeat
+/// an alert once per severity. Each such rule gets the labels that tell the copies apart as a
+/// suffix, e.g. `HighLatency (severity=critical)`, or its position when the labels are the same.
+/// Returns the old and new title of every renamed rule.
+fn unique_titles(rules: &mut [ProvisionedAlertRule]) -> Vec<(String, String)> {
+    let mut by_title = BTreeMap::<String, Vec<usize>>::new();
+    for (index, rule) in rules.iter().enumerate() {
+        by_title.entry(rule.title.clone()).or_default().push(index);
+    }
+    let mut renamed = Vec::new();
+    for (title, indices) in by_title {
+        if indices.len() < 2 {
+            continue;
+        }
+        let mut keys: Vec<&String> = indices
+            .iter()
+            .flat_map(|&index| rules[index].labels.keys())
+            .collect();
+        keys.sort();
+        keys.dedup();
+        keys.retain(|&key| {
+            let first = rules[indices[0]].labels.get(key);
+            indices
+                .iter()
+                .any(|&index| rules[index].labels.get(key) != first)
+        });
+        let mut titles: Vec<String> = indices
+            .iter()
+            .map(|&index| {
+                let labels = &rules[index].labels;
+                let suffix = keys
+                    .iter()
+                    .filter_map(|&key| Some(format!("{key}={}", labels.get(key)?)))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                format!("{title} ({suffix})")
+            })
+            .collect();
+        let mut sorted: Vec<&String> = titles.iter().collect();
+        sorted.sort();
+        sorted.dedup();
+        if sorted.len() < titles.len() {
+            titles = (1..=indices.len())
+                .map(|position| format!("{title} ({position})"))
+                .collect();
+        }
+        for (&index, new_title) in indices.iter().zip(titles) {
+            rules[index].title.clone_from(&new_title);
+            renamed.push((index, title.clone(), new_title));
+        }
+    }
+    renamed.sort_by_key(|&(index, ..)| index);
+    renamed
+        .into_iter()
+        .map(|(_, title, new_title)| (title, new_title))
+        .collect()
+}
+
+fn math_node() -> AlertQuery {
+    AlertQuery::new(
+        MATH_REF_ID,
+        EXPRESSION_DATASOURCE,
+        serde_json::json!({
+            "datasource": {"type": EXPRESSION_DATASOURCE, "uid": EXPRESSION_DATASOURCE},
+            "expression": format!(
+                "is_number(${QUERY_REF_ID}) || is_nan(${QUERY_REF_ID}) || is_inf(${QUERY_REF_ID})"
+            ),
+            "refId": MATH_REF_ID,
+            "type": "math",
+        }),
+    )
+}
+
+fn threshold_node() -> AlertQuery {
+    AlertQuery::new(
+        THRESHOLD_REF_ID,
+        EXPRESSION_DATASOURCE,
+        serde_json::json!({
+            "datasource": {"type": EXPRESSION_DATASOURCE, "uid": EXPRESSION_DATASOURCE},
+            "conditions": [{"evaluator": {"type": "gt", "params": [0]}}],
+            "expression": MATH_REF_ID,
+            "refId": THRESHOLD_REF_ID,
+            "type": "threshold",
+        }),
+    )
+}
+
+/// Validates a Prometheus duration and keeps it as written; Grafana accepts the same syntax.
+fn checked_duration(
+    field: &str,
+    value: Option<&str>,
+) -> std::result::Result<Option<String>, String> {
+    match value {
+        Some(value) if parse_duration(value).is_none() => {
+            Err(format!("invalid {field} duration {value:?}"))
+        }
+        value => Ok(value.map(str::to_owned)),
+    }
+}
+
+/// Parses a Prometheus duration such as `90s`, `1h30m` or `2w`.
+fn parse_duration(value: &str) -> Option<Duration> {
+    const UNITS: [(&str, u64); 7] = [
+        ("ms", 1),
+        ("s", 1_000),
+        ("m", 60_000),
+        ("h", 3_600_000),
+        ("d", 86_400_000),
+        ("w", 604_800_000),
+        ("y", 31_536_000_000),
+    ];
+
+    let mut rest = value.trim();
+    if rest == "0" {
+        return Some(Duration::ZERO);
+    }
+    if rest.is_empty() {
+        return None;
+    }
+    let mut millis = 0u64;
+    while !rest.is_empty() {
+        let digits = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        let number: u64 = rest[..digits].parse().ok()?;
+        rest = &rest[digits..];
+        let (unit, unit_millis) = UNITS.iter().find(|(unit, _)| rest.starts_with(unit))?;
+        rest = &rest[unit.len()..];
+        millis = millis.checked_add(number.checked_mul(*unit_millis)?)?;
+    }
+    Some(Duration::from_millis(millis))
+}
+
+/// Converted groups, in file order, and what could not be converted.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Conversion {
+    pub groups: Vec<AlertRuleGroup>,
+    pub issues: Vec<ConversionIssue>,
+}
+
+impl Conversion {
+    /// Whether everything was converted as written.
+    pub fn is_lossless(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ConversionIssue {
+    pub group: String,
+    /// Alert or record name; unset for group-level issues.
+    pub rule: Option<String>,
+    pub message: String,
+}
+
+impl fmt::Display for ConversionIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.rule {
+            Some(rule) => write!(f, "group {:?}, rule {rule:?}: {}", self.group, self.message),
+            None => write!(f, "group {:?}: {}", self.group, self.message),
+        }
+    }
+}
+
+fn yaml_to_json(yaml: Yaml) -> Result<serde_json::Value> {
+    Ok(match yaml {
+        Yaml::Null => serde_json::Value::Null,
+        Yaml::Boolean(value) => value.into(),
+        Yaml::Integer(value) => value.into(),
+        Yaml::Real(value) => value
+            .parse::<f64>()
+            .ok()
+            .and_then(serde_json::Number::from_f64)
+            .map_or(serde_json::Value::String(value), serde_json::Value::Number),
+        Yaml::String(value) => value.into(),
+        Yaml::Array(items) => items.into_iter().map(yaml_to_json).collect::<Result<_>>()?,
+        Yaml::Hash(entries) => {
+            let mut map = serde_json::Map::with_capacity(entries.len());
+            for (key, value) in entries {
+                let key = match yaml_to_json(key)? {
+                    serde_json::Value::String(key) => key,
+                    key => key.to_string(),
+                };
+                map.insert(key, yaml_to_json(value)?);
+            }
+            serde_json::Value::Object(map)
+        }
+        Yaml::Alias(_) => {
+            return Err(Error::invalid_config(
+                "invalid rule file: YAML aliases are not supported",
+            ));
+        }
+        Yaml::BadValue => {
+            return Err(Error::invalid_config(
+                "invalid rule file: malformed YAML value",
+            ));
+        }
+    })
+}
+
+/// Label and annotation values may be written as YAML numbers or booleans.
+fn string_map<'de, D>(deserializer: D) -> std::result::Result<BTreeMap<String, String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let map = Option::<BTreeMap<String, serde_json::Value>>::deserialize(deserializer)?;
+    Ok(map
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(name, value)| {
+            let value = match value {
+                serde_json::Value::String(value) => value,
+                serde_json::Value::Null => String::new(),
+                value => value.to_string(),
+            };
+            (name, value)
+        })
+        .collect())
+}
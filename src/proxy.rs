@@ -0,0 +1,169 @@
+//! Outbound proxy configuration.
+//!
+//! A [`Proxy`] routes either plain HTTP requests, HTTPS requests, or both through an HTTP,
+//! HTTPS or SOCKS5 proxy. Credentials may be given inline in the URL or with
+//! [`Proxy::basic_auth`]; either way they are kept out of `Debug` output. Each proxy can carry a
+//! `NO_PROXY`-style bypass list.
+//!
+//! Environment variables (`HTTP_PROXY`, `HTTPS_PROXY`, `ALL_PROXY`, `NO_PROXY`) are ignored
+//! unless the client builder opts in with `proxy_from_env(true)`.
+
+use std::fmt;
+
+use url::Url;
+
+use crate::{Error, Result, util::url::percent_decode};
+
+#[derive(Clone)]
+pub struct Proxy {
+    scope: Scope,
+    url: Url,
+    credentials: Option<Credentials>,
+    no_proxy: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Scope {
+    Http,
+    Https,
+    All,
+}
+
+#[derive(Clone)]
+struct Credentials {
+    username: String,
+    password: String,
+}
+
+impl Proxy {
+    /// Proxies requests to `http://` URLs.
+    pub fn http(url: impl AsRef<str>) -> Result<Self> {
+        Self::new(Scope::Http, url.as_ref())
+    }
+
+    /// Proxies requests to `https://` URLs.
+    pub fn https(url: impl AsRef<str>) -> Result<Self> {
+        Self::new(Scope::Https, url.as_ref())
+    }
+
+    /// Proxies every request.
+    pub fn all(url: impl AsRef<str>) -> Result<Self> {
+        Self::new(Scope::All, url.as_ref())
+    }
+
+    fn new(scope: Scope, url: &str) -> Result<Self> {
+        // Like curl, a bare `host:port` means an HTTP proxy.
+        let url = if url.contains("://") {
+            Url::parse(url)
+        } else {
+            Url::parse(&format!("http://{url}"))
+        }
+        .map_err(|e| Error::invalid_config(format!("invalid proxy URL: {e}")))?;
+
+        if !matches!(url.scheme(), "http" | "https" | "socks5" | "socks5h") {
+            return Err(Error::invalid_config(
+                "proxy URL scheme must be http, https, socks5 or socks5h",
+            ));
+        }
+        if url.host_str().is_none() {
+            return Err(Error::invalid_config("proxy URL must include a host"));
+        }
+
+        let credentials = (!url.username().is_empty()).then(|| Credentials {
+            username: percent_decode(url.username()),
+            password: percent_decode(url.password().unwrap_or_default()),
+        });
+        let mut url = url;
+        let _ = url.set_username("");
+        let _ = url.set_password(None);
+
+        Ok(Self {
+            scope,
+            url,
+            credentials,
+            no_proxy: None,
+        })
+    }
+
+    pub fn basic_auth(mut self, username: impl Into<String>, password: impl Into<String>) -> Self {
+        self.credentials = Some(Credentials {
+            username: username.into(),
+            password: password.into(),
+        });
+        self
+    }
+
+    /// Hosts that bypass this proxy, in `NO_PROXY` syntax: comma-separated domains (matching
+    /// subdomains too), IP addresses, CIDR blocks, or `*` for everything.
+    pub fn no_proxy(mut self, hosts: impl Into<String>) -> Self {
+        self.no_proxy = Some(hosts.into());
+        self
+    }
+
+    /// Proxies configured through the environment. Lowercase names win over uppercase ones, and
+    /// `HTTP_PROXY` is skipped under CGI (`REQUEST_METHOD` set) where a client can inject it.
+    pub(crate) fn from_env() -> Result<Vec<Self>> {
+        let no_proxy = env_var("no_proxy");
+        let mut proxies = Vec::new();
+
+        let http_proxy = match std::env::var_os("REQUEST_METHOD") {
+            Some(_) => std::env::var("http_proxy").ok().filter(|v| !v.is_empty()),
+            None => env_var("http_proxy"),
+        };
+        for (scope, url) in [
+            (Scope::Http, http_proxy),
+            (Scope::Https, env_var("https_proxy")),
+            (Scope::All, env_var("all_proxy")),
+        ] {
+            if let Some(url) = url {
+                let mut proxy = Self::new(scope, &url)?;
+                proxy.no_proxy.clone_from(&no_proxy);
+                proxies.push(proxy);
+            }
+        }
+
+        Ok(proxies)
+    }
+
+    pub(crate) fn to_reqwest(&self) -> Result<reqwest::Proxy> {
+        let url = self.url.as_str();
+        let proxy = match self.scope {
+            Scope::Http => reqwest::Proxy::http(url),
+            Scope::Https => reqwest::Proxy::https(url),
+            Scope::All => reqwest::Proxy::all(url),
+        }
+        .map_err(|e| Error::invalid_config(format!("invalid proxy: {e}")))?;
+
+        let proxy = match &self.credentials {
+            Some(credentials) => proxy.basic_auth(&credentials.username, &credentials.password),
+            None => proxy,
+        };
+        Ok(proxy.no_proxy(
+            self.no_proxy
+                .as_deref()
+                .and_then(reqwest::NoProxy::from_string),
+        ))
+    }
+}
+
+impl fmt::Debug for Proxy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut debug = f.debug_struct("Proxy");
+        debug
+            .field("scope", &self.scope)
+            .field("url", &self.url.as_str());
+        if let Some(credentials) = &self.credentials {
+            debug
+                .field("username", &credentials.username)
+                .field("password", &"<redacted>");
+        }
+        debug.field("no_proxy", &self.no_proxy).finish()
+    }
+}
+
+fn env_var(lowercase: &str) -> Option<String> {
+    std::env::var(lowercase)
+        .or_else(|_| std::env::var(lowercase.to_ascii_uppercase()))
+        .ok()
+        .filter(|value| !value.is_empty())
+}
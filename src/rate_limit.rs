@@ -0,0 +1,162 @@
+//! Client-side rate limiting.
+//!
+//! A [`RateLimiter`] is a token bucket shared by every clone of the client it is installed on
+//! (and by any other client given a clone of the same limiter). Besides pacing requests, it
+//! pauses all callers when the server signals exhaustion, either with a `429` carrying
+//! `Retry-After` or with `X-RateLimit-Remaining: 0` plus `X-RateLimit-Reset`. The bucket is empty
+//! when a pause ends, so callers queued behind it resume at the configured rate, not all at once.
+
+use std::{
+    fmt,
+    sync::{Arc, Mutex, PoisonError},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use http::{HeaderMap, StatusCode};
+
+use crate::{Error, Result};
+
+#[derive(Clone)]
+pub struct RateLimiter {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    requests_per_second: f64,
+    burst: f64,
+    state: Mutex<State>,
+}
+
+struct State {
+    tokens: f64,
+    last_refill: Instant,
+    paused_until: Option<Instant>,
+}
+
+impl RateLimiter {
+    /// Allows `requests_per_second` on average with bursts of up to `burst` requests.
+    pub fn new(requests_per_second: f64, burst: u32) -> Result<Self> {
+        if !requests_per_second.is_finite() || requests_per_second <= 0.0 {
+            return Err(Error::invalid_config(
+                "rate limit must be a positive number of requests per second",
+            ));
+        }
+        if burst == 0 {
+            return Err(Error::invalid_config("rate limit burst must be at least 1"));
+        }
+
+        let burst = f64::from(burst);
+        Ok(Self {
+            inner: Arc::new(Inner {
+                requests_per_second,
+                burst,
+                state: Mutex::new(State {
+                    tokens: burst,
+                    last_refill: Instant::now(),
+                    paused_until: None,
+                }),
+            }),
+        })
+    }
+
+    pub fn requests_per_second(&self) -> f64 {
+        self.inner.requests_per_second
+    }
+
+    pub fn burst(&self) -> u32 {
+        self.inner.burst as u32
+    }
+
+    /// Time left until a server-imposed pause ends, if one is active.
+    pub fn paused_for(&self) -> Option<Duration> {
+        let state = self.lock();
+        let remaining = state
+            .paused_until?
+            .saturating_duration_since(Instant::now());
+        (!remaining.is_zero()).then_some(remaining)
+    }
+
+    /// Takes a token and returns how long the caller must wait before sending.
+    pub(crate) fn reserve(&self) -> Duration {
+        let now = Instant::now();
+        let mut state = self.lock();
+
+        // During a server-imposed pause `last_refill` lies in the future, so nothing refills
+        // until the pause ends and queued callers leave at the configured rate afterwards.
+        if now > state.last_refill {
+            let elapsed = (now - state.last_refill).as_secs_f64();
+            state.tokens =
+                (state.tokens + elapsed * self.inner.requests_per_second).min(self.inner.burst);
+            state.last_refill = now;
+        }
+        state.tokens -= 1.0;
+
+        let bucket_wait = if state.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-state.tokens / self.inner.requests_per_second)
+        };
+        state.last_refill.saturating_duration_since(now) + bucket_wait
+    }
+
+    /// Updates the shared pause from a response's status and rate limit headers.
+    pub(crate) fn observe(&self, status: StatusCode, headers: &HeaderMap) {
+        let mut pause = None;
+        if status == StatusCode::TOO_MANY_REQUESTS {
+            pause = crate::transport::extract_retry_after(headers);
+        }
+        if pause.is_none() && header_u64(headers, "x-ratelimit-remaining") == Some(0) {
+            pause = header_u64(headers, "x-ratelimit-reset").map(reset_delay);
+        }
+
+        let Some(pause) = pause.filter(|pause| !pause.is_zero()) else {
+            return;
+        };
+
+        #[cfg(feature = "tracing")]
+        tracing::debug!(?pause, "server requested a rate limit pause");
+
+        let until = Instant::now() + pause;
+        let mut state = self.lock();
+        if state.paused_until.is_none_or(|current| current < until) {
+            state.paused_until = Some(until);
+        }
+        state.tokens = state.tokens.min(0.0);
+        state.last_refill = state.last_refill.max(until);
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, State> {
+        self.inner
+            .state
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl fmt::Debug for RateLimiter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RateLimiter")
+            .field("requests_per_second", &self.inner.requests_per_second)
+            .field("burst", &self.inner.burst)
+            .finish()
+    }
+}
+
+fn header_u64(headers: &HeaderMap, name: &str) -> Option<u64> {
+    headers.get(name)?.to_str().ok()?.trim().parse().ok()
+}
+
+/// `X-RateLimit-Reset` is either a Unix timestamp or a number of seconds from now.
+fn reset_delay(value: u64) -> Duration {
+    const EPOCH_THRESHOLD: u64 = 1_000_000_000;
+
+    if value < EPOCH_THRESHOLD {
+        return Duration::from_secs(value);
+    }
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    Duration::from_secs(value.saturating_sub(now))
+}
@@ -2,12 +2,20 @@ use std::time::Duration;
 
 use http::{HeaderMap, HeaderName, HeaderValue};
 
+#[cfg(feature = "async")]
+use tokio_util::sync::CancellationToken;
+
 use crate::{Error, Result};
 
+/// Per-call overrides. Cancellation tokens are only available to async callers; blocking calls
+/// are bounded by [`total_timeout`](Self::total_timeout) instead.
 #[derive(Clone, Default)]
 pub struct RequestOptions {
     headers: HeaderMap,
     timeout: Option<Duration>,
+    total_timeout: Option<Duration>,
+    #[cfg(feature = "async")]
+    cancellation: Option<CancellationToken>,
 }
 
 impl RequestOptions {
@@ -29,11 +37,25 @@ impl RequestOptions {
         Ok(self)
     }
 
+    /// Per-attempt timeout, from sending the request until its body is read to the end.
     pub fn timeout(mut self, timeout: Duration) -> Self {
         self.timeout = Some(timeout);
         self
     }
 
+    /// Budget for the whole call, covering every attempt, retry delay and rate limiter wait.
+    pub fn total_timeout(mut self, timeout: Duration) -> Self {
+        self.total_timeout = Some(timeout);
+        self
+    }
+
+    /// Aborts in-flight attempts and retry sleeps once the token is cancelled. Async client only.
+    #[cfg(feature = "async")]
+    pub fn cancellation_token(mut self, token: CancellationToken) -> Self {
+        self.cancellation = Some(token);
+        self
+    }
+
     pub fn headers(&self) -> &HeaderMap {
         &self.headers
     }
@@ -41,4 +63,13 @@ impl RequestOptions {
     pub fn timeout_override(&self) -> Option<Duration> {
         self.timeout
     }
+
+    pub fn total_timeout_override(&self) -> Option<Duration> {
+        self.total_timeout
+    }
+
+    #[cfg(feature = "async")]
+    pub fn cancellation(&self) -> Option<&CancellationToken> {
+        self.cancellation.as_ref()
+    }
 }
@@ -1,6 +1,24 @@
 use std::fmt;
+#[cfg(feature = "blocking-core")]
+use std::io::{Read, Write};
+#[cfg(feature = "async")]
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
 
+#[cfg(feature = "async")]
+use bytes::Bytes;
+#[cfg(feature = "async")]
+use futures_core::Stream;
 use http::{HeaderMap, StatusCode};
+#[cfg(feature = "async")]
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+
+#[cfg(feature = "async")]
+use crate::transport::ResponseBody;
+#[cfg(any(feature = "async", feature = "blocking-core"))]
+use crate::{Error, Result};
 
 #[derive(Clone)]
 pub struct ResponseBytes {
@@ -37,19 +55,187 @@ impl ResponseBytes {
 
 impl fmt::Debug for ResponseBytes {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let mut redacted = HeaderMap::new();
-        for (name, value) in self.headers.iter() {
-            if name == http::header::SET_COOKIE {
-                redacted.insert(name.clone(), http::HeaderValue::from_static("<redacted>"));
-                continue;
-            }
-            redacted.insert(name.clone(), value.clone());
-        }
-
         f.debug_struct("ResponseBytes")
             .field("status", &self.status)
-            .field("headers", &redacted)
+            .field("headers", &redacted_headers(&self.headers))
             .field("body_len", &self.body.len())
             .finish()
     }
 }
+
+/// A successful response whose body is read incrementally.
+///
+/// Implements [`Stream`] over body chunks; [`copy_to`](Self::copy_to) writes the remaining body
+/// straight into an [`AsyncWrite`].
+///
+/// The per-attempt timeout covers reading the whole body, not just the response head: raise the
+/// client's `timeout` or pass [`RequestOptions::timeout`](crate::RequestOptions::timeout) for
+/// exports that may take longer to download.
+#[cfg(feature = "async")]
+pub struct ResponseStream {
+    status: StatusCode,
+    headers: HeaderMap,
+    body: ResponseBody,
+}
+
+#[cfg(feature = "async")]
+impl ResponseStream {
+    pub(crate) fn new(status: StatusCode, headers: HeaderMap, body: ResponseBody) -> Self {
+        Self {
+            status,
+            headers,
+            body,
+        }
+    }
+
+    pub fn status(&self) -> StatusCode {
+        self.status
+    }
+
+    pub fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+
+    /// Returns the next body chunk, or `None` once the body is exhausted.
+    pub async fn chunk(&mut self) -> Result<Option<Bytes>> {
+        std::future::poll_fn(|cx| Pin::new(&mut *self).poll_next(cx))
+            .await
+            .transpose()
+    }
+
+    /// Copies the remaining body into `writer` and returns the number of bytes written.
+    pub async fn copy_to<W>(mut self, writer: &mut W) -> Result<u64>
+    where
+        W: AsyncWrite + Unpin + ?Sized,
+    {
+        let mut written = 0u64;
+        while let Some(chunk) = self.chunk().await? {
+            writer
+                .write_all(&chunk)
+                .await
+                .map_err(|e| Error::transport("failed to write response body", e))?;
+            written += chunk.len() as u64;
+        }
+        writer
+            .flush()
+            .await
+            .map_err(|e| Error::transport("failed to write response body", e))?;
+        Ok(written)
+    }
+
+    /// Buffers the remaining body.
+    pub async fn into_response_bytes(mut self) -> Result<ResponseBytes> {
+        let mut body = Vec::new();
+        while let Some(chunk) = self.chunk().await? {
+            body.extend_from_slice(&chunk);
+        }
+        Ok(ResponseBytes::new(self.status, self.headers, body))
+    }
+}
+
+#[cfg(feature = "async")]
+impl Stream for ResponseStream {
+    type Item = Result<Bytes>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut self.body).poll_next(cx).map(|item| {
+            item.map(|chunk| {
+                chunk.map_err(|e| Error::transport_boxed("failed to read response body", e))
+            })
+        })
+    }
+}
+
+#[cfg(feature = "async")]
+impl fmt::Debug for ResponseStream {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ResponseStream")
+            .field("status", &self.status)
+            .field("headers", &redacted_headers(&self.headers))
+            .finish_non_exhaustive()
+    }
+}
+
+/// A successful response whose body is read incrementally through [`Read`].
+///
+/// As with async streams, the per-attempt timeout covers reading the whole body.
+#[cfg(feature = "blocking-core")]
+pub struct ResponseReader {
+    status: StatusCode,
+    headers: HeaderMap,
+    body: Box<dyn Read + Send>,
+}
+
+#[cfg(feature = "blocking-core")]
+impl ResponseReader {
+    pub(crate) fn new(
+        status: StatusCode,
+        headers: HeaderMap,
+        body: impl Read + Send + 'static,
+    ) -> Self {
+        Self {
+            status,
+            headers,
+            body: Box::new(body),
+        }
+    }
+
+    pub fn status(&self) -> StatusCode {
+        self.status
+    }
+
+    pub fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+
+    /// Copies the remaining body into `writer` and returns the number of bytes written.
+    pub fn copy_to<W>(mut self, writer: &mut W) -> Result<u64>
+    where
+        W: Write + ?Sized,
+    {
+        let written = std::io::copy(&mut self.body, writer)
+            .map_err(|e| Error::transport("failed to copy response body", e))?;
+        writer
+            .flush()
+            .map_err(|e| Error::transport("failed to write response body", e))?;
+        Ok(written)
+    }
+
+    /// Buffers the remaining body.
+    pub fn into_response_bytes(mut self) -> Result<ResponseBytes> {
+        let mut body = Vec::new();
+        self.body
+            .read_to_end(&mut body)
+            .map_err(|e| Error::transport("failed to read response body", e))?;
+        Ok(ResponseBytes::new(self.status, self.headers, body))
+    }
+}
+
+#[cfg(feature = "blocking-core")]
+impl Read for ResponseReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.body.read(buf)
+    }
+}
+
+#[cfg(feature = "blocking-core")]
+impl fmt::Debug for ResponseReader {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ResponseReader")
+            .field("status", &self.status)
+            .field("headers", &redacted_headers(&self.headers))
+            .finish_non_exhaustive()
+    }
+}
+
+fn redacted_headers(headers: &HeaderMap) -> HeaderMap {
+    let mut redacted = HeaderMap::new();
+    for (name, value) in headers.iter() {
+        if name == http::header::SET_COOKIE {
+            redacted.insert(name.clone(), http::HeaderValue::from_static("<redacted>"));
+            continue;
+        }
+        redacted.insert(name.clone(), value.clone());
+    }
+    redacted
+}
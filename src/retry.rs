@@ -0,0 +1,298 @@
+//! Retry policies.
+//!
+//! The transport consults a [`RetryPolicy`] after every failed attempt. The policy sees the
+//! request method and path, what went wrong, and how many retries have already been made, and
+//! either returns the delay before the next attempt or `None` to give up.
+
+use std::{sync::Arc, time::Duration};
+
+use http::{Method, StatusCode};
+
+/// Broad classification of a transport-level failure.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum TransportErrorKind {
+    Timeout,
+    Connect,
+    Body,
+    Other,
+}
+
+/// What caused the attempt to fail.
+#[derive(Clone, Copy, Debug)]
+pub enum RetryCause {
+    Status {
+        status: StatusCode,
+        retry_after: Option<Duration>,
+    },
+    Transport(TransportErrorKind),
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct RetryContext<'a> {
+    method: &'a Method,
+    path: &'a str,
+    attempt: usize,
+    cause: RetryCause,
+    previous_delay: Option<Duration>,
+}
+
+impl<'a> RetryContext<'a> {
+    pub(crate) fn new(
+        method: &'a Method,
+        path: &'a str,
+        attempt: usize,
+        cause: RetryCause,
+        previous_delay: Option<Duration>,
+    ) -> Self {
+        Self {
+            method,
+            path,
+            attempt,
+            cause,
+            previous_delay,
+        }
+    }
+
+    pub fn method(&self) -> &Method {
+        self.method
+    }
+
+    /// Full request path, including the base URL subpath and the `/api` segment.
+    pub fn path(&self) -> &str {
+        self.path
+    }
+
+    /// Number of retries already made for this call (`0` after the first attempt).
+    pub fn attempt(&self) -> usize {
+        self.attempt
+    }
+
+    pub fn cause(&self) -> RetryCause {
+        self.cause
+    }
+
+    pub fn status(&self) -> Option<StatusCode> {
+        match self.cause {
+            RetryCause::Status { status, .. } => Some(status),
+            RetryCause::Transport(_) => None,
+        }
+    }
+
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self.cause {
+            RetryCause::Status { retry_after, .. } => retry_after,
+            RetryCause::Transport(_) => None,
+        }
+    }
+
+    pub fn transport_error(&self) -> Option<TransportErrorKind> {
+        match self.cause {
+            RetryCause::Status { .. } => None,
+            RetryCause::Transport(kind) => Some(kind),
+        }
+    }
+
+    /// Delay slept before the attempt that just failed, if any.
+    pub fn previous_delay(&self) -> Option<Duration> {
+        self.previous_delay
+    }
+
+    pub fn is_idempotent(&self) -> bool {
+        matches!(
+            *self.method,
+            Method::GET | Method::HEAD | Method::PUT | Method::DELETE | Method::OPTIONS
+        )
+    }
+
+    /// The SDK's default rule: idempotent methods are retried on 429/502/503/504 and on
+    /// connect or timeout errors.
+    pub fn is_retryable_by_default(&self) -> bool {
+        if !self.is_idempotent() {
+            return false;
+        }
+
+        match self.cause {
+            RetryCause::Status { status, .. } => matches!(
+                status,
+                StatusCode::TOO_MANY_REQUESTS
+                    | StatusCode::BAD_GATEWAY
+                    | StatusCode::SERVICE_UNAVAILABLE
+                    | StatusCode::GATEWAY_TIMEOUT
+            ),
+            RetryCause::Transport(kind) => {
+                matches!(
+                    kind,
+                    TransportErrorKind::Timeout | TransportErrorKind::Connect
+                )
+            }
+        }
+    }
+}
+
+pub trait RetryPolicy: Send + Sync + 'static {
+    /// Returns the delay before the next attempt, or `None` to stop retrying.
+    fn next_delay(&self, ctx: &RetryContext<'_>) -> Option<Duration>;
+}
+
+impl<T> RetryPolicy for Arc<T>
+where
+    T: RetryPolicy + ?Sized,
+{
+    fn next_delay(&self, ctx: &RetryContext<'_>) -> Option<Duration> {
+        (**self).next_delay(ctx)
+    }
+}
+
+/// Never retries.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NoRetry;
+
+impl RetryPolicy for NoRetry {
+    fn next_delay(&self, _ctx: &RetryContext<'_>) -> Option<Duration> {
+        None
+    }
+}
+
+/// Capped exponential backoff with full jitter; honors `Retry-After`.
+///
+/// This is the policy clients use unless another one is configured.
+#[derive(Clone, Debug)]
+pub struct ExponentialBackoff {
+    max_retries: usize,
+    base_delay: Duration,
+    max_delay: Duration,
+}
+
+impl Default for ExponentialBackoff {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            base_delay: Duration::from_millis(200),
+            max_delay: Duration::from_secs(2),
+        }
+    }
+}
+
+impl ExponentialBackoff {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn max_retries(mut self, max_retries: usize) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    pub fn base_delay(mut self, delay: Duration) -> Self {
+        self.base_delay = delay;
+        self
+    }
+
+    pub fn max_delay(mut self, delay: Duration) -> Self {
+        self.max_delay = delay;
+        self
+    }
+
+    /// Delay for the given context, ignoring whether the failure is retryable.
+    pub fn delay(&self, ctx: &RetryContext<'_>) -> Duration {
+        if let Some(delay) = ctx.retry_after() {
+            return delay;
+        }
+
+        if self.base_delay.is_zero() {
+            return Duration::ZERO;
+        }
+
+        let base_ms = duration_ms(self.base_delay);
+        let max_ms = duration_ms(self.max_delay);
+
+        let shift = ctx.attempt().min(32) as u32;
+        let exp_ms = base_ms.saturating_mul(1u64 << shift);
+        let cap_ms = exp_ms.min(max_ms);
+
+        if cap_ms == 0 {
+            return Duration::ZERO;
+        }
+
+        Duration::from_millis(fastrand::u64(0..=cap_ms))
+    }
+}
+
+impl RetryPolicy for ExponentialBackoff {
+    fn next_delay(&self, ctx: &RetryContext<'_>) -> Option<Duration> {
+        if ctx.attempt() >= self.max_retries || !ctx.is_retryable_by_default() {
+            return None;
+        }
+        Some(self.delay(ctx))
+    }
+}
+
+/// "Decorrelated jitter" backoff: each delay is drawn between `base_delay` and three times the
+/// previous delay, capped at `max_delay`. Honors `Retry-After`.
+#[derive(Clone, Debug)]
+pub struct DecorrelatedJitter {
+    max_retries: usize,
+    base_delay: Duration,
+    max_delay: Duration,
+}
+
+impl Default for DecorrelatedJitter {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            base_delay: Duration::from_millis(200),
+            max_delay: Duration::from_secs(2),
+        }
+    }
+}
+
+impl DecorrelatedJitter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn max_retries(mut self, max_retries: usize) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    pub fn base_delay(mut self, delay: Duration) -> Self {
+        self.base_delay = delay;
+        self
+    }
+
+    pub fn max_delay(mut self, delay: Duration) -> Self {
+        self.max_delay = delay;
+        self
+    }
+
+    /// Delay for the given context, ignoring whether the failure is retryable.
+    pub fn delay(&self, ctx: &RetryContext<'_>) -> Duration {
+        if let Some(delay) = ctx.retry_after() {
+            return delay;
+        }
+
+        let base_ms = duration_ms(self.base_delay);
+        let max_ms = duration_ms(self.max_delay);
+        let previous_ms = ctx.previous_delay().map(duration_ms).unwrap_or(base_ms);
+
+        let upper_ms = previous_ms.saturating_mul(3).max(base_ms).min(max_ms);
+        let lower_ms = base_ms.min(upper_ms);
+
+        Duration::from_millis(fastrand::u64(lower_ms..=upper_ms))
+    }
+}
+
+impl RetryPolicy for DecorrelatedJitter {
+    fn next_delay(&self, ctx: &RetryContext<'_>) -> Option<Duration> {
+        if ctx.attempt() >= self.max_retries || !ctx.is_retryable_by_default() {
+            return None;
+        }
+        Some(self.delay(ctx))
+    }
+}
+
+fn duration_ms(duration: Duration) -> u64 {
+    duration.as_millis().min(u128::from(u64::MAX)) as u64
+}
@@ -0,0 +1,959 @@
+//! Scriptable mock of the typed services.
+//!
+//! [`MockApi`] implements every service trait (async and blocking) and [`GrafanaApi`]
+//! (`api::GrafanaApi`). Responses are queued per operation, named `<service>.<method>` after the
+//! client accessor and service method (`"dashboards.get_by_uid"`, `"teams.add_member"`, ...), as
+//! JSON that is deserialized into the method's return type. Each call pops the next queued
+//! response; the last one is repeated once the queue is down to it. Every call is recorded with
+//! its arguments serialized to JSON.
+//!
+//! ```
+//! # #[cfg(feature = "blocking-core")]
+//! # fn demo() -> Result<(), grafana::Error> {
+//! use grafana::{api::BlockingFoldersApi, testing::MockApi};
+//! use http::StatusCode;
+//!
+//! fn folder_title(api: &impl BlockingFoldersApi, uid: &str) -> Result<String, grafana::Error> {
+//!     Ok(api.get_by_uid(uid)?.title)
+//! }
+//!
+//! let mock = MockApi::new();
+//! mock.respond("folders.get_by_uid", serde_json::json!({ "id": 1, "uid": "ops", "title": "Ops" }));
+//! assert_eq!(folder_title(&mock, "ops")?, "Ops");
+//!
+//! mock.fail("folders.get_by_uid", StatusCode::NOT_FOUND, "folder not found");
+//! assert!(matches!(folder_title(&mock, "ops"), Err(grafana::Error::NotFound(_))));
+//! assert_eq!(mock.calls_to("folders.get_by_uid").len(), 2);
+//! # Ok(())
+//! # }
+//! ```
+
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt,
+    sync::{Arc, Mutex, PoisonError},
+};
+
+#[cfg(feature = "async")]
+use std::future::Future;
+
+use http::{Method, StatusCode};
+use serde::{Serialize, de::DeserializeOwned};
+use serde_json::Value;
+
+use crate::{
+    Error, Result, api,
+    error::HttpError,
+    types::{
+        AddOrgUserRequest, CreateFolderRequest, CreateServiceAccountRequest,
+        CreateServiceAccountTokenRequest, CreateTeamRequest, CreateTeamResponse, DashboardUid,
+        Datasource, DatasourceId, DeleteDashboardResponse, DeleteDatasourceResponse,
+        DeleteFolderResponse, Folder, FolderUid, GetDashboardResponse, HealthResponse, NewApiKey,
+        OrgDetails, OrgId, OrgUser, SaveDashboardRequest, SaveDashboardResponse, SearchParams,
+        SearchResult, ServiceAccount, ServiceAccountId, ServiceAccountSearchParams,
+        ServiceAccountSearchResult, SuccessResponse, Team, TeamId, TeamMember, TeamSearchParams,
+        TeamSearchResult, Token, TokenId, UpdateFolderRequest, UpdateOrgRequest,
+        UpdateOrgUserRequest, UpdateServiceAccountRequest, UpdateServiceAccountResponse,
+        UpdateTeamMemberRequest, UpdateTeamRequest, UpdateUserRequest, UserId, UserOrg,
+        UserProfile,
+    },
+};
+
+#[derive(Clone, Default)]
+pub struct MockApi {
+    inner: Arc<Mutex<State>>,
+}
+
+#[derive(Default)]
+struct State {
+    responses: HashMap<String, VecDeque<MockResponse>>,
+    calls: Vec<MockCall>,
+}
+
+#[derive(Clone)]
+enum MockResponse {
+    Json(Value),
+    Error { status: StatusCode, message: String },
+}
+
+/// One recorded call: the operation name and its arguments as JSON.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MockCall {
+    pub operation: String,
+    pub args: Vec<Value>,
+}
+
+impl MockApi {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queues a successful response for `operation`.
+    pub fn respond(&self, operation: impl Into<String>, body: Value) -> &Self {
+        self.push(operation.into(), MockResponse::Json(body))
+    }
+
+    /// Queues an API error for `operation`, classified by status like a real response
+    /// (`404` becomes [`Error::NotFound`], `409`/`412` [`Error::Conflict`], ...).
+    pub fn fail(
+        &self,
+        operation: impl Into<String>,
+        status: StatusCode,
+        message: impl Into<String>,
+    ) -> &Self {
+        self.push(
+            operation.into(),
+            MockResponse::Error {
+                status,
+                message: message.into(),
+            },
+        )
+    }
+
+    pub fn calls(&self) -> Vec<MockCall> {
+        self.lock().calls.clone()
+    }
+
+    pub fn calls_to(&self, operation: &str) -> Vec<MockCall> {
+        self.lock()
+            .calls
+            .iter()
+            .filter(|call| call.operation == operation)
+            .cloned()
+            .collect()
+    }
+
+    /// Drops all queued responses and recorded calls.
+    pub fn reset(&self) {
+        let mut state = self.lock();
+        state.responses.clear();
+        state.calls.clear();
+    }
+
+    fn push(&self, operation: String, response: MockResponse) -> &Self {
+        self.lock()
+            .responses
+            .entry(operation)
+            .or_default()
+            .push_back(response);
+        self
+    }
+
+    fn call<T>(&self, operation: &'static str, args: Vec<Value>) -> Result<T>
+    where
+        T: DeserializeOwned,
+    {
+        let response = {
+            let mut state = self.lock();
+            state.calls.push(MockCall {
+                operation: operation.to_owned(),
+                args,
+            });
+            let queue = state.responses.get_mut(operation);
+            match queue {
+                Some(queue) if queue.len() > 1 => queue.pop_front(),
+                Some(queue) => queue.front().cloned(),
+                None => None,
+            }
+        };
+
+        match response {
+            Some(MockResponse::Json(body)) => serde_json::from_value(body).map_err(|e| {
+                Error::decode(
+                    HttpError::new(Method::GET, operation.to_owned(), Some(StatusCode::OK)),
+                    e,
+                )
+            }),
+            Some(MockResponse::Error { status, message }) => Err(Error::from_http(
+                HttpError::new(Method::GET, operation.to_owned(), Some(status))
+                    .with_message(Some(message)),
+            )),
+            None => Err(Error::invalid_config(format!(
+                "no mock response configured for {operation}"
+            ))),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, State> {
+        self.inner.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl fmt::Debug for MockApi {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = self.lock();
+        f.debug_struct("MockApi")
+            .field("operations", &state.responses.keys().collect::<Vec<_>>())
+            .field("calls", &state.calls.len())
+            .finish()
+    }
+}
+
+fn arg(value: &impl Serialize) -> Value {
+    serde_json::to_value(value).unwrap_or(Value::Null)
+}
+
+#[cfg(feature = "async")]
+impl api::GrafanaApi for MockApi {
+    type Health = MockApi;
+    type Dashboards = MockApi;
+    type Folders = MockApi;
+    type Datasources = MockApi;
+    type Search = MockApi;
+    type Teams = MockApi;
+    type ServiceAccounts = MockApi;
+    type Org = MockApi;
+    type User = MockApi;
+
+    fn health(&self) -> MockApi {
+        self.clone()
+    }
+
+    fn dashboards(&self) -> MockApi {
+        self.clone()
+    }
+
+    fn folders(&self) -> MockApi {
+        self.clone()
+    }
+
+    fn datasources(&self) -> MockApi {
+        self.clone()
+    }
+
+    fn search(&self) -> MockApi {
+        self.clone()
+    }
+
+    fn teams(&self) -> MockApi {
+        self.clone()
+    }
+
+    fn service_accounts(&self) -> MockApi {
+        self.clone()
+    }
+
+    fn org(&self) -> MockApi {
+        self.clone()
+    }
+
+    fn user(&self) -> MockApi {
+        self.clone()
+    }
+}
+
+#[cfg(feature = "blocking-core")]
+impl api::BlockingGrafanaApi for MockApi {
+    type Health = MockApi;
+    type Dashboards = MockApi;
+    type Folders = MockApi;
+    type Datasources = MockApi;
+    type Search = MockApi;
+    type Teams = MockApi;
+    type ServiceAccounts = MockApi;
+    type Org = MockApi;
+    type User = MockApi;
+
+    fn health(&self) -> MockApi {
+        self.clone()
+    }
+
+    fn dashboards(&self) -> MockApi {
+        self.clone()
+    }
+
+    fn folders(&self) -> MockApi {
+        self.clone()
+    }
+
+    fn datasources(&self) -> MockApi {
+        self.clone()
+    }
+
+    fn search(&self) -> MockApi {
+        self.clone()
+    }
+
+    fn teams(&self) -> MockApi {
+        self.clone()
+    }
+
+    fn service_accounts(&self) -> MockApi {
+        self.clone()
+    }
+
+    fn org(&self) -> MockApi {
+        self.clone()
+    }
+
+    fn user(&self) -> MockApi {
+        self.clone()
+    }
+}
+
+#[cfg(feature = "async")]
+impl api::HealthApi for MockApi {
+    fn get(&self) -> impl Future<Output = Result<HealthResponse>> + Send {
+        std::future::ready(self.call("health.get", vec![]))
+    }
+}
+
+#[cfg(feature = "async")]
+impl api::DashboardsApi for MockApi {
+    fn get_by_uid(
+        &self,
+        uid: impl Into<DashboardUid> + Send,
+    ) -> impl Future<Output = Result<GetDashboardResponse>> + Send {
+        std::future::ready(self.call(
+            "dashboards.get_by_uid",
+            vec![arg(&Into::<DashboardUid>::into(uid))],
+        ))
+    }
+
+    fn save(
+        &self,
+        request: &SaveDashboardRequest,
+    ) -> impl Future<Output = Result<SaveDashboardResponse>> + Send {
+        std::future::ready(self.call("dashboards.save", vec![arg(request)]))
+    }
+
+    fn delete_by_uid(
+        &self,
+        uid: impl Into<DashboardUid> + Send,
+    ) -> impl Future<Output = Result<DeleteDashboardResponse>> + Send {
+        std::future::ready(self.call(
+            "dashboards.delete_by_uid",
+            vec![arg(&Into::<DashboardUid>::into(uid))],
+        ))
+    }
+}
+
+#[cfg(feature = "async")]
+impl api::FoldersApi for MockApi {
+    fn list(&self) -> impl Future<Output = Result<Vec<Folder>>> + Send {
+        std::future::ready(self.call("folders.list", vec![]))
+    }
+
+    fn get_by_uid(
+        &self,
+        uid: impl Into<FolderUid> + Send,
+    ) -> impl Future<Output = Result<Folder>> + Send {
+        std::future::ready(self.call(
+            "folders.get_by_uid",
+            vec![arg(&Into::<FolderUid>::into(uid))],
+        ))
+    }
+
+    fn create(&self, request: &CreateFolderRequest) -> impl Future<Output = Result<Folder>> + Send {
+        std::future::ready(self.call("folders.create", vec![arg(request)]))
+    }
+
+    fn update(
+        &self,
+        uid: impl Into<FolderUid> + Send,
+        request: &UpdateFolderRequest,
+    ) -> impl Future<Output = Result<Folder>> + Send {
+        std::future::ready(self.call(
+            "folders.update",
+            vec![arg(&Into::<FolderUid>::into(uid)), arg(request)],
+        ))
+    }
+
+    fn delete_by_uid(
+        &self,
+        uid: impl Into<FolderUid> + Send,
+    ) -> impl Future<Output = Result<DeleteFolderResponse>> + Send {
+        std::future::ready(self.call(
+            "folders.delete_by_uid",
+            vec![arg(&Into::<FolderUid>::into(uid))],
+        ))
+    }
+}
+
+#[cfg(feature = "async")]
+impl api::DatasourcesApi for MockApi {
+    fn list(&self) -> impl Future<Output = Result<Vec<Datasource>>> + Send {
+        std::future::ready(self.call("datasources.list", vec![]))
+    }
+
+    fn get_by_id(
+        &self,
+        id: impl Into<DatasourceId> + Send,
+    ) -> impl Future<Output = Result<Datasource>> + Send {
+        std::future::ready(self.call(
+            "datasources.get_by_id",
+            vec![arg(&Into::<DatasourceId>::into(id))],
+        ))
+    }
+
+    fn delete_by_id(
+        &self,
+        id: impl Into<DatasourceId> + Send,
+    ) -> impl Future<Output = Result<DeleteDatasourceResponse>> + Send {
+        std::future::ready(self.call(
+            "datasources.delete_by_id",
+            vec![arg(&Into::<DatasourceId>::into(id))],
+        ))
+    }
+}
+
+#[cfg(feature = "async")]
+impl api::SearchApi for MockApi {
+    fn search(
+        &self,
+        params: &SearchParams,
+    ) -> impl Future<Output = Result<Vec<SearchResult>>> + Send {
+        std::future::ready(self.call("search.search", vec![arg(params)]))
+    }
+}
+
+#[cfg(feature = "async")]
+impl api::TeamsApi for MockApi {
+    fn search(
+        &self,
+        params: &TeamSearchParams,
+    ) -> impl Future<Output = Result<TeamSearchResult>> + Send {
+        std::future::ready(self.call("teams.search", vec![arg(params)]))
+    }
+
+    fn create(
+        &self,
+        request: &CreateTeamRequest,
+    ) -> impl Future<Output = Result<CreateTeamResponse>> + Send {
+        std::future::ready(self.call("teams.create", vec![arg(request)]))
+    }
+
+    fn get_by_id(
+        &self,
+        team_id: impl Into<TeamId> + Send,
+    ) -> impl Future<Output = Result<Team>> + Send {
+        std::future::ready(self.call("teams.get_by_id", vec![arg(&Into::<TeamId>::into(team_id))]))
+    }
+
+    fn update(
+        &self,
+        team_id: impl Into<TeamId> + Send,
+        request: &UpdateTeamRequest,
+    ) -> impl Future<Output = Result<SuccessResponse>> + Send {
+        std::future::ready(self.call(
+            "teams.update",
+            vec![arg(&Into::<TeamId>::into(team_id)), arg(request)],
+        ))
+    }
+
+    fn delete(
+        &self,
+        team_id: impl Into<TeamId> + Send,
+    ) -> impl Future<Output = Result<SuccessResponse>> + Send {
+        std::future::ready(self.call("teams.delete", vec![arg(&Into::<TeamId>::into(team_id))]))
+    }
+
+    fn members(
+        &self,
+        team_id: impl Into<TeamId> + Send,
+    ) -> impl Future<Output = Result<Vec<TeamMember>>> + Send {
+        std::future::ready(self.call("teams.members", vec![arg(&Into::<TeamId>::into(team_id))]))
+    }
+
+    fn add_member(
+        &self,
+        team_id: impl Into<TeamId> + Send,
+        user_id: impl Into<UserId> + Send,
+    ) -> impl Future<Output = Result<SuccessResponse>> + Send {
+        std::future::ready(self.call(
+            "teams.add_member",
+            vec![
+                arg(&Into::<TeamId>::into(team_id)),
+                arg(&Into::<UserId>::into(user_id)),
+            ],
+        ))
+    }
+
+    fn update_member_permission(
+        &self,
+        team_id: impl Into<TeamId> + Send,
+        user_id: impl Into<UserId> + Send,
+        request: &UpdateTeamMemberRequest,
+    ) -> impl Future<Output = Result<SuccessResponse>> + Send {
+        std::future::ready(self.call(
+            "teams.update_member_permission",
+            vec![
+                arg(&Into::<TeamId>::into(team_id)),
+                arg(&Into::<UserId>::into(user_id)),
+                arg(request),
+            ],
+        ))
+    }
+
+    fn remove_member(
+        &self,
+        team_id: impl Into<TeamId> + Send,
+        user_id: impl Into<UserId> + Send,
+    ) -> impl Future<Output = Result<SuccessResponse>> + Send {
+        std::future::ready(self.call(
+            "teams.remove_member",
+            vec![
+                arg(&Into::<TeamId>::into(team_id)),
+                arg(&Into::<UserId>::into(user_id)),
+            ],
+        ))
+    }
+}
+
+#[cfg(feature = "async")]
+impl api::ServiceAccountsApi for MockApi {
+    fn search(
+        &self,
+        params: &ServiceAccountSearchParams,
+    ) -> impl Future<Output = Result<ServiceAccountSearchResult>> + Send {
+        std::future::ready(self.call("service_accounts.search", vec![arg(params)]))
+    }
+
+    fn create(
+        &self,
+        request: &CreateServiceAccountRequest,
+    ) -> impl Future<Output = Result<ServiceAccount>> + Send {
+        std::future::ready(self.call("service_accounts.create", vec![arg(request)]))
+    }
+
+    fn get_by_id(
+        &self,
+        service_account_id: impl Into<ServiceAccountId> + Send,
+    ) -> impl Future<Output = Result<ServiceAccount>> + Send {
+        std::future::ready(self.call(
+            "service_accounts.get_by_id",
+            vec![arg(&Into::<ServiceAccountId>::into(service_account_id))],
+        ))
+    }
+
+    fn update(
+        &self,
+        service_account_id: impl Into<ServiceAccountId> + Send,
+        request: &UpdateServiceAccountRequest,
+    ) -> impl Future<Output = Result<UpdateServiceAccountResponse>> + Send {
+        std::future::ready(self.call(
+            "service_accounts.update",
+            vec![
+                arg(&Into::<ServiceAccountId>::into(service_account_id)),
+                arg(request),
+            ],
+        ))
+    }
+
+    fn delete(
+        &self,
+        service_account_id: impl Into<ServiceAccountId> + Send,
+    ) -> impl Future<Output = Result<SuccessResponse>> + Send {
+        std::future::ready(self.call(
+            "service_accounts.delete",
+            vec![arg(&Into::<ServiceAccountId>::into(service_account_id))],
+        ))
+    }
+
+    fn tokens(
+        &self,
+        service_account_id: impl Into<ServiceAccountId> + Send,
+    ) -> impl Future<Output = Result<Vec<Token>>> + Send {
+        std::future::ready(self.call(
+            "service_accounts.tokens",
+            vec![arg(&Into::<ServiceAccountId>::into(service_account_id))],
+        ))
+    }
+
+    fn create_token(
+        &self,
+        service_account_id: impl Into<ServiceAccountId> + Send,
+        request: &CreateServiceAccountTokenRequest,
+    ) -> impl Future<Output = Result<NewApiKey>> + Send {
+        std::future::ready(self.call(
+            "service_accounts.create_token",
+            vec![
+                arg(&Into::<ServiceAccountId>::into(service_account_id)),
+                arg(request),
+            ],
+        ))
+    }
+
+    fn delete_token(
+        &self,
+        service_account_id: impl Into<ServiceAccountId> + Send,
+        token_id: impl Into<TokenId> + Send,
+    ) -> impl Future<Output = Result<SuccessResponse>> + Send {
+        std::future::ready(self.call(
+            "service_accounts.delete_token",
+            vec![
+                arg(&Into::<ServiceAccountId>::into(service_account_id)),
+                arg(&Into::<TokenId>::into(token_id)),
+            ],
+        ))
+    }
+}
+
+#[cfg(feature = "async")]
+impl api::OrgApi for MockApi {
+    fn get(&self) -> impl Future<Output = Result<OrgDetails>> + Send {
+        std::future::ready(self.call("org.get", vec![]))
+    }
+
+    fn update(
+        &self,
+        request: &UpdateOrgRequest,
+    ) -> impl Future<Output = Result<SuccessResponse>> + Send {
+        std::future::ready(self.call("org.update", vec![arg(request)]))
+    }
+
+    fn users(&self) -> impl Future<Output = Result<Vec<OrgUser>>> + Send {
+        std::future::ready(self.call("org.users", vec![]))
+    }
+
+    fn add_user(
+        &self,
+        request: &AddOrgUserRequest,
+    ) -> impl Future<Output = Result<SuccessResponse>> + Send {
+        std::future::ready(self.call("org.add_user", vec![arg(request)]))
+    }
+
+    fn update_user_role(
+        &self,
+        user_id: impl Into<UserId> + Send,
+        request: &UpdateOrgUserRequest,
+    ) -> impl Future<Output = Result<SuccessResponse>> + Send {
+        std::future::ready(self.call(
+            "org.update_user_role",
+            vec![arg(&Into::<UserId>::into(user_id)), arg(request)],
+        ))
+    }
+
+    fn remove_user(
+        &self,
+        user_id: impl Into<UserId> + Send,
+    ) -> impl Future<Output = Result<SuccessResponse>> + Send {
+        std::future::ready(self.call("org.remove_user", vec![arg(&Into::<UserId>::into(user_id))]))
+    }
+}
+
+#[cfg(feature = "async")]
+impl api::UserApi for MockApi {
+    fn get_profile(&self) -> impl Future<Output = Result<UserProfile>> + Send {
+        std::future::ready(self.call("user.get_profile", vec![]))
+    }
+
+    fn update_profile(
+        &self,
+        request: &UpdateUserRequest,
+    ) -> impl Future<Output = Result<SuccessResponse>> + Send {
+        std::future::ready(self.call("user.update_profile", vec![arg(request)]))
+    }
+
+    fn orgs(&self) -> impl Future<Output = Result<Vec<UserOrg>>> + Send {
+        std::future::ready(self.call("user.orgs", vec![]))
+    }
+
+    fn teams(&self) -> impl Future<Output = Result<Vec<Team>>> + Send {
+        std::future::ready(self.call("user.teams", vec![]))
+    }
+
+    fn switch_org(
+        &self,
+        org_id: impl Into<OrgId> + Send,
+    ) -> impl Future<Output = Result<SuccessResponse>> + Send {
+        std::future::ready(self.call("user.switch_org", vec![arg(&Into::<OrgId>::into(org_id))]))
+    }
+}
+
+#[cfg(feature = "blocking-core")]
+impl api::BlockingHealthApi for MockApi {
+    fn get(&self) -> Result<HealthResponse> {
+        self.call("health.get", vec![])
+    }
+}
+
+#[cfg(feature = "blocking-core")]
+impl api::BlockingDashboardsApi for MockApi {
+    fn get_by_uid(&self, uid: impl Into<DashboardUid>) -> Result<GetDashboardResponse> {
+        self.call(
+            "dashboards.get_by_uid",
+            vec![arg(&Into::<DashboardUid>::into(uid))],
+        )
+    }
+
+    fn save(&self, request: &SaveDashboardRequest) -> Result<SaveDashboardResponse> {
+        self.call("dashboards.save", vec![arg(request)])
+    }
+
+    fn delete_by_uid(&self, uid: impl Into<DashboardUid>) -> Result<DeleteDashboardResponse> {
+        self.call(
+            "dashboards.delete_by_uid",
+            vec![arg(&Into::<DashboardUid>::into(uid))],
+        )
+    }
+}
+
+#[cfg(feature = "blocking-core")]
+impl api::BlockingFoldersApi for MockApi {
+    fn list(&self) -> Result<Vec<Folder>> {
+        self.call("folders.list", vec![])
+    }
+
+    fn get_by_uid(&self, uid: impl Into<FolderUid>) -> Result<Folder> {
+        self.call(
+            "folders.get_by_uid",
+            vec![arg(&Into::<FolderUid>::into(uid))],
+        )
+    }
+
+    fn create(&self, request: &CreateFolderRequest) -> Result<Folder> {
+        self.call("folders.create", vec![arg(request)])
+    }
+
+    fn update(&self, uid: impl Into<FolderUid>, request: &UpdateFolderRequest) -> Result<Folder> {
+        self.call(
+            "folders.update",
+            vec![arg(&Into::<FolderUid>::into(uid)), arg(request)],
+        )
+    }
+
+    fn delete_by_uid(&self, uid: impl Into<FolderUid>) -> Result<DeleteFolderResponse> {
+        self.call(
+            "folders.delete_by_uid",
+            vec![arg(&Into::<FolderUid>::into(uid))],
+        )
+    }
+}
+
+#[cfg(feature = "blocking-core")]
+impl api::BlockingDatasourcesApi for MockApi {
+    fn list(&self) -> Result<Vec<Datasource>> {
+        self.call("datasources.list", vec![])
+    }
+
+    fn get_by_id(&self, id: impl Into<DatasourceId>) -> Result<Datasource> {
+        self.call(
+            "datasources.get_by_id",
+            vec![arg(&Into::<DatasourceId>::into(id))],
+        )
+    }
+
+    fn delete_by_id(&self, id: impl Into<DatasourceId>) -> Result<DeleteDatasourceResponse> {
+        self.call(
+            "datasources.delete_by_id",
+            vec![arg(&Into::<DatasourceId>::into(id))],
+        )
+    }
+}
+
+#[cfg(feature = "blocking-core")]
+impl api::BlockingSearchApi for MockApi {
+    fn search(&self, params: &SearchParams) -> Result<Vec<SearchResult>> {
+        self.call("search.search", vec![arg(params)])
+    }
+}
+
+#[cfg(feature = "blocking-core")]
+impl api::BlockingTeamsApi for MockApi {
+    fn search(&self, params: &TeamSearchParams) -> Result<TeamSearchResult> {
+        self.call("teams.search", vec![arg(params)])
+    }
+
+    fn create(&self, request: &CreateTeamRequest) -> Result<CreateTeamResponse> {
+        self.call("teams.create", vec![arg(request)])
+    }
+
+    fn get_by_id(&self, team_id: impl Into<TeamId>) -> Result<Team> {
+        self.call("teams.get_by_id", vec![arg(&Into::<TeamId>::into(team_id))])
+    }
+
+    fn update(
+        &self,
+        team_id: impl Into<TeamId>,
+        request: &UpdateTeamRequest,
+    ) -> Result<SuccessResponse> {
+        self.call(
+            "teams.update",
+            vec![arg(&Into::<TeamId>::into(team_id)), arg(request)],
+        )
+    }
+
+    fn delete(&self, team_id: impl Into<TeamId>) -> Result<SuccessResponse> {
+        self.call("teams.delete", vec![arg(&Into::<TeamId>::into(team_id))])
+    }
+
+    fn members(&self, team_id: impl Into<TeamId>) -> Result<Vec<TeamMember>> {
+        self.call("teams.members", vec![arg(&Into::<TeamId>::into(team_id))])
+    }
+
+    fn add_member(
+        &self,
+        team_id: impl Into<TeamId>,
+        user_id: impl Into<UserId>,
+    ) -> Result<SuccessResponse> {
+        self.call(
+            "teams.add_member",
+            vec![
+                arg(&Into::<TeamId>::into(team_id)),
+                arg(&Into::<UserId>::into(user_id)),
+            ],
+        )
+    }
+
+    fn update_member_permission(
+        &self,
+        team_id: impl Into<TeamId>,
+        user_id: impl Into<UserId>,
+        request: &UpdateTeamMemberRequest,
+    ) -> Result<SuccessResponse> {
+        self.call(
+            "teams.update_member_permission",
+            vec![
+                arg(&Into::<TeamId>::into(team_id)),
+                arg(&Into::<UserId>::into(user_id)),
+                arg(request),
+            ],
+        )
+    }
+
+    fn remove_member(
+        &self,
+        team_id: impl Into<TeamId>,
+        user_id: impl Into<UserId>,
+    ) -> Result<SuccessResponse> {
+        self.call(
+            "teams.remove_member",
+            vec![
+                arg(&Into::<TeamId>::into(team_id)),
+                arg(&Into::<UserId>::into(user_id)),
+            ],
+        )
+    }
+}
+
+#[cfg(feature = "blocking-core")]
+impl api::BlockingServiceAccountsApi for MockApi {
+    fn search(&self, params: &ServiceAccountSearchParams) -> Result<ServiceAccountSearchResult> {
+        self.call("service_accounts.search", vec![arg(params)])
+    }
+
+    fn create(&self, request: &CreateServiceAccountRequest) -> Result<ServiceAccount> {
+        self.call("service_accounts.create", vec![arg(request)])
+    }
+
+    fn get_by_id(&self, service_account_id: impl Into<ServiceAccountId>) -> Result<ServiceAccount> {
+        self.call(
+            "service_accounts.get_by_id",
+            vec![arg(&Into::<ServiceAccountId>::into(service_account_id))],
+        )
+    }
+
+    fn update(
+        &self,
+        service_account_id: impl Into<ServiceAccountId>,
+        request: &UpdateServiceAccountRequest,
+    ) -> Result<UpdateServiceAccountResponse> {
+        self.call(
+            "service_accounts.update",
+            vec![
+                arg(&Into::<ServiceAccountId>::into(service_account_id)),
+                arg(request),
+            ],
+        )
+    }
+
+    fn delete(&self, service_account_id: impl Into<ServiceAccountId>) -> Result<SuccessResponse> {
+        self.call(
+            "service_accounts.delete",
+            vec![arg(&Into::<ServiceAccountId>::into(service_account_id))],
+        )
+    }
+
+    fn tokens(&self, service_account_id: impl Into<ServiceAccountId>) -> Result<Vec<Token>> {
+        self.call(
+            "service_accounts.tokens",
+            vec![arg(&Into::<ServiceAccountId>::into(service_account_id))],
+        )
+    }
+
+    fn create_token(
+        &self,
+        service_account_id: impl Into<ServiceAccountId>,
+        request: &CreateServiceAccountTokenRequest,
+    ) -> Result<NewApiKey> {
+        self.call(
+            "service_accounts.create_token",
+            vec![
+                arg(&Into::<ServiceAccountId>::into(service_account_id)),
+                arg(request),
+            ],
+        )
+    }
+
+    fn delete_token(
+        &self,
+        service_account_id: impl Into<ServiceAccountId>,
+        token_id: impl Into<TokenId>,
+    ) -> Result<SuccessResponse> {
+        self.call(
+            "service_accounts.delete_token",
+            vec![
+                arg(&Into::<ServiceAccountId>::into(service_account_id)),
+                arg(&Into::<TokenId>::into(token_id)),
+            ],
+        )
+    }
+}
+
+#[cfg(feature = "blocking-core")]
+impl api::BlockingOrgApi for MockApi {
+    fn get(&self) -> Result<OrgDetails> {
+        self.call("org.get", vec![])
+    }
+
+    fn update(&self, request: &UpdateOrgRequest) -> Result<SuccessResponse> {
+        self.call("org.update", vec![arg(request)])
+    }
+
+    fn users(&self) -> Result<Vec<OrgUser>> {
+        self.call("org.users", vec![])
+    }
+
+    fn add_user(&self, request: &AddOrgUserRequest) -> Result<SuccessResponse> {
+        self.call("org.add_user", vec![arg(request)])
+    }
+
+    fn update_user_role(
+        &self,
+        user_id: impl Into<UserId>,
+        request: &UpdateOrgUserRequest,
+    ) -> Result<SuccessResponse> {
+        self.call(
+            "org.update_user_role",
+            vec![arg(&Into::<UserId>::into(user_id)), arg(request)],
+        )
+    }
+
+    fn remove_user(&self, user_id: impl Into<UserId>) -> Result<SuccessResponse> {
+        self.call("org.remove_user", vec![arg(&Into::<UserId>::into(user_id))])
+    }
+}
+
+#[cfg(feature = "blocking-core")]
+impl api::BlockingUserApi for MockApi {
+    fn get_profile(&self) -> Result<UserProfile> {
+        self.call("user.get_profile", vec![])
+    }
+
+    fn update_profile(&self, request: &UpdateUserRequest) -> Result<SuccessResponse> {
+        self.call("user.update_profile", vec![arg(request)])
+    }
+
+    fn orgs(&self) -> Result<Vec<UserOrg>> {
+        self.call("user.orgs", vec![])
+    }
+
+    fn teams(&self) -> Result<Vec<Team>> {
+        self.call("user.teams", vec![])
+    }
+
+    fn switch_org(&self, org_id: impl Into<OrgId>) -> Result<SuccessResponse> {
+        self.call("user.switch_org", vec![arg(&Into::<OrgId>::into(org_id))])
+    }
+}
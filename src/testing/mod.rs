@@ -0,0 +1,249 @@
+//! In-process fake Grafana for downstream tests.
+//!
+//! [`FakeGrafana`] is a small HTTP server backed by an in-memory model. It implements the
+//! endpoints behind the typed services — dashboards, folders, datasources, search, teams,
+//! service accounts, org users, the signed-in user and health — statefully, so a test can create
+//! a folder, save a dashboard into it, find it through search and delete it again. Version
+//! conflicts, missing resources and bad credentials produce the same status codes Grafana uses.
+//!
+//! ```no_run
+//! # #[cfg(feature = "async")]
+//! # async fn demo() -> Result<(), grafana::Error> {
+//! use grafana::{Auth, Client, testing::FakeGrafana};
+//!
+//! let grafana = FakeGrafana::builder().bearer_token("TOKEN").start()?;
+//! let client = Client::builder(grafana.url())?
+//!     .auth(Auth::bearer("TOKEN"))
+//!     .build()?;
+//!
+//! let folder = client
+//!     .folders()
+//!     .create(&grafana::types::CreateFolderRequest::new("Ops"))
+//!     .await?;
+//! assert_eq!(folder.title, "Ops");
+//! # Ok(())
+//! # }
+//! ```
+
+mod http;
+#[cfg(any(feature = "async", feature = "blocking-core"))]
+mod mock;
+mod state;
+
+use std::{
+    fmt,
+    io::{BufReader, BufWriter},
+    net::{SocketAddr, TcpListener, TcpStream},
+    sync::{
+        Arc, Mutex, PoisonError,
+        atomic::{AtomicBool, Ordering},
+    },
+    thread::JoinHandle,
+};
+
+use base64::Engine as _;
+use serde_json::{Value, json};
+
+use crate::{
+    Error, Result,
+    types::{DatasourceId, UserId},
+};
+
+#[cfg(any(feature = "async", feature = "blocking-core"))]
+pub use self::mock::{MockApi, MockCall};
+use self::state::State;
+
+pub struct FakeGrafana {
+    address: SocketAddr,
+    shared: Arc<Shared>,
+    acceptor: Option<JoinHandle<()>>,
+}
+
+pub struct FakeGrafanaBuilder {
+    credentials: Vec<String>,
+    version: String,
+}
+
+struct Shared {
+    state: Mutex<State>,
+    credentials: Vec<String>,
+    shutdown: AtomicBool,
+}
+
+impl FakeGrafana {
+    pub fn builder() -> FakeGrafanaBuilder {
+        FakeGrafanaBuilder {
+            credentials: Vec::new(),
+            version: "11.0.0".to_owned(),
+        }
+    }
+
+    /// Starts a server that accepts every request without authentication.
+    pub fn start() -> Result<Self> {
+        Self::builder().start()
+    }
+
+    /// Base URL to pass to `Client::builder`.
+    pub fn url(&self) -> String {
+        format!("http://{}", self.address)
+    }
+
+    pub fn address(&self) -> SocketAddr {
+        self.address
+    }
+
+    /// Adds a user that exists on the server but is not yet a member of the org.
+    pub fn add_user(&self, login: &str, email: &str) -> UserId {
+        UserId(self.lock().add_user(login, email))
+    }
+
+    /// Adds a datasource from the same JSON body `POST /api/datasources` accepts.
+    pub fn add_datasource(&self, datasource: Value) -> Result<DatasourceId> {
+        self.lock()
+            .add_datasource(&datasource)
+            .map(DatasourceId)
+            .map_err(Error::invalid_config)
+    }
+
+    /// Current stored model of a dashboard, including the server-assigned `id` and `version`.
+    pub fn dashboard(&self, uid: &str) -> Option<Value> {
+        self.lock().dashboard(uid)
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, State> {
+        self.shared.lock()
+    }
+}
+
+impl Drop for FakeGrafana {
+    fn drop(&mut self) {
+        self.shared.shutdown.store(true, Ordering::SeqCst);
+        // Wake the acceptor so it notices the shutdown flag.
+        let _ = TcpStream::connect(self.address);
+        if let Some(acceptor) = self.acceptor.take() {
+            let _ = acceptor.join();
+        }
+    }
+}
+
+impl fmt::Debug for FakeGrafana {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FakeGrafana")
+            .field("address", &self.address)
+            .finish_non_exhaustive()
+    }
+}
+
+impl FakeGrafanaBuilder {
+    /// Requires `Authorization: Bearer <token>`. May be combined with other credentials.
+    pub fn bearer_token(mut self, token: impl Into<String>) -> Self {
+        self.credentials.push(format!("Bearer {}", token.into()));
+        self
+    }
+
+    /// Requires HTTP basic auth with these credentials. May be combined with other credentials.
+    pub fn basic_auth(mut self, username: impl AsRef<str>, password: impl AsRef<str>) -> Self {
+        let encoded = base64::engine::general_purpose::STANDARD.encode(format!(
+            "{}:{}",
+            username.as_ref(),
+            password.as_ref()
+        ));
+        self.credentials.push(format!("Basic {encoded}"));
+        self
+    }
+
+    /// Version reported by `GET /api/health`.
+    pub fn version(mut self, version: impl Into<String>) -> Self {
+        self.version = version.into();
+        self
+    }
+
+    pub fn start(self) -> Result<FakeGrafana> {
+        let listener = TcpListener::bind(("127.0.0.1", 0))
+            .map_err(|e| Error::transport("failed to bind fake Grafana server", e))?;
+        let address = listener
+            .local_addr()
+            .map_err(|e| Error::transport("failed to bind fake Grafana server", e))?;
+
+        let shared = Arc::new(Shared {
+            state: Mutex::new(State::new(self.version)),
+            credentials: self.credentials,
+            shutdown: AtomicBool::new(false),
+        });
+
+        let acceptor = {
+            let shared = Arc::clone(&shared);
+            std::thread::Builder::new()
+                .name("fake-grafana".to_owned())
+                .spawn(move || accept_loop(listener, shared))
+                .map_err(|e| Error::transport("failed to start fake Grafana server", e))?
+        };
+
+        Ok(FakeGrafana {
+            address,
+            shared,
+            acceptor: Some(acceptor),
+        })
+    }
+}
+
+impl Shared {
+    fn lock(&self) -> std::sync::MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn authorized(&self, request: &http::Request) -> bool {
+        if self.credentials.is_empty() {
+            return true;
+        }
+        request
+            .header("authorization")
+            .is_some_and(|value| self.credentials.iter().any(|expected| expected == value))
+    }
+
+    fn respond(&self, request: &http::Request) -> (u16, Value) {
+        let Some(segments) = request.api_segments() else {
+            return (404, json!({ "message": "Not found" }));
+        };
+        let segments: Vec<&str> = segments.iter().map(String::as_str).collect();
+
+        // Like Grafana, the health endpoint is reachable without credentials.
+        if segments != ["health"] && !self.authorized(request) {
+            return (401, json!({ "message": "Unauthorized" }));
+        }
+
+        let reply = self.lock().handle(request, &segments);
+        (reply.status, reply.body)
+    }
+}
+
+fn accept_loop(listener: TcpListener, shared: Arc<Shared>) {
+    for stream in listener.incoming() {
+        if shared.shutdown.load(Ordering::SeqCst) {
+            break;
+        }
+        let Ok(stream) = stream else {
+            continue;
+        };
+        let shared = Arc::clone(&shared);
+        let _ = std::thread::Builder::new()
+            .name("fake-grafana-conn".to_owned())
+            .spawn(move || serve_connection(stream, &shared));
+    }
+}
+
+fn serve_connection(stream: TcpStream, shared: &Shared) {
+    let Ok(write_half) = stream.try_clone() else {
+        return;
+    };
+    let mut reader = BufReader::new(stream);
+    let mut writer = BufWriter::new(write_half);
+
+    while let Ok(Some(request)) = http::read_request(&mut reader) {
+        let (status, body) = shared.respond(&request);
+        let keep_alive = request.keep_alive() && !shared.shutdown.load(Ordering::SeqCst);
+        if http::write_response(&mut writer, status, &body, keep_alive).is_err() || !keep_alive {
+            break;
+        }
+    }
+}
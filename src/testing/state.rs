@@ -0,0 +1,1318 @@
+//! In-memory Grafana model and the API routes that operate on it.
+
+use serde_json::{Map, Value, json};
+
+use super::http::Request;
+
+const ORG_ID: i64 = 1;
+const TIMESTAMP: &str = "2024-01-01T00:00:00Z";
+const ROLES: [&str; 4] = ["None", "Viewer", "Editor", "Admin"];
+
+pub(super) struct Reply {
+    pub status: u16,
+    pub body: Value,
+}
+
+impl Reply {
+    fn ok(body: Value) -> Self {
+        Self { status: 200, body }
+    }
+
+    fn message(status: u16, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            body: json!({ "message": message.into() }),
+        }
+    }
+}
+
+type Handled = Result<Reply, Reply>;
+
+pub(super) struct State {
+    version: String,
+    next_id: i64,
+    org_name: String,
+    current_user: i64,
+    users: Vec<User>,
+    folders: Vec<Folder>,
+    dashboards: Vec<Dashboard>,
+    datasources: Vec<Value>,
+    teams: Vec<Team>,
+    service_accounts: Vec<ServiceAccount>,
+}
+
+struct User {
+    id: i64,
+    uid: String,
+    login: String,
+    email: String,
+    name: String,
+    theme: String,
+    is_grafana_admin: bool,
+    org_role: Option<String>,
+}
+
+struct Folder {
+    id: i64,
+    uid: String,
+    title: String,
+    version: i64,
+}
+
+struct Dashboard {
+    id: i64,
+    uid: String,
+    title: String,
+    version: i64,
+    folder_uid: Option<String>,
+    tags: Vec<String>,
+    model: Value,
+}
+
+struct Team {
+    id: i64,
+    uid: String,
+    name: String,
+    email: String,
+    members: Vec<TeamMember>,
+}
+
+struct TeamMember {
+    user_id: i64,
+    permission: i64,
+}
+
+struct ServiceAccount {
+    id: i64,
+    uid: String,
+    name: String,
+    login: String,
+    role: String,
+    is_disabled: bool,
+    tokens: Vec<ServiceAccountToken>,
+}
+
+struct ServiceAccountToken {
+    id: i64,
+    name: String,
+    seconds_to_live: Option<i64>,
+}
+
+impl State {
+    pub(super) fn new(version: String) -> Self {
+        Self {
+            version,
+            next_id: 2,
+            org_name: "Main Org.".to_owned(),
+            current_user: 1,
+            users: vec![User {
+                id: 1,
+                uid: new_uid(),
+                login: "admin".to_owned(),
+                email: "admin@localhost".to_owned(),
+                name: String::new(),
+                theme: String::new(),
+                is_grafana_admin: true,
+                org_role: Some("Admin".to_owned()),
+            }],
+            folders: Vec::new(),
+            dashboards: Vec::new(),
+            datasources: Vec::new(),
+            teams: Vec::new(),
+            service_accounts: Vec::new(),
+        }
+    }
+
+    pub(super) fn handle(&mut self, request: &Request, segments: &[&str]) -> Reply {
+        match self.route(request, segments) {
+            Ok(reply) | Err(reply) => reply,
+        }
+    }
+
+    pub(super) fn add_user(&mut self, login: &str, email: &str) -> i64 {
+        let id = self.next_id();
+        self.users.push(User {
+            id,
+            uid: new_uid(),
+            login: login.to_owned(),
+            email: email.to_owned(),
+            name: login.to_owned(),
+            theme: String::new(),
+            is_grafana_admin: false,
+            org_role: None,
+        });
+        id
+    }
+
+    pub(super) fn add_datasource(&mut self, body: &Value) -> Result<i64, String> {
+        match self.create_datasource(body) {
+            Ok(reply) => Ok(reply.body["id"].as_i64().unwrap_or_default()),
+            Err(reply) => Err(reply.body["message"]
+                .as_str()
+                .unwrap_or_default()
+                .to_owned()),
+        }
+    }
+
+    pub(super) fn dashboard(&self, uid: &str) -> Option<Value> {
+        self.dashboards
+            .iter()
+            .find(|dashboard| dashboard.uid == uid)
+            .map(|dashboard| dashboard.model.clone())
+    }
+
+    fn route(&mut self, request: &Request, segments: &[&str]) -> Handled {
+        match (request.method.as_str(), segments) {
+            ("GET", ["health"]) => Ok(Reply::ok(json!({
+                "commit": "fake",
+                "database": "ok",
+                "version": self.version,
+            }))),
+
+            ("GET", ["dashboards", "uid", uid]) => self.get_dashboard(uid),
+            ("POST", ["dashboards", "db"]) => self.save_dashboard(&json_body(request)?),
+            ("DELETE", ["dashboards", "uid", uid]) => self.delete_dashboard(uid),
+
+            ("GET", ["folders"]) => Ok(Reply::ok(Value::Array(
+                self.folders.iter().map(Folder::to_json).collect(),
+            ))),
+            ("POST", ["folders"]) => self.create_folder(&json_body(request)?),
+            ("GET", ["folders", uid]) => Ok(Reply::ok(self.folder(uid)?.to_json())),
+            ("PUT", ["folders", uid]) => self.update_folder(uid, &json_body(request)?),
+            ("DELETE", ["folders", uid]) => self.delete_folder(uid),
+
+            ("GET", ["search"]) => Ok(Reply::ok(self.search(request))),
+
+            ("GET", ["datasources"]) => Ok(Reply::ok(Value::Array(self.datasources.clone()))),
+            ("POST", ["datasources"]) => self.create_datasource(&json_body(request)?),
+            ("GET", ["datasources", "uid", uid]) => {
+                let index = self.datasource_index(|ds| ds["uid"] == *uid)?;
+                Ok(Reply::ok(self.datasources[index].clone()))
+            }
+            ("GET", ["datasources", id]) => {
+                let id = parse_id(id)?;
+                let index = self.datasource_index(|ds| ds["id"] == id)?;
+                Ok(Reply::ok(self.datasources[index].clone()))
+            }
+            ("DELETE", ["datasources", id]) => {
+                let id = parse_id(id)?;
+                let index = self.datasource_index(|ds| ds["id"] == id)?;
+                self.datasources.remove(index);
+                Ok(Reply::message(200, "Data source deleted"))
+            }
+
+            ("GET", ["teams", "search"]) => Ok(Reply::ok(self.search_teams(request))),
+            ("POST", ["teams"]) => self.create_team(&json_body(request)?),
+            ("GET", ["teams", id]) => {
+                let team = self.team_index(id)?;
+                Ok(Reply::ok(self.teams[team].to_json()))
+            }
+            ("PUT", ["teams", id]) => self.update_team(id, &json_body(request)?),
+            ("DELETE", ["teams", id]) => {
+                let team = self.team_index(id)?;
+                self.teams.remove(team);
+                Ok(Reply::message(200, "Team deleted"))
+            }
+            ("GET", ["teams", id, "members"]) => {
+                let team = self.team_index(id)?;
+                Ok(Reply::ok(self.team_members(team)))
+            }
+            ("POST", ["teams", id, "members"]) => self.add_team_member(id, &json_body(request)?),
+            ("PUT", ["teams", id, "members", user_id]) => {
+                self.update_team_member(id, user_id, &json_body(request)?)
+            }
+            ("DELETE", ["teams", id, "members", user_id]) => {
+                let team = self.team_index(id)?;
+                let member = self.team_member_index(team, user_id)?;
+                self.teams[team].members.remove(member);
+                Ok(Reply::message(200, "Team Member removed"))
+            }
+
+            ("GET", ["serviceaccounts", "search"]) => {
+                Ok(Reply::ok(self.search_service_accounts(request)))
+            }
+            ("POST", ["serviceaccounts"]) => self.create_service_account(&json_body(request)?),
+            ("GET", ["serviceaccounts", id]) => {
+                let account = self.service_account_index(id)?;
+                Ok(Reply::ok(self.service_accounts[account].to_json()))
+            }
+            ("PATCH", ["serviceaccounts", id]) => {
+                self.update_service_account(id, &json_body(request)?)
+            }
+            ("DELETE", ["serviceaccounts", id]) => {
+                let account = self.service_account_index(id)?;
+                self.service_accounts.remove(account);
+                Ok(Reply::message(200, "Service account deleted"))
+            }
+            ("GET", ["serviceaccounts", id, "tokens"]) => {
+                let account = self.service_account_index(id)?;
+                Ok(Reply::ok(Value::Array(
+                    self.service_accounts[account]
+                        .tokens
+                        .iter()
+                        .map(ServiceAccountToken::to_json)
+                        .collect(),
+                )))
+            }
+            ("POST", ["serviceaccounts", id, "tokens"]) => {
+                self.create_service_account_token(id, &json_body(request)?)
+            }
+            ("DELETE", ["serviceaccounts", id, "tokens", token_id]) => {
+                let account = self.service_account_index(id)?;
+                let token_id = parse_id(token_id)?;
+                let tokens = &mut self.service_accounts[account].tokens;
+                let index = tokens
+                    .iter()
+                    .position(|token| token.id == token_id)
+                    .ok_or_else(|| Reply::message(404, "service account token not found"))?;
+                tokens.remove(index);
+                Ok(Reply::message(200, "Service account token deleted"))
+            }
+
+            ("GET", ["org"]) => Ok(Reply::ok(json!({
+                "id": ORG_ID,
+                "name": self.org_name,
+                "address": {
+                    "address1": "",
+                    "address2": "",
+                    "city": "",
+                    "zipCode": "",
+                    "state": "",
+                    "country": "",
+                },
+            }))),
+            ("PUT", ["org"]) => {
+                let body = json_body(request)?;
+                let name = str_field(&body, "name")
+                    .ok_or_else(|| Reply::message(400, "Organization name cannot be empty"))?;
+                self.org_name = name.to_owned();
+                Ok(Reply::message(200, "Organization updated"))
+            }
+            ("GET", ["org", "users"]) => Ok(Reply::ok(Value::Array(
+                self.users
+                    .iter()
+                    .filter(|user| user.org_role.is_some())
+                    .map(User::to_org_user_json)
+                    .collect(),
+            ))),
+            ("POST", ["org", "users"]) => self.add_org_user(&json_body(request)?),
+            ("PATCH", ["org", "users", user_id]) => {
+                let user = self.org_user_index(user_id)?;
+                let body = json_body(request)?;
+                self.users[user].org_role = Some(parse_role(&body)?);
+                Ok(Reply::message(200, "Organization user updated"))
+            }
+            ("DELETE", ["org", "users", user_id]) => {
+                let user = self.org_user_index(user_id)?;
+                let user_id = self.users[user].id;
+                self.users[user].org_role = None;
+                for team in &mut self.teams {
+                    team.members.retain(|member| member.user_id != user_id);
+                }
+                Ok(Reply::message(200, "User removed from organization"))
+            }
+
+            ("GET", ["user"]) => Ok(Reply::ok(self.current_user().to_profile_json())),
+            ("PUT", ["user"]) => self.update_current_user(&json_body(request)?),
+            ("GET", ["user", "orgs"]) => {
+                let user = self.current_user();
+                let orgs = match &user.org_role {
+                    Some(role) => vec![json!({
+                        "orgId": ORG_ID,
+                        "name": self.org_name,
+                        "role": role,
+                    })],
+                    None => Vec::new(),
+                };
+                Ok(Reply::ok(Value::Array(orgs)))
+            }
+            ("GET", ["user", "teams"]) => {
+                let user_id = self.current_user;
+                Ok(Reply::ok(Value::Array(
+                    self.teams
+                        .iter()
+                        .filter(|team| team.members.iter().any(|m| m.user_id == user_id))
+                        .map(Team::to_json)
+                        .collect(),
+                )))
+            }
+            ("POST", ["user", "using", org_id]) => {
+                if parse_id(org_id)? != ORG_ID {
+                    return Err(Reply::message(401, "Not a valid organization"));
+                }
+                Ok(Reply::message(200, "Active organization changed"))
+            }
+
+            _ => Err(Reply::message(404, "Not found")),
+        }
+    }
+
+    fn next_id(&mut self) -> i64 {
+        let id = self.next_id;
+        self.next_id += 1;
+        id
+    }
+
+    // Dashboards
+
+    fn get_dashboard(&self, uid: &str) -> Handled {
+        let dashboard = self.dashboard_by_uid(uid)?;
+        let folder = dashboard
+            .folder_uid
+            .as_deref()
+            .and_then(|uid| self.folders.iter().find(|folder| folder.uid == uid));
+
+        Ok(Reply::ok(json!({
+            "dashboard": dashboard.model,
+            "meta": {
+                "type": "db",
+                "slug": slugify(&dashboard.title),
+                "url": dashboard.url(),
+                "version": dashboard.version,
+                "created": TIMESTAMP,
+                "updated": TIMESTAMP,
+                "canSave": true,
+                "canEdit": true,
+                "isFolder": false,
+                "folderId": folder.map(|folder| folder.id).unwrap_or_default(),
+                "folderUid": folder.map(|folder| folder.uid.as_str()).unwrap_or_default(),
+                "folderTitle": folder.map(|folder| folder.title.as_str()).unwrap_or("General"),
+            },
+        })))
+    }
+
+    fn save_dashboard(&mut self, body: &Value) -> Handled {
+        let Some(model) = body.get("dashboard").and_then(Value::as_object) else {
+            return Err(Reply::message(400, "bad request data"));
+        };
+        let title = str_field(&body["dashboard"], "title")
+            .ok_or_else(|| Reply::message(400, "Dashboard title cannot be empty"))?
+            .to_owned();
+        let overwrite = body["overwrite"].as_bool().unwrap_or(false);
+
+        let folder_uid = str_field(body, "folderUid").map(str::to_owned);
+        if let Some(folder_uid) = &folder_uid {
+            self.folder(folder_uid)?;
+        }
+
+        let uid = str_field(&body["dashboard"], "uid")
+            .map(str::to_owned)
+            .unwrap_or_else(new_uid);
+        let existing = self.dashboards.iter().position(|d| d.uid == uid);
+
+        if !overwrite {
+            if let Some(index) = existing {
+                let sent_version = model.get("version").and_then(Value::as_i64);
+                if sent_version != Some(self.dashboards[index].version) {
+                    return Err(Reply {
+                        status: 412,
+                        body: json!({
+                            "status": "version-mismatch",
+                            "message": "The dashboard has been changed by someone else",
+                        }),
+                    });
+                }
+            }
+            let name_taken = self.dashboards.iter().any(|d| {
+                d.uid != uid && d.folder_uid == folder_uid && d.title.eq_ignore_ascii_case(&title)
+            });
+            if name_taken {
+                return Err(Reply {
+                    status: 412,
+                    body: json!({
+                        "status": "name-exists",
+                        "message": "A dashboard with the same name in the folder already exists",
+                    }),
+                });
+            }
+        }
+
+        if existing.is_none() && model.get("id").is_some_and(|id| !id.is_null()) {
+            return Err(Reply {
+                status: 404,
+                body: json!({ "status": "not-found", "message": "Dashboard not found" }),
+            });
+        }
+
+        let (id, version) = match existing {
+            Some(index) => (
+                self.dashboards[index].id,
+                self.dashboards[index].version + 1,
+            ),
+            None => (self.next_id(), 1),
+        };
+
+        let mut model: Map<String, Value> = model.clone();
+        model.insert("id".to_owned(), json!(id));
+        model.insert("uid".to_owned(), json!(uid));
+        model.insert("version".to_owned(), json!(version));
+        let tags = model
+            .get("tags")
+            .and_then(Value::as_array)
+            .map(|tags| {
+                tags.iter()
+                    .filter_map(Value::as_str)
+                    .map(str::to_owned)
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let dashboard = Dashboard {
+            id,
+            uid: uid.clone(),
+            title,
+            version,
+            folder_uid,
+            tags,
+            model: Value::Object(model),
+        };
+        let reply = json!({
+            "id": id,
+            "uid": uid,
+            "url": dashboard.url(),
+            "status": "success",
+            "slug": slugify(&dashboard.title),
+            "version": version,
+        });
+
+        match existing {
+            Some(index) => self.dashboards[index] = dashboard,
+            None => self.dashboards.push(dashboard),
+        }
+        Ok(Reply::ok(reply))
+    }
+
+    fn delete_dashboard(&mut self, uid: &str) -> Handled {
+        let index = self
+            .dashboards
+            .iter()
+            .position(|dashboard| dashboard.uid == uid)
+            .ok_or_else(dashboard_not_found)?;
+        let dashboard = self.dashboards.remove(index);
+        Ok(Reply::ok(json!({
+            "id": dashboard.id,
+            "title": dashboard.title,
+            "message": format!("Dashboard {} deleted", dashboard.title),
+        })))
+    }
+
+    fn dashboard_by_uid(&self, uid: &str) -> Result<&Dashboard, Reply> {
+        self.dashboards
+            .iter()
+            .find(|dashboard| dashboard.uid == uid)
+            .ok_or_else(dashboard_not_found)
+    }
+
+    // Folders
+
+    fn folder(&self, uid: &str) -> Result<&Folder, Reply> {
+        self.folders
+            .iter()
+            .find(|folder| folder.uid == uid)
+            .ok_or_else(|| Reply::message(404, "folder not found"))
+    }
+
+    fn create_folder(&mut self, body: &Value) -> Handled {
+        let title = str_field(body, "title")
+            .ok_or_else(|| Reply::message(400, "folder title cannot be empty"))?
+            .to_owned();
+        let uid = str_field(body, "uid")
+            .map(str::to_owned)
+            .unwrap_or_else(new_uid);
+
+        if self.folders.iter().any(|folder| folder.uid == uid) {
+            return Err(Reply::message(
+                409,
+                "a folder with the same uid already exists",
+            ));
+        }
+        if self
+            .folders
+            .iter()
+            .any(|folder| folder.title.eq_ignore_ascii_case(&title))
+        {
+            return Err(Reply::message(
+                409,
+                "a folder or dashboard in the general folder with the same name already exists",
+            ));
+        }
+
+        let folder = Folder {
+            id: self.next_id(),
+            uid,
+            title,
+            version: 1,
+        };
+        let reply = folder.to_json();
+        self.folders.push(folder);
+        Ok(Reply::ok(reply))
+    }
+
+    fn update_folder(&mut self, uid: &str, body: &Value) -> Handled {
+        let title = str_field(body, "title")
+            .ok_or_else(|| Reply::message(400, "folder title cannot be empty"))?
+            .to_owned();
+        let index = self
+            .folders
+            .iter()
+            .position(|folder| folder.uid == uid)
+            .ok_or_else(|| Reply::message(404, "folder not found"))?;
+
+        let overwrite = body["overwrite"].as_bool().unwrap_or(false);
+        if let Some(version) = body["version"].as_i64()
+            && !overwrite
+            && version != self.folders[index].version
+        {
+            return Err(Reply::message(
+                412,
+                "the folder has been changed by someone else",
+            ));
+        }
+        if self
+            .folders
+            .iter()
+            .any(|folder| folder.uid != uid && folder.title.eq_ignore_ascii_case(&title))
+        {
+            return Err(Reply::message(
+                409,
+                "a folder or dashboard in the general folder with the same name already exists",
+            ));
+        }
+
+        let folder = &mut self.folders[index];
+        folder.title = title;
+        folder.version += 1;
+        Ok(Reply::ok(folder.to_json()))
+    }
+
+    fn delete_folder(&mut self, uid: &str) -> Handled {
+        let index = self
+            .folders
+            .iter()
+            .position(|folder| folder.uid == uid)
+            .ok_or_else(|| Reply::message(404, "folder not found"))?;
+        let folder = self.folders.remove(index);
+        self.dashboards
+            .retain(|dashboard| dashboard.folder_uid.as_deref() != Some(uid));
+
+        Ok(Reply::ok(json!({
+            "id": folder.id,
+            "title": folder.title,
+            "message": "Folder deleted",
+        })))
+    }
+
+    // Search
+
+    fn search(&self, request: &Request) -> Value {
+        let query = request.query_param("query").map(str::to_lowercase);
+        let kind = request.query_param("type");
+        let tags = request.query_all("tag");
+        let folder_uids = request.query_all("folderUIDs");
+        let dashboard_uids = request.query_all("dashboardUIDs");
+        let matches_query = |title: &str| {
+            query
+                .as_deref()
+                .is_none_or(|query| title.to_lowercase().contains(query))
+        };
+
+        let mut hits: Vec<(String, Value)> = Vec::new();
+        if kind.is_none_or(|kind| kind == "dash-folder")
+            && tags.is_empty()
+            && dashboard_uids.is_empty()
+        {
+            for folder in &self.folders {
+                if matches_query(&folder.title)
+                    && (folder_uids.is_empty() || folder_uids.contains(&"general"))
+                {
+                    hits.push((folder.title.to_lowercase(), folder.to_search_json()));
+                }
+            }
+        }
+        if kind.is_none_or(|kind| kind == "dash-db") {
+            for dashboard in &self.dashboards {
+                let folder_uid = dashboard.folder_uid.as_deref().unwrap_or("general");
+                if matches_query(&dashboard.title)
+                    && tags
+                        .iter()
+                        .all(|tag| dashboard.tags.iter().any(|t| t == tag))
+                    && (folder_uids.is_empty() || folder_uids.contains(&folder_uid))
+                    && (dashboard_uids.is_empty()
+                        || dashboard_uids.contains(&dashboard.uid.as_str()))
+                {
+                    hits.push((
+                        dashboard.title.to_lowercase(),
+                        self.dashboard_search_json(dashboard),
+                    ));
+                }
+            }
+        }
+        hits.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let hits = hits.into_iter().map(|(_, hit)| hit).collect();
+        Value::Array(paginate(
+            hits,
+            request.query_param("page"),
+            request.query_param("limit"),
+        ))
+    }
+
+    fn dashboard_search_json(&self, dashboard: &Dashboard) -> Value {
+        let mut hit = json!({
+            "id": dashboard.id,
+            "uid": dashboard.uid,
+            "title": dashboard.title,
+            "uri": format!("db/{}", slugify(&dashboard.title)),
+            "url": dashboard.url(),
+            "slug": "",
+            "type": "dash-db",
+            "tags": dashboard.tags,
+            "isStarred": false,
+        });
+        if let Some(folder) = dashboard
+            .folder_uid
+            .as_deref()
+            .and_then(|uid| self.folders.iter().find(|folder| folder.uid == uid))
+        {
+            hit["folderId"] = json!(folder.id);
+            hit["folderUid"] = json!(folder.uid);
+            hit["folderTitle"] = json!(folder.title);
+            hit["folderUrl"] = json!(folder.url());
+        }
+        hit
+    }
+
+    // Datasources
+
+    fn create_datasource(&mut self, body: &Value) -> Handled {
+        let name = str_field(body, "name")
+            .ok_or_else(|| Reply::message(400, "Name is required"))?
+            .to_owned();
+        if self.datasources.iter().any(|ds| ds["name"] == name) {
+            return Err(Reply::message(
+                409,
+                "data source with the same name already exists",
+            ));
+        }
+        let uid = str_field(body, "uid")
+            .map(str::to_owned)
+            .unwrap_or_else(new_uid);
+        if self.datasources.iter().any(|ds| ds["uid"] == uid) {
+            return Err(Reply::message(
+                409,
+                "data source with the same uid already exists",
+            ));
+        }
+
+        let secure_fields: Map<String, Value> = body["secureJsonData"]
+            .as_object()
+            .map(|secure| {
+                secure
+                    .keys()
+                    .map(|key| (key.clone(), json!(true)))
+                    .collect()
+            })
+            .unwrap_or_default();
+        let id = self.next_id();
+        let datasource = json!({
+            "id": id,
+            "uid": uid,
+            "orgId": ORG_ID,
+            "name": name,
+            "type": str_field(body, "type").unwrap_or_default(),
+            "access": str_field(body, "access").unwrap_or("proxy"),
+            "url": str_field(body, "url").unwrap_or_default(),
+            "database": str_field(body, "database").unwrap_or_default(),
+            "basicAuth": body["basicAuth"].as_bool().unwrap_or(false),
+            "isDefault": body["isDefault"].as_bool().unwrap_or(false),
+            "jsonData": body.get("jsonData").cloned().unwrap_or_else(|| json!({})),
+            "secureJsonFields": secure_fields,
+            "readOnly": false,
+            "version": 1,
+        });
+        self.datasources.push(datasource.clone());
+
+        Ok(Reply::ok(json!({
+            "datasource": datasource,
+            "id": id,
+            "message": "Datasource added",
+            "name": name,
+        })))
+    }
+
+    fn datasource_index(&self, predicate: impl Fn(&Value) -> bool) -> Result<usize, Reply> {
+        self.datasources
+            .iter()
+            .position(predicate)
+            .ok_or_else(|| Reply::message(404, "Data source not found"))
+    }
+
+    // Teams
+
+    fn team_index(&self, id: &str) -> Result<usize, Reply> {
+        let id = parse_id(id)?;
+        self.teams
+            .iter()
+            .position(|team| team.id == id)
+            .ok_or_else(|| Reply::message(404, "Team not found"))
+    }
+
+    fn team_member_index(&self, team: usize, user_id: &str) -> Result<usize, Reply> {
+        let user_id = parse_id(user_id)?;
+        self.teams[team]
+            .members
+            .iter()
+            .position(|member| member.user_id == user_id)
+            .ok_or_else(|| Reply::message(404, "Team member not found"))
+    }
+
+    fn search_teams(&self, request: &Request) -> Value {
+        let query = request.query_param("query").map(str::to_lowercase);
+        let name = request.query_param("name");
+        let teams: Vec<Value> = self
+            .teams
+            .iter()
+            .filter(|team| {
+                query
+                    .as_deref()
+                    .is_none_or(|query| team.name.to_lowercase().contains(query))
+                    && name.is_none_or(|name| team.name == name)
+            })
+            .map(Team::to_json)
+            .collect();
+        let total = teams.len();
+        let (page, per_page) =
+            page_params(request.query_param("page"), request.query_param("perpage"));
+
+        json!({
+            "totalCount": total,
+            "teams": paginate(teams, Some(&page.to_string()), Some(&per_page.to_string())),
+            "page": page,
+            "perPage": per_page,
+        })
+    }
+
+    fn create_team(&mut self, body: &Value) -> Handled {
+        let name = str_field(body, "name")
+            .ok_or_else(|| Reply::message(400, "Team name is required"))?
+            .to_owned();
+        if self.teams.iter().any(|team| team.name == name) {
+            return Err(Reply::message(409, "Team name taken"));
+        }
+
+        let team = Team {
+            id: self.next_id(),
+            uid: new_uid(),
+            name,
+            email: str_field(body, "email").unwrap_or_default().to_owned(),
+            members: Vec::new(),
+        };
+        let reply = json!({
+            "message": "Team created",
+            "teamId": team.id,
+            "uid": team.uid,
+        });
+        self.teams.push(team);
+        Ok(Reply::ok(reply))
+    }
+
+    fn update_team(&mut self, id: &str, body: &Value) -> Handled {
+        let index = self.team_index(id)?;
+        if let Some(name) = str_field(body, "name") {
+            if self
+                .teams
+                .iter()
+                .any(|team| team.name == name && team.id != self.teams[index].id)
+            {
+                return Err(Reply::message(409, "Team name taken"));
+            }
+            self.teams[index].name = name.to_owned();
+        }
+        if let Some(email) = body["email"].as_str() {
+            self.teams[index].email = email.to_owned();
+        }
+        Ok(Reply::message(200, "Team updated"))
+    }
+
+    fn team_members(&self, team: usize) -> Value {
+        let team = &self.teams[team];
+        Value::Array(
+            team.members
+                .iter()
+                .filter_map(|member| {
+                    let user = self.users.iter().find(|user| user.id == member.user_id)?;
+                    Some(json!({
+                        "orgId": ORG_ID,
+                        "teamId": team.id,
+                        "teamUID": team.uid,
+                        "userId": user.id,
+                        "userUID": user.uid,
+                        "uid": user.uid,
+                        "auth_module": "",
+                        "email": user.email,
+                        "name": user.name,
+                        "login": user.login,
+                        "avatarUrl": avatar_url(&user.email),
+                        "labels": [],
+                        "permission": member.permission,
+                    }))
+                })
+                .collect(),
+        )
+    }
+
+    fn add_team_member(&mut self, id: &str, body: &Value) -> Handled {
+        let team = self.team_index(id)?;
+        let user_id = body["userId"]
+            .as_i64()
+            .ok_or_else(|| Reply::message(400, "bad request data"))?;
+        if !self.users.iter().any(|user| user.id == user_id) {
+            return Err(Reply::message(404, "User not found"));
+        }
+        let members = &mut self.teams[team].members;
+        if members.iter().any(|member| member.user_id == user_id) {
+            return Err(Reply::message(400, "User is already added to this team"));
+        }
+        members.push(TeamMember {
+            user_id,
+            permission: 0,
+        });
+        Ok(Reply::message(200, "Member added to Team"))
+    }
+
+    fn update_team_member(&mut self, id: &str, user_id: &str, body: &Value) -> Handled {
+        let team = self.team_index(id)?;
+        let member = self.team_member_index(team, user_id)?;
+        self.teams[team].members[member].permission = body["permission"].as_i64().unwrap_or(0);
+        Ok(Reply::message(200, "Team member updated"))
+    }
+
+    // Service accounts
+
+    fn service_account_index(&self, id: &str) -> Result<usize, Reply> {
+        let id = parse_id(id)?;
+        self.service_accounts
+            .iter()
+            .position(|account| account.id == id)
+            .ok_or_else(|| Reply::message(404, "service account not found"))
+    }
+
+    fn search_service_accounts(&self, request: &Request) -> Value {
+        let query = request.query_param("query").map(str::to_lowercase);
+        let disabled = request
+            .query_param("disabled")
+            .and_then(|value| value.parse::<bool>().ok());
+        let accounts: Vec<Value> = self
+            .service_accounts
+            .iter()
+            .filter(|account| {
+                query.as_deref().is_none_or(|query| {
+                    account.name.to_lowercase().contains(query)
+                        || account.login.to_lowercase().contains(query)
+                }) && disabled.is_none_or(|disabled| account.is_disabled == disabled)
+            })
+            .map(ServiceAccount::to_json)
+            .collect();
+        let total = accounts.len();
+        let (page, per_page) =
+            page_params(request.query_param("page"), request.query_param("perpage"));
+
+        json!({
+            "totalCount": total,
+            "serviceAccounts": paginate(accounts, Some(&page.to_string()), Some(&per_page.to_string())),
+            "page": page,
+            "perPage": per_page,
+        })
+    }
+
+    fn create_service_account(&mut self, body: &Value) -> Handled {
+        let name = str_field(body, "name")
+            .ok_or_else(|| Reply::message(400, "required value Name must not be empty"))?
+            .to_owned();
+        let role = match str_field(body, "role") {
+            Some(role) => valid_role(role)?,
+            None => "Viewer".to_owned(),
+        };
+        if self
+            .service_accounts
+            .iter()
+            .any(|account| account.name == name)
+        {
+            return Err(Reply::message(409, "service account already exists"));
+        }
+
+        let id = self.next_id();
+        let account = ServiceAccount {
+            id,
+            uid: new_uid(),
+            login: format!("sa-{ORG_ID}-{}", slugify(&name)),
+            name,
+            role,
+            is_disabled: body["isDisabled"].as_bool().unwrap_or(false),
+            tokens: Vec::new(),
+        };
+        let reply = account.to_json();
+        self.service_accounts.push(account);
+        Ok(Reply {
+            status: 201,
+            body: reply,
+        })
+    }
+
+    fn update_service_account(&mut self, id: &str, body: &Value) -> Handled {
+        let index = self.service_account_index(id)?;
+        let role = str_field(body, "role").map(valid_role).transpose()?;
+        if let Some(name) = str_field(body, "name")
+            && self.service_accounts.iter().any(|account| {
+                account.name == name && account.id != self.service_accounts[index].id
+            })
+        {
+            return Err(Reply::message(409, "service account already exists"));
+        }
+
+        let account = &mut self.service_accounts[index];
+        if let Some(name) = str_field(body, "name") {
+            account.name = name.to_owned();
+        }
+        if let Some(role) = role {
+            account.role = role;
+        }
+        if let Some(disabled) = body["isDisabled"].as_bool() {
+            account.is_disabled = disabled;
+        }
+
+        let mut profile = account.to_json();
+        profile["createdAt"] = json!(TIMESTAMP);
+        profile["updatedAt"] = json!(TIMESTAMP);
+        profile["teams"] = json!([]);
+        Ok(Reply::ok(json!({
+            "id": account.id,
+            "name": account.name,
+            "message": "Service account updated",
+            "serviceaccount": profile,
+        })))
+    }
+
+    fn create_service_account_token(&mut self, id: &str, body: &Value) -> Handled {
+        let index = self.service_account_index(id)?;
+        let name = str_field(body, "name")
+            .ok_or_else(|| Reply::message(400, "token name is required"))?
+            .to_owned();
+        if self.service_accounts[index]
+            .tokens
+            .iter()
+            .any(|token| token.name == name)
+        {
+            return Err(Reply::message(
+                409,
+                "service account token with given name already exists in the organization",
+            ));
+        }
+
+        let token = ServiceAccountToken {
+            id: self.next_id(),
+            name,
+            seconds_to_live: body["secondsToLive"].as_i64().filter(|ttl| *ttl > 0),
+        };
+        let reply = json!({
+            "id": token.id,
+            "name": token.name,
+            "key": format!("glsa_{}_{}", fastrand_token(32), fastrand_token(8)),
+        });
+        self.service_accounts[index].tokens.push(token);
+        Ok(Reply::ok(reply))
+    }
+
+    // Org and users
+
+    fn org_user_index(&self, user_id: &str) -> Result<usize, Reply> {
+        let user_id = parse_id(user_id)?;
+        self.users
+            .iter()
+            .position(|user| user.id == user_id && user.org_role.is_some())
+            .ok_or_else(|| Reply::message(404, "user not found"))
+    }
+
+    fn add_org_user(&mut self, body: &Value) -> Handled {
+        let login = str_field(body, "loginOrEmail")
+            .ok_or_else(|| Reply::message(400, "bad request data"))?;
+        let role = parse_role(body)?;
+        let user = self
+            .users
+            .iter_mut()
+            .find(|user| user.login == login || user.email == login)
+            .ok_or_else(|| Reply::message(404, "User not found"))?;
+        if user.org_role.is_some() {
+            return Err(Reply::message(
+                409,
+                "User is already member of this organization",
+            ));
+        }
+
+        user.org_role = Some(role);
+        Ok(Reply::ok(json!({
+            "message": "User added to organization",
+            "userId": user.id,
+        })))
+    }
+
+    fn current_user(&self) -> &User {
+        self.users
+            .iter()
+            .find(|user| user.id == self.current_user)
+            .expect("the signed-in user always exists")
+    }
+
+    fn update_current_user(&mut self, body: &Value) -> Handled {
+        let current = self.current_user;
+        if let Some(login) = str_field(body, "login")
+            && self
+                .users
+                .iter()
+                .any(|user| user.login == login && user.id != current)
+        {
+            return Err(Reply::message(409, "Username already taken"));
+        }
+
+        let user = self
+            .users
+            .iter_mut()
+            .find(|user| user.id == current)
+            .expect("the signed-in user always exists");
+        if let Some(login) = str_field(body, "login") {
+            user.login = login.to_owned();
+        }
+        if let Some(email) = str_field(body, "email") {
+            user.email = email.to_owned();
+        }
+        if let Some(name) = body["name"].as_str() {
+            user.name = name.to_owned();
+        }
+        if let Some(theme) = body["theme"].as_str() {
+            user.theme = theme.to_owned();
+        }
+        Ok(Reply::message(200, "User updated"))
+    }
+}
+
+impl User {
+    fn to_org_user_json(&self) -> Value {
+        json!({
+            "orgId": ORG_ID,
+            "userId": self.id,
+            "uid": self.uid,
+            "email": self.email,
+            "name": self.name,
+            "login": self.login,
+            "avatarUrl": avatar_url(&self.email),
+            "role": self.org_role,
+            "lastSeenAt": TIMESTAMP,
+            "lastSeenAtAge": "< 1 minute",
+            "isDisabled": false,
+            "isExternallySynced": false,
+            "isProvisioned": false,
+            "authLabels": [],
+        })
+    }
+
+    fn to_profile_json(&self) -> Value {
+        json!({
+            "id": self.id,
+            "uid": self.uid,
+            "email": self.email,
+            "name": self.name,
+            "login": self.login,
+            "theme": self.theme,
+            "orgId": ORG_ID,
+            "isGrafanaAdmin": self.is_grafana_admin,
+            "isDisabled": false,
+            "isExternal": false,
+            "isExternallySynced": false,
+            "isGrafanaAdminExternallySynced": false,
+            "isProvisioned": false,
+            "authLabels": [],
+            "avatarUrl": avatar_url(&self.email),
+            "createdAt": TIMESTAMP,
+            "updatedAt": TIMESTAMP,
+        })
+    }
+}
+
+impl Folder {
+    fn url(&self) -> String {
+        format!("/dashboards/f/{}/{}", self.uid, slugify(&self.title))
+    }
+
+    fn to_json(&self) -> Value {
+        json!({
+            "id": self.id,
+            "uid": self.uid,
+            "title": self.title,
+            "url": self.url(),
+            "version": self.version,
+            "hasAcl": false,
+            "canSave": true,
+            "canEdit": true,
+            "canAdmin": true,
+            "canDelete": true,
+            "created": TIMESTAMP,
+            "updated": TIMESTAMP,
+        })
+    }
+
+    fn to_search_json(&self) -> Value {
+        json!({
+            "id": self.id,
+            "uid": self.uid,
+            "title": self.title,
+            "uri": format!("db/{}", slugify(&self.title)),
+            "url": self.url(),
+            "slug": "",
+            "type": "dash-folder",
+            "tags": [],
+            "isStarred": false,
+        })
+    }
+}
+
+impl Dashboard {
+    fn url(&self) -> String {
+        format!("/d/{}/{}", self.uid, slugify(&self.title))
+    }
+}
+
+impl Team {
+    fn to_json(&self) -> Value {
+        json!({
+            "id": self.id,
+            "uid": self.uid,
+            "orgId": ORG_ID,
+            "name": self.name,
+            "email": self.email,
+            "avatarUrl": avatar_url(&self.name),
+            "memberCount": self.members.len(),
+            "permission": 0,
+            "isProvisioned": false,
+            "externalUID": "",
+        })
+    }
+}
+
+impl ServiceAccount {
+    fn to_json(&self) -> Value {
+        json!({
+            "id": self.id,
+            "uid": self.uid,
+            "name": self.name,
+            "login": self.login,
+            "orgId": ORG_ID,
+            "role": self.role,
+            "isDisabled": self.is_disabled,
+            "isExternal": false,
+            "tokens": self.tokens.len(),
+            "avatarUrl": avatar_url(&self.login),
+        })
+    }
+}
+
+impl ServiceAccountToken {
+    fn to_json(&self) -> Value {
+        json!({
+            "id": self.id,
+            "name": self.name,
+            "created": TIMESTAMP,
+            "expiration": self.seconds_to_live.map(|_| TIMESTAMP),
+            "secondsUntilExpiration": self.seconds_to_live.unwrap_or_default(),
+            "hasExpired": false,
+            "isRevoked": false,
+            "lastUsedAt": null,
+        })
+    }
+}
+
+fn json_body(request: &Request) -> Result<Value, Reply> {
+    if request.body.is_empty() {
+        return Ok(Value::Null);
+    }
+    serde_json::from_slice(&request.body).map_err(|_| Reply::message(400, "bad request data"))
+}
+
+fn str_field<'a>(value: &'a Value, key: &str) -> Option<&'a str> {
+    value
+        .get(key)
+        .and_then(Value::as_str)
+        .filter(|value| !value.is_empty())
+}
+
+fn parse_id(id: &str) -> Result<i64, Reply> {
+    id.parse().map_err(|_| Reply::message(400, "id is invalid"))
+}
+
+fn parse_role(body: &Value) -> Result<String, Reply> {
+    let role =
+        str_field(body, "role").ok_or_else(|| Reply::message(400, "Invalid role specified"))?;
+    valid_role(role)
+}
+
+fn valid_role(role: &str) -> Result<String, Reply> {
+    ROLES
+        .iter()
+        .find(|known| known.eq_ignore_ascii_case(role))
+        .map(|known| (*known).to_owned())
+        .ok_or_else(|| Reply::message(400, "Invalid role specified"))
+}
+
+fn dashboard_not_found() -> Reply {
+    Reply::message(404, "Dashboard not found")
+}
+
+fn page_params(page: Option<&str>, per_page: Option<&str>) -> (usize, usize) {
+    let page = page
+        .and_then(|page| page.parse().ok())
+        .filter(|page| *page > 0);
+    let per_page = per_page
+        .and_then(|per_page| per_page.parse().ok())
+        .filter(|per_page| *per_page > 0);
+    (page.unwrap_or(1), per_page.unwrap_or(1000))
+}
+
+fn paginate(items: Vec<Value>, page: Option<&str>, per_page: Option<&str>) -> Vec<Value> {
+    let (page, per_page) = page_params(page, per_page);
+    items
+        .into_iter()
+        .skip((page - 1).saturating_mul(per_page))
+        .take(per_page)
+        .collect()
+}
+
+fn slugify(title: &str) -> String {
+    let mut slug = String::with_capacity(title.len());
+    for ch in title.chars() {
+        if ch.is_alphanumeric() {
+            slug.extend(ch.to_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    while slug.ends_with('-') {
+        slug.pop();
+    }
+    slug
+}
+
+fn avatar_url(seed: &str) -> String {
+    let hash = seed.bytes().fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    });
+    format!("/avatar/{hash:016x}")
+}
+
+fn new_uid() -> String {
+    fastrand_token(14)
+}
+
+fn fastrand_token(len: usize) -> String {
+    std::iter::repeat_with(fastrand::alphanumeric)
+        .take(len)
+        .collect()
+}
@@ -0,0 +1,220 @@
+#[cfg(feature = "blocking-core")]
+use std::io::{self, Read};
+use std::{error::Error as StdError, fmt, time::Duration};
+#[cfg(feature = "async")]
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+#[cfg(feature = "async")]
+use bytes::Bytes;
+#[cfg(feature = "async")]
+use futures_core::Stream;
+
+use crate::{error::BoxError, retry::TransportErrorKind};
+
+#[cfg(feature = "async")]
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// Sends one HTTP request for the async client.
+///
+/// The client calls this once per attempt with a fully prepared request: URL, auth and default
+/// headers, interceptors and body are already applied. Retries, rate limiting, circuit breaking
+/// and error mapping stay in the client, so an implementation only moves bytes. `timeout` is the
+/// budget for this attempt, already clamped to the call's total timeout.
+#[cfg(feature = "async")]
+pub trait HttpTransport: Send + Sync + 'static {
+    fn send(
+        &self,
+        request: http::Request<Vec<u8>>,
+        timeout: Duration,
+    ) -> BoxFuture<'_, Result<http::Response<ResponseBody>, TransportError>>;
+}
+
+/// Blocking counterpart of [`HttpTransport`].
+#[cfg(feature = "blocking-core")]
+pub trait BlockingHttpTransport: Send + Sync + 'static {
+    fn send(
+        &self,
+        request: http::Request<Vec<u8>>,
+        timeout: Duration,
+    ) -> Result<http::Response<BlockingResponseBody>, TransportError>;
+}
+
+/// A failed attempt. The kind decides whether the retry policy sees a timeout, a connect error
+/// or something else.
+#[derive(Debug)]
+pub struct TransportError {
+    kind: TransportErrorKind,
+    source: BoxError,
+}
+
+impl TransportError {
+    pub fn new(
+        kind: TransportErrorKind,
+        source: impl Into<Box<dyn StdError + Send + Sync + 'static>>,
+    ) -> Self {
+        Self {
+            kind,
+            source: source.into(),
+        }
+    }
+
+    pub fn kind(&self) -> TransportErrorKind {
+        self.kind
+    }
+
+    pub(crate) fn into_source(self) -> BoxError {
+        self.source
+    }
+}
+
+impl fmt::Display for TransportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.source.fmt(f)
+    }
+}
+
+impl StdError for TransportError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        self.source.source()
+    }
+}
+
+/// Response body handed back by an [`HttpTransport`], read as a stream of chunks.
+#[cfg(feature = "async")]
+pub struct ResponseBody {
+    inner: Pin<Box<dyn Stream<Item = Result<Bytes, BoxError>> + Send>>,
+}
+
+#[cfg(feature = "async")]
+impl ResponseBody {
+    pub fn empty() -> Self {
+        Self::from(Bytes::new())
+    }
+
+    pub fn from_stream<S, E>(stream: S) -> Self
+    where
+        S: Stream<Item = Result<Bytes, E>> + Send + 'static,
+        E: Into<Box<dyn StdError + Send + Sync + 'static>>,
+    {
+        Self {
+            inner: Box::pin(MapErr(Box::pin(stream))),
+        }
+    }
+
+    /// Reads the remaining body into memory.
+    pub(crate) async fn collect(mut self) -> Result<Vec<u8>, BoxError> {
+        let mut body = Vec::new();
+        while let Some(chunk) = std::future::poll_fn(|cx| self.inner.as_mut().poll_next(cx)).await {
+            body.extend_from_slice(&chunk?);
+        }
+        Ok(body)
+    }
+}
+
+#[cfg(feature = "async")]
+impl Stream for ResponseBody {
+    type Item = Result<Bytes, BoxError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.inner.as_mut().poll_next(cx)
+    }
+}
+
+#[cfg(feature = "async")]
+impl From<Bytes> for ResponseBody {
+    fn from(bytes: Bytes) -> Self {
+        Self {
+            inner: Box::pin(Once(Some(bytes))),
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl From<Vec<u8>> for ResponseBody {
+    fn from(bytes: Vec<u8>) -> Self {
+        Self::from(Bytes::from(bytes))
+    }
+}
+
+#[cfg(feature = "async")]
+impl fmt::Debug for ResponseBody {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ResponseBody").finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "async")]
+struct Once(Option<Bytes>);
+
+#[cfg(feature = "async")]
+impl Stream for Once {
+    type Item = Result<Bytes, BoxError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Poll::Ready(self.0.take().filter(|bytes| !bytes.is_empty()).map(Ok))
+    }
+}
+
+#[cfg(feature = "async")]
+struct MapErr<S>(Pin<Box<S>>);
+
+#[cfg(feature = "async")]
+impl<S, E> Stream for MapErr<S>
+where
+    S: Stream<Item = Result<Bytes, E>>,
+    E: Into<BoxError>,
+{
+    type Item = Result<Bytes, BoxError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.0
+            .as_mut()
+            .poll_next(cx)
+            .map(|item| item.map(|chunk| chunk.map_err(Into::into)))
+    }
+}
+
+/// Response body handed back by a [`BlockingHttpTransport`], read through [`Read`].
+#[cfg(feature = "blocking-core")]
+pub struct BlockingResponseBody {
+    inner: Box<dyn Read + Send>,
+}
+
+#[cfg(feature = "blocking-core")]
+impl BlockingResponseBody {
+    pub fn empty() -> Self {
+        Self::from(Vec::new())
+    }
+
+    pub fn from_reader(reader: impl Read + Send + 'static) -> Self {
+        Self {
+            inner: Box::new(reader),
+        }
+    }
+}
+
+#[cfg(feature = "blocking-core")]
+impl Read for BlockingResponseBody {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.inner.read(buf)
+    }
+}
+
+#[cfg(feature = "blocking-core")]
+impl From<Vec<u8>> for BlockingResponseBody {
+    fn from(bytes: Vec<u8>) -> Self {
+        Self::from_reader(io::Cursor::new(bytes))
+    }
+}
+
+#[cfg(feature = "blocking-core")]
+impl fmt::Debug for BlockingResponseBody {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BlockingResponseBody")
+            .finish_non_exhaustive()
+    }
+}
@@ -1,31 +1,64 @@
-use http::{HeaderValue, Method};
+use std::{
+    borrow::Cow,
+    io::Read,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use http::{Method, StatusCode, request::Parts};
 use serde::{Serialize, de::DeserializeOwned};
 
 use crate::{
-    Error, RequestOptions, ResponseBytes, Result,
-    error::HttpError,
-    transport::{RequestContext, TransportConfig},
-    util::url as url_util,
+    Auth, Error, RequestOptions, ResponseBytes, ResponseReader, Result,
+    auth::Session,
+    error::BoxError,
+    failover::{Failover, Route},
+    oauth2::ClientCredentials,
+    retry::{RetryCause, RetryContext},
+    transport::{
+        BlockingHttpTransport, BlockingResponseBody, Deadline, EncodedBody, RequestContext,
+        TransportConfig, TransportError,
+    },
+    util::flight::Cached,
 };
 
+#[cfg(feature = "cassette")]
+use crate::{cassette::CassetteMode, retry::TransportErrorKind};
+
 pub(crate) struct BlockingTransport {
-    client: reqwest::blocking::Client,
+    backend: Arc<dyn BlockingHttpTransport>,
+    timeout: Duration,
+    total_timeout: Option<Duration>,
 }
 
-impl BlockingTransport {
-    pub(crate) fn new(config: &TransportConfig) -> Result<Self> {
-        #[cfg(feature = "rustls")]
-        if rustls::crypto::CryptoProvider::get_default().is_none() {
-            let _ = rustls::crypto::ring::default_provider().install_default();
-        }
+struct Sent {
+    parts: Parts,
+    response: http::Response<BlockingResponseBody>,
+}
 
-        let client = reqwest::blocking::Client::builder()
-            .timeout(config.timeout)
-            .connect_timeout(config.connect_timeout)
-            .build()
-            .map_err(|e| Error::transport("failed to build HTTP client", e))?;
+impl BlockingTransport {
+    /// Uses `backend` when given, otherwise a reqwest client built from `config`.
+    pub(crate) fn new(
+        config: &TransportConfig,
+        backend: Option<Arc<dyn BlockingHttpTransport>>,
+    ) -> Result<Self> {
+        let backend = match backend {
+            Some(backend) => backend,
+            #[cfg(feature = "blocking")]
+            None => Arc::new(super::ReqwestBlockingTransport::from_config(config)?),
+            #[cfg(not(feature = "blocking"))]
+            None => {
+                return Err(Error::invalid_config(
+                    "no HTTP transport configured: enable the `blocking` feature or call `transport`",
+                ));
+            }
+        };
 
-        Ok(Self { client })
+        Ok(Self {
+            backend,
+            timeout: config.timeout,
+            total_timeout: config.total_timeout,
+        })
     }
 
     pub(crate) fn send_json<Response, Query, Body>(
@@ -44,17 +77,7 @@ impl BlockingTransport {
     {
         let response = self.send_bytes(ctx, method.clone(), segments, query, body, options)?;
 
-        let bytes = response.body();
-        let decoded: Response = serde_json::from_slice(bytes).map_err(|e| {
-            let url =
-                url_util::endpoint(ctx.base_url, segments).unwrap_or_else(|_| ctx.base_url.clone());
-            let http = HttpError::new(method, url.path().to_owned(), Some(response.status()))
-                .with_request_id(super::extract_request_id(response.headers()))
-                .with_body_snippet(super::capture_snippet(bytes, ctx.body_snippet));
-            Error::decode(http, e)
-        })?;
-
-        Ok(decoded)
+        super::decode_json(ctx, method, segments, &response)
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -82,17 +105,7 @@ impl BlockingTransport {
             options,
         )?;
 
-        let bytes = response.body();
-        let decoded: Response = serde_json::from_slice(bytes).map_err(|e| {
-            let url =
-                url_util::endpoint(ctx.base_url, segments).unwrap_or_else(|_| ctx.base_url.clone());
-            let http = HttpError::new(method, url.path().to_owned(), Some(response.status()))
-                .with_request_id(super::extract_request_id(response.headers()))
-                .with_body_snippet(super::capture_snippet(bytes, ctx.body_snippet));
-            Error::decode(http, e)
-        })?;
-
-        Ok(decoded)
+        super::decode_json(ctx, method, segments, &response)
     }
 
     pub(crate) fn send_bytes<Query, Body>(
@@ -108,211 +121,428 @@ impl BlockingTransport {
         Query: Serialize + ?Sized,
         Body: Serialize + ?Sized,
     {
-        let url = url_util::endpoint(ctx.base_url, segments)?;
-        let path = url.path().to_owned();
+        let body = body.map(EncodedBody::json).transpose()?;
+        self.execute(ctx, method, segments, query, body, options)
+    }
 
-        #[cfg(feature = "tracing")]
-        let span = tracing::info_span!(
-            "grafana.request",
-            http.method = %method,
-            http.host = %url.host_str().unwrap_or_default(),
-            http.path = %path
-        );
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn send_bytes_text<Query>(
+        &self,
+        ctx: RequestContext<'_>,
+        method: Method,
+        segments: &[&str],
+        query: Option<&Query>,
+        body: &str,
+        content_type: &'static str,
+        options: Option<&RequestOptions>,
+    ) -> Result<ResponseBytes>
+    where
+        Query: Serialize + ?Sized,
+    {
+        let body = EncodedBody::text(body, content_type);
+        self.execute(ctx, method, segments, query, Some(body), options)
+    }
 
-        #[cfg(feature = "tracing")]
-        let _enter = span.enter();
+    pub(crate) fn send_stream<Query, Body>(
+        &self,
+        ctx: RequestContext<'_>,
+        method: Method,
+        segments: &[&str],
+        query: Option<&Query>,
+        body: Option<&Body>,
+        options: Option<&RequestOptions>,
+    ) -> Result<ResponseReader>
+    where
+        Query: Serialize + ?Sized,
+        Body: Serialize + ?Sized,
+    {
+        let body = body.map(EncodedBody::json).transpose()?;
+        let sent = self.send_with_retries(ctx, method, segments, query, body.as_ref(), options)?;
 
-        let mut attempt: usize = 0;
-        loop {
-            #[cfg(feature = "tracing")]
-            tracing::debug!(attempt, "sending request");
+        let status = sent.response.status();
+        let headers = sent.response.headers().clone();
+        super::notify_response(
+            ctx,
+            &sent.parts,
+            &ResponseBytes::new(status, headers.clone(), Vec::new()),
+        );
+        Ok(ResponseReader::new(
+            status,
+            headers,
+            sent.response.into_body(),
+        ))
+    }
 
-            let mut headers = ctx.default_headers.clone();
-            if let Some(options) = options {
-                for (name, value) in options.headers().iter() {
-                    headers.insert(name.clone(), value.clone());
-                }
-            }
-            ctx.auth.apply(&mut headers)?;
-
-            let mut request = self
-                .client
-                .request(method.clone(), url.clone())
-                .headers(headers);
-            if let Some(query) = query {
-                request = request.query(query);
-            }
-            if let Some(body) = body {
-                request = request.json(body);
-            }
-            if let Some(timeout) = options.and_then(RequestOptions::timeout_override) {
-                request = request.timeout(timeout);
+    fn execute<Query>(
+        &self,
+        ctx: RequestContext<'_>,
+        method: Method,
+        segments: &[&str],
+        query: Option<&Query>,
+        body: Option<EncodedBody>,
+        options: Option<&RequestOptions>,
+    ) -> Result<ResponseBytes>
+    where
+        Query: Serialize + ?Sized,
+    {
+        let body = body.as_ref();
+        let sent = self.send_with_retries(ctx, method, segments, query, body, options)?;
+
+        let status = sent.response.status();
+        let headers = sent.response.headers().clone();
+        let bytes = match read_body(sent.response.into_body()) {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                let err = Error::transport("failed to read response body", e);
+                super::notify_error(ctx, &sent.parts, &err);
+                return Err(err);
             }
+        };
 
-            let response = request.send();
-            match response {
-                Ok(response) => {
-                    let status = response.status();
-                    let headers = response.headers().clone();
-
-                    if status.is_success() {
-                        let bytes = response
-                            .bytes()
-                            .map_err(|e| Error::transport("failed to read response body", e))?;
-                        return Ok(ResponseBytes::new(status, headers, bytes.to_vec()));
-                    }
-
-                    let bytes = response
-                        .bytes()
-                        .map_err(|e| Error::transport("failed to read error response body", e))?;
-
-                    let retry_after = super::extract_retry_after(&headers);
-                    let http = HttpError::new(method.clone(), path.clone(), Some(status))
-                        .with_request_id(super::extract_request_id(&headers))
-                        .with_message(super::extract_message(&bytes))
-                        .with_body_snippet(super::capture_snippet(&bytes, ctx.body_snippet))
-                        .with_retry_after(retry_after);
-                    if attempt < ctx.retry.max_retries
-                        && super::is_retryable_status(&method, status)
-                        && let Some(delay) =
-                            super::compute_retry_delay(http.retry_after(), ctx.retry, attempt)
-                    {
-                        attempt += 1;
-                        if !delay.is_zero() {
-                            std::thread::sleep(delay);
-                        }
-                        continue;
-                    }
-
-                    return Err(Error::from_http(http));
-                }
-                Err(err) => {
-                    if attempt < ctx.retry.max_retries
-                        && super::is_retryable_transport_error(&method, &err)
-                        && let Some(delay) = super::compute_retry_delay(None, ctx.retry, attempt)
-                    {
-                        attempt += 1;
-                        if !delay.is_zero() {
-                            std::thread::sleep(delay);
-                        }
-                        continue;
-                    }
-
-                    return Err(Error::transport("request failed", err));
-                }
-            }
-        }
+        let response = ResponseBytes::new(status, headers, bytes);
+        super::notify_response(ctx, &sent.parts, &response);
+        Ok(response)
     }
 
-    #[allow(clippy::too_many_arguments)]
-    pub(crate) fn send_bytes_text<Query>(
+    /// Runs the attempt loop and returns the first successful response with its body unread.
+    fn send_with_retries<Query>(
         &self,
         ctx: RequestContext<'_>,
         method: Method,
         segments: &[&str],
         query: Option<&Query>,
-        body: &str,
-        content_type: &'static str,
+        body: Option<&EncodedBody>,
         options: Option<&RequestOptions>,
-    ) -> Result<ResponseBytes>
+    ) -> Result<Sent>
     where
         Query: Serialize + ?Sized,
     {
-        let url = url_util::endpoint(ctx.base_url, segments)?;
-        let path = url.path().to_owned();
+        let url = super::request_url(ctx.base_url, segments, query)?;
+        let mut route = ctx.failover.map(Failover::route);
 
         #[cfg(feature = "tracing")]
         let span = tracing::info_span!(
             "grafana.request",
             http.method = %method,
             http.host = %url.host_str().unwrap_or_default(),
-            http.path = %path
+            http.path = %url.path()
         );
 
         #[cfg(feature = "tracing")]
         let _enter = span.enter();
 
+        let deadline = Deadline::new(
+            options
+                .and_then(RequestOptions::total_timeout_override)
+                .or(self.total_timeout),
+        );
+        let attempt_timeout = options
+            .and_then(RequestOptions::timeout_override)
+            .unwrap_or(self.timeout);
+
         let mut attempt: usize = 0;
+        let mut previous_delay = None;
+        let mut refreshed = false;
+        let mut base_url = None;
+        let mut same_endpoint = false;
         loop {
             #[cfg(feature = "tracing")]
             tracing::debug!(attempt, "sending request");
 
-            let mut headers = ctx.default_headers.clone();
-            headers.insert(
-                http::header::CONTENT_TYPE,
-                HeaderValue::from_static(content_type),
-            );
-            if let Some(options) = options {
-                for (name, value) in options.headers().iter() {
-                    headers.insert(name.clone(), value.clone());
+            if let Some(limiter) = ctx.rate_limiter {
+                let wait = limiter.reserve();
+                if !wait.is_zero() {
+                    if !deadline.allows(wait) {
+                        return Err(deadline.exceeded(attempt, None));
+                    }
+
+                    #[cfg(feature = "tracing")]
+                    tracing::debug!(?wait, "waiting for rate limiter");
+                    std::thread::sleep(wait);
                 }
             }
-            ctx.auth.apply(&mut headers)?;
-
-            let mut request = self
-                .client
-                .request(method.clone(), url.clone())
-                .headers(headers)
-                .body(body.to_owned());
-            if let Some(query) = query {
-                request = request.query(query);
-            }
-            if let Some(timeout) = options.and_then(RequestOptions::timeout_override) {
-                request = request.timeout(timeout);
+
+            if let Some(breaker) = ctx.circuit_breaker {
+                breaker.acquire()?;
             }
 
-            let response = request.send();
-            match response {
+            let Some(timeout) = deadline.attempt_timeout(attempt_timeout) else {
+                return Err(deadline.exceeded(attempt, None));
+            };
+
+            // The retry after a credential refresh goes to the same replica.
+            if !std::mem::take(&mut same_endpoint) {
+                base_url = route.as_mut().map(Route::next);
+            }
+            let url = match base_url {
+                Some(base_url) => Cow::Owned(super::request_url(base_url, segments, query)?),
+                None => Cow::Borrowed(&url),
+            };
+            let path = url.path().to_owned();
+            let resolved = self.authenticate(ctx, base_url.unwrap_or(ctx.base_url), timeout)?;
+            let auth = resolved.as_ref().unwrap_or(ctx.auth);
+            let parts = super::prepare_request(ctx, auth, &method, &url, body, options)?;
+
+            let response = self.dispatch(ctx, &parts, body, timeout)?;
+            let (err, cause) = match response {
                 Ok(response) => {
                     let status = response.status();
                     let headers = response.headers().clone();
+                    if let Some(limiter) = ctx.rate_limiter {
+                        limiter.observe(status, &headers);
+                    }
+                    if let Some(route) = &route {
+                        route.record_status(status);
+                    }
+                    if let Some(breaker) = ctx.circuit_breaker {
+                        if status.is_server_error() {
+                            super::breaker_failure(breaker, route.as_ref());
+                        } else {
+                            breaker.record_success();
+                        }
+                    }
 
                     if status.is_success() {
-                        let bytes = response
-                            .bytes()
-                            .map_err(|e| Error::transport("failed to read response body", e))?;
-                        return Ok(ResponseBytes::new(status, headers, bytes.to_vec()));
+                        super::rotate_session(ctx, &headers);
+                        return Ok(Sent { parts, response });
                     }
 
-                    let bytes = response
-                        .bytes()
-                        .map_err(|e| Error::transport("failed to read error response body", e))?;
-
-                    let retry_after = super::extract_retry_after(&headers);
-                    let http = HttpError::new(method.clone(), path.clone(), Some(status))
-                        .with_request_id(super::extract_request_id(&headers))
-                        .with_message(super::extract_message(&bytes))
-                        .with_body_snippet(super::capture_snippet(&bytes, ctx.body_snippet))
-                        .with_retry_after(retry_after);
-                    if attempt < ctx.retry.max_retries
-                        && super::is_retryable_status(&method, status)
-                        && let Some(delay) =
-                            super::compute_retry_delay(http.retry_after(), ctx.retry, attempt)
-                    {
-                        attempt += 1;
-                        if !delay.is_zero() {
-                            std::thread::sleep(delay);
+                    let bytes = match read_body(response.into_body()) {
+                        Ok(bytes) => bytes,
+                        Err(e) => {
+                            let err = Error::transport("failed to read error response body", e);
+                            super::notify_error(ctx, &parts, &err);
+                            return Err(err);
                         }
-                        continue;
-                    }
-
-                    return Err(Error::from_http(http));
+                    };
+
+                    let http = super::http_error(ctx, &method, &path, status, &headers, &bytes);
+                    let err = Error::from_http(http);
+                    let cause = RetryCause::Status {
+                        status,
+                        retry_after: err.retry_after(),
+                    };
+                    (err, cause)
                 }
                 Err(err) => {
-                    if attempt < ctx.retry.max_retries
-                        && super::is_retryable_transport_error(&method, &err)
-                        && let Some(delay) = super::compute_retry_delay(None, ctx.retry, attempt)
-                    {
-                        attempt += 1;
-                        if !delay.is_zero() {
-                            std::thread::sleep(delay);
-                        }
-                        continue;
+                    let cause = RetryCause::Transport(err.kind());
+                    if let Some(route) = &route {
+                        route.record_transport_error();
                     }
-
-                    return Err(Error::transport("request failed", err));
+                    if let Some(breaker) = ctx.circuit_breaker {
+                        super::breaker_failure(breaker, route.as_ref());
+                    }
+                    (
+                        Error::transport_boxed("request failed", err.into_source()),
+                        cause,
+                    )
                 }
+            };
+
+            super::notify_error(ctx, &parts, &err);
+            if !refreshed
+                && ctx.auth.is_refreshable()
+                && matches!(cause, RetryCause::Status { status, .. } if status == StatusCode::UNAUTHORIZED)
+            {
+                #[cfg(feature = "tracing")]
+                tracing::debug!("refreshing credentials after 401");
+
+                refreshed = true;
+                ctx.auth.refresh(&parts.headers)?;
+                same_endpoint = true;
+                continue;
+            }
+            let retry = RetryContext::new(&method, &path, attempt, cause, previous_delay);
+            match ctx.retry.next_delay(&retry) {
+                Some(delay) if !deadline.allows(delay) => {
+                    return Err(deadline.exceeded(attempt + 1, Some(err)));
+                }
+                Some(delay) => {
+                    attempt += 1;
+                    previous_delay = Some(delay);
+                    if !delay.is_zero() {
+                        std::thread::sleep(delay);
+                    }
+                }
+                None if deadline.is_expired() => {
+                    return Err(deadline.exceeded(attempt + 1, Some(err)));
+                }
+                None => return Err(err),
             }
         }
     }
+
+    /// Gets `ctx.auth` ready for the next attempt: logs a session in or fetches an OAuth2
+    /// token. Returns the credentials to use instead of `ctx.auth`, if any.
+    fn authenticate(
+        &self,
+        ctx: RequestContext<'_>,
+        api_base_url: &url::Url,
+        timeout: Duration,
+    ) -> Result<Option<Auth>> {
+        match ctx.auth {
+            // Logins and token requests are never recorded, and replayed responses need no
+            // credentials.
+            Auth::Session(_) if ctx.is_replaying() => Ok(None),
+            Auth::OAuth2(_) if ctx.is_replaying() => Ok(Some(Auth::None)),
+            Auth::Session(session) => {
+                self.login(ctx, session, api_base_url, timeout)?;
+                Ok(None)
+            }
+            Auth::OAuth2(credentials) => {
+                let token = self.oauth2_token(ctx, credentials, timeout)?;
+                Ok(Some(Auth::bearer(token)))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Logs a session in before the attempt that needs its cookie, unless it has one. One
+    /// request logs in while the others wait for its cookie. A failed login is not retried.
+    fn login(
+        &self,
+        ctx: RequestContext<'_>,
+        session: &Session,
+        api_base_url: &url::Url,
+        timeout: Duration,
+    ) -> Result<()> {
+        let claim = loop {
+            match session.cached() {
+                Cached::Fresh(_) => return Ok(()),
+                Cached::Fetch { claim, .. } => break claim,
+                Cached::Wait(pending) => session.flight().wait(pending)?,
+            }
+        };
+
+        #[cfg(feature = "tracing")]
+        tracing::debug!("logging in session");
+
+        let (parts, body) = super::login_request(ctx, session, api_base_url)?;
+        let logged_in = match self.exchange(&parts, body, timeout) {
+            Ok((head, bytes)) => {
+                super::finish_login(ctx, session, &parts, head.status, &head.headers, &bytes)
+            }
+            Err(e) => Err(Error::transport_boxed("login request failed", e)),
+        };
+        if let Err(err) = &logged_in {
+            claim.fail(err);
+        }
+        logged_in
+    }
+
+    /// The cached OAuth2 token, or a new one from the token endpoint. When the cached token is
+    /// only close to expiry, a failed refresh falls back to it.
+    fn oauth2_token(
+        &self,
+        ctx: RequestContext<'_>,
+        credentials: &ClientCredentials,
+        timeout: Duration,
+    ) -> Result<String> {
+        let (current, claim) = loop {
+            match credentials.cached() {
+                Cached::Fresh(token) => return Ok(token),
+                Cached::Fetch { current, claim } => break (current, claim),
+                Cached::Wait(pending) => credentials.flight().wait(pending)?,
+            }
+        };
+
+        #[cfg(feature = "tracing")]
+        tracing::debug!("fetching OAuth2 token");
+
+        let requested_at = Instant::now();
+        let (parts, body) = super::token_request(credentials)?;
+        let fetched = match self.exchange(&parts, body, timeout) {
+            Ok((head, bytes)) => super::finish_token(
+                ctx,
+                credentials,
+                &parts,
+                head.status,
+                &head.headers,
+                &bytes,
+                requested_at,
+            ),
+            Err(e) => Err(super::token_error(
+                &parts,
+                None,
+                format!("OAuth2 token request failed: {e}"),
+            )),
+        };
+        match (fetched, current) {
+            (Err(_err), Some(current)) => {
+                #[cfg(feature = "tracing")]
+                tracing::warn!(error = %_err, "OAuth2 token refresh failed; using current token");
+
+                Ok(current)
+            }
+            (Err(err), None) => {
+                claim.fail(&err);
+                Err(err)
+            }
+            (fetched, _) => fetched,
+        }
+    }
+
+    /// Sends a login or token request. These bypass the cassette so that passwords and tokens
+    /// are never written to disk, and are skipped when replaying.
+    fn exchange(
+        &self,
+        parts: &Parts,
+        body: EncodedBody,
+        timeout: Duration,
+    ) -> std::result::Result<(http::response::Parts, Vec<u8>), BoxError> {
+        let request = http::Request::from_parts(parts.clone(), body.bytes);
+        let response = self
+            .backend
+            .send(request, timeout)
+            .map_err(TransportError::into_source)?;
+        let (head, body) = response.into_parts();
+        Ok((head, read_body(body)?))
+    }
+
+    /// Sends one attempt, going through the cassette when one is configured.
+    fn dispatch(
+        &self,
+        ctx: RequestContext<'_>,
+        parts: &Parts,
+        body: Option<&EncodedBody>,
+        timeout: Duration,
+    ) -> Result<std::result::Result<http::Response<BlockingResponseBody>, TransportError>> {
+        let request = http::Request::from_parts(
+            parts.clone(),
+            body.map(|body| body.bytes.clone()).unwrap_or_default(),
+        );
+
+        #[cfg(feature = "cassette")]
+        if let Some(cassette) = ctx.cassette {
+            let request_body = body.map(|body| body.bytes.as_slice());
+            if cassette.mode() == CassetteMode::Replay {
+                let response = cassette.replay_response(parts, request_body)?;
+                return Ok(Ok(response.map(BlockingResponseBody::from)));
+            }
+
+            let response = match self.backend.send(request, timeout) {
+                Ok(response) => response,
+                Err(e) => return Ok(Err(e)),
+            };
+            let (head, response_body) = response.into_parts();
+            let bytes = match read_body(response_body) {
+                Ok(bytes) => bytes,
+                Err(e) => return Ok(Err(TransportError::new(TransportErrorKind::Body, e))),
+            };
+            cassette.record_interaction(parts, request_body, head.status, &head.headers, &bytes)?;
+            return Ok(Ok(http::Response::from_parts(
+                head,
+                BlockingResponseBody::from(bytes),
+            )));
+        }
+
+        let _ = (ctx, parts);
+        Ok(self.backend.send(request, timeout))
+    }
+}
+
+fn read_body(mut body: BlockingResponseBody) -> std::io::Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    body.read_to_end(&mut bytes)?;
+    Ok(bytes)
 }
@@ -1,30 +1,103 @@
-use std::time::Duration;
+//! HTTP backends.
+//!
+//! The clients run retries, rate limiting, circuit breaking, interceptors and error mapping
+//! themselves and hand each attempt to an [`HttpTransport`] (or [`BlockingHttpTransport`]).
+//! reqwest is the default backend; implement the trait to reuse another HTTP stack, or wrap a
+//! preconfigured `reqwest::Client` in [`ReqwestTransport`].
 
-use http::{HeaderMap, Method, StatusCode};
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
-use crate::{Auth, util::redact};
+use http::{HeaderMap, HeaderValue, Method, StatusCode, request::Parts};
+use serde::{Serialize, de::DeserializeOwned};
+
+#[cfg(any(feature = "async", feature = "blocking"))]
+use crate::Proxy;
+use crate::{
+    Auth, Error, Interceptor, RequestOptions, ResponseBytes, Result,
+    auth::{SESSION_COOKIE, Session},
+    circuit_breaker::CircuitBreaker,
+    error::HttpError,
+    failover::{Failover, Route},
+    oauth2::{ClientCredentials, TokenResponse},
+    rate_limit::RateLimiter,
+    retry::RetryPolicy,
+    util::{redact, url as url_util},
+};
 
 #[cfg(feature = "async")]
 mod async_transport;
-#[cfg(feature = "blocking")]
+mod backend;
+#[cfg(feature = "blocking-core")]
 mod blocking_transport;
+#[cfg(any(feature = "async", feature = "blocking"))]
+mod reqwest_backend;
+#[cfg(feature = "tower")]
+mod service;
+#[cfg(all(
+    any(feature = "rustls", feature = "native-tls"),
+    any(feature = "async", feature = "blocking")
+))]
+mod tls;
 
 #[cfg(feature = "async")]
 pub(crate) use async_transport::AsyncTransport;
-#[cfg(feature = "blocking")]
+pub use backend::TransportError;
+#[cfg(feature = "blocking-core")]
+pub use backend::{BlockingHttpTransport, BlockingResponseBody};
+#[cfg(feature = "async")]
+pub use backend::{BoxFuture, HttpTransport, ResponseBody};
+#[cfg(feature = "blocking-core")]
 pub(crate) use blocking_transport::BlockingTransport;
+#[cfg(feature = "blocking")]
+pub use reqwest_backend::ReqwestBlockingTransport;
+#[cfg(feature = "async")]
+pub use reqwest_backend::ReqwestTransport;
+#[cfg(feature = "tower")]
+pub use service::{AttemptTimeout, TransportService};
+#[cfg(feature = "tower")]
+pub(crate) use service::{BoxLayer, apply_layers, box_layer};
+#[cfg(all(
+    any(feature = "rustls", feature = "native-tls"),
+    any(feature = "async", feature = "blocking")
+))]
+pub(crate) use tls::TlsConfig;
 
 #[derive(Clone)]
 pub(crate) struct TransportConfig {
     pub timeout: Duration,
     pub connect_timeout: Duration,
+    pub total_timeout: Option<Duration>,
+    #[cfg(any(feature = "async", feature = "blocking"))]
+    pub proxies: Vec<Proxy>,
+    #[cfg(any(feature = "async", feature = "blocking"))]
+    pub proxy_from_env: bool,
+    #[cfg(all(unix, any(feature = "async", feature = "blocking")))]
+    pub unix_socket: Option<std::path::PathBuf>,
+    #[cfg(all(
+        any(feature = "rustls", feature = "native-tls"),
+        any(feature = "async", feature = "blocking")
+    ))]
+    pub tls: TlsConfig,
 }
 
-#[derive(Clone)]
-pub(crate) struct RetryConfig {
-    pub max_retries: usize,
-    pub base_delay: Duration,
-    pub max_delay: Duration,
+#[cfg(any(feature = "async", feature = "blocking"))]
+impl TransportConfig {
+    /// Explicit proxies first, then (if enabled) the ones from the environment.
+    pub(crate) fn reqwest_proxies(&self) -> Result<Vec<reqwest::Proxy>> {
+        let from_env = if self.proxy_from_env {
+            Proxy::from_env()?
+        } else {
+            Vec::new()
+        };
+        self.proxies
+            .iter()
+            .chain(&from_env)
+            .map(Proxy::to_reqwest)
+            .collect()
+    }
 }
 
 #[derive(Clone)]
@@ -38,37 +111,354 @@ pub(crate) struct RequestContext<'a> {
     pub base_url: &'a url::Url,
     pub auth: &'a Auth,
     pub default_headers: &'a HeaderMap,
-    pub retry: &'a RetryConfig,
+    pub retry: &'a dyn RetryPolicy,
     pub body_snippet: &'a BodySnippetConfig,
+    pub interceptors: &'a [Arc<dyn Interceptor>],
+    pub rate_limiter: Option<&'a RateLimiter>,
+    pub circuit_breaker: Option<&'a CircuitBreaker>,
+    pub failover: Option<&'a Failover>,
+    #[cfg(feature = "cassette")]
+    pub cassette: Option<&'a crate::cassette::Cassette>,
 }
 
-fn is_retryable_status(method: &Method, status: StatusCode) -> bool {
-    if !is_idempotent_method(method) {
-        return false;
+impl RequestContext<'_> {
+    /// Whether responses come from a cassette instead of the network.
+    pub(crate) fn is_replaying(&self) -> bool {
+        #[cfg(feature = "cassette")]
+        if let Some(cassette) = self.cassette {
+            return cassette.mode() == crate::cassette::CassetteMode::Replay;
+        }
+        false
     }
+}
 
-    matches!(
-        status,
-        StatusCode::TOO_MANY_REQUESTS
-            | StatusCode::BAD_GATEWAY
-            | StatusCode::SERVICE_UNAVAILABLE
-            | StatusCode::GATEWAY_TIMEOUT
-    )
+/// Overall time budget for one logical call.
+#[derive(Clone, Copy)]
+pub(crate) struct Deadline {
+    started: Instant,
+    budget: Option<Duration>,
 }
 
-fn is_retryable_transport_error(method: &Method, err: &reqwest::Error) -> bool {
-    if !is_idempotent_method(method) {
-        return false;
+impl Deadline {
+    pub(crate) fn new(budget: Option<Duration>) -> Self {
+        Self {
+            started: Instant::now(),
+            budget,
+        }
     }
 
-    err.is_timeout() || err.is_connect()
+    fn remaining(&self) -> Option<Duration> {
+        self.budget
+            .map(|budget| budget.saturating_sub(self.started.elapsed()))
+    }
+
+    pub(crate) fn is_expired(&self) -> bool {
+        self.remaining()
+            .is_some_and(|remaining| remaining.is_zero())
+    }
+
+    /// Whether waiting `delay` still leaves time for another attempt.
+    pub(crate) fn allows(&self, delay: Duration) -> bool {
+        self.remaining().is_none_or(|remaining| delay < remaining)
+    }
+
+    /// Per-attempt timeout clamped to the remaining budget; `None` once the budget is spent.
+    pub(crate) fn attempt_timeout(&self, timeout: Duration) -> Option<Duration> {
+        match self.remaining() {
+            Some(remaining) if remaining.is_zero() => None,
+            Some(remaining) => Some(timeout.min(remaining)),
+            None => Some(timeout),
+        }
+    }
+
+    pub(crate) fn exceeded(&self, attempts: usize, last_error: Option<Error>) -> Error {
+        Error::Timeout {
+            attempts,
+            elapsed: self.started.elapsed(),
+            last_error: last_error.map(Box::new),
+        }
+    }
 }
 
-fn is_idempotent_method(method: &Method) -> bool {
-    matches!(
-        *method,
-        Method::GET | Method::HEAD | Method::PUT | Method::DELETE | Method::OPTIONS
-    )
+pub(crate) struct EncodedBody {
+    pub bytes: Vec<u8>,
+    pub content_type: HeaderValue,
+}
+
+impl EncodedBody {
+    pub(crate) fn json<Body>(body: &Body) -> Result<Self>
+    where
+        Body: Serialize + ?Sized,
+    {
+        let bytes = serde_json::to_vec(body)
+            .map_err(|e| Error::transport("failed to encode request body", e))?;
+        Ok(Self {
+            bytes,
+            content_type: HeaderValue::from_static("application/json"),
+        })
+    }
+
+    pub(crate) fn text(body: &str, content_type: &'static str) -> Self {
+        Self {
+            bytes: body.as_bytes().to_vec(),
+            content_type: HeaderValue::from_static(content_type),
+        }
+    }
+}
+
+pub(crate) fn request_url<Query>(
+    base_url: &url::Url,
+    segments: &[&str],
+    query: Option<&Query>,
+) -> Result<url::Url>
+where
+    Query: Serialize + ?Sized,
+{
+    let mut url = url_util::endpoint(base_url, segments)?;
+    if let Some(query) = query {
+        let encoded = serde_urlencoded::to_string(query)
+            .map_err(|e| Error::transport("failed to encode request query", e))?;
+        if !encoded.is_empty() {
+            url.set_query(Some(&encoded));
+        }
+    }
+    Ok(url)
+}
+
+/// Builds the request head for one attempt and runs it through the interceptors. `auth` is
+/// `ctx.auth`, or what an async credential provider returned for this attempt.
+pub(crate) fn prepare_request(
+    ctx: RequestContext<'_>,
+    auth: &Auth,
+    method: &Method,
+    url: &url::Url,
+    body: Option<&EncodedBody>,
+    options: Option<&RequestOptions>,
+) -> Result<Parts> {
+    let mut headers = ctx.default_headers.clone();
+    if let Some(body) = body {
+        headers.insert(http::header::CONTENT_TYPE, body.content_type.clone());
+    }
+    if let Some(options) = options {
+        for (name, value) in options.headers().iter() {
+            headers.insert(name.clone(), value.clone());
+        }
+    }
+    let _credential_headers = auth.apply(&mut headers)?;
+
+    let (mut parts, ()) = http::Request::builder()
+        .method(method.clone())
+        .uri(url.as_str())
+        .body(())
+        .map_err(|e| Error::invalid_config(format!("invalid request: {e}")))?
+        .into_parts();
+    parts.headers = headers;
+    #[cfg(feature = "cassette")]
+    parts
+        .extensions
+        .insert(crate::auth::CredentialHeaders(_credential_headers));
+
+    for interceptor in ctx.interceptors {
+        interceptor.on_request(&mut parts)?;
+    }
+    Ok(parts)
+}
+
+/// Builds the `POST /login` request for a session auth without a cookie. The login goes to the
+/// same Grafana (or failover replica) as the attempt it precedes.
+pub(crate) fn login_request(
+    ctx: RequestContext<'_>,
+    session: &Session,
+    api_base_url: &url::Url,
+) -> Result<(Parts, EncodedBody)> {
+    let url = url_util::login_url(api_base_url)?;
+    let body = EncodedBody::json(&session.login_body())?;
+    let mut headers = ctx.default_headers.clone();
+    headers.insert(http::header::CONTENT_TYPE, body.content_type.clone());
+
+    let (mut parts, ()) = http::Request::builder()
+        .method(Method::POST)
+        .uri(url.as_str())
+        .body(())
+        .map_err(|e| Error::invalid_config(format!("invalid login request: {e}")))?
+        .into_parts();
+    parts.headers = headers;
+    Ok((parts, body))
+}
+
+/// Stores the `grafana_session` cookie from a login response.
+pub(crate) fn finish_login(
+    ctx: RequestContext<'_>,
+    session: &Session,
+    parts: &Parts,
+    status: StatusCode,
+    headers: &HeaderMap,
+    bytes: &[u8],
+) -> Result<()> {
+    let path = parts.uri.path();
+    if !status.is_success() {
+        return Err(Error::from_http(http_error(
+            ctx,
+            &parts.method,
+            path,
+            status,
+            headers,
+            bytes,
+        )));
+    }
+
+    match session_cookie(headers) {
+        Some(value) => {
+            session.set_cookie(value.to_owned());
+            Ok(())
+        }
+        None => Err(Error::Auth(Box::new(
+            HttpError::new(parts.method.clone(), path.to_owned(), Some(status))
+                .with_message(Some(format!("login did not set a {SESSION_COOKIE} cookie"))),
+        ))),
+    }
+}
+
+/// Keeps a session's cookie current when Grafana rotates it on a successful response.
+pub(crate) fn rotate_session(ctx: RequestContext<'_>, headers: &HeaderMap) {
+    if let Auth::Session(session) = ctx.auth
+        && !ctx.is_replaying()
+        && let Some(cookie) = session_cookie(headers)
+    {
+        session.set_cookie(cookie.to_owned());
+    }
+}
+
+/// Counts a failed attempt against the circuit breaker, unless failover routes around the
+/// replica that failed: one dead replica must not open the circuit for the healthy ones.
+pub(crate) fn breaker_failure(breaker: &CircuitBreaker, route: Option<&Route<'_>>) {
+    if route.is_some_and(Route::routes_around) {
+        breaker.record_skipped();
+    } else {
+        breaker.record_failure();
+    }
+}
+
+/// The `grafana_session` value set by a response, if any.
+fn session_cookie(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get_all(http::header::SET_COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .filter_map(|value| value.split(';').next()?.trim().split_once('='))
+        .find(|(name, value)| *name == SESSION_COOKIE && !value.is_empty())
+        .map(|(_, value)| value)
+}
+
+/// Builds the client-credentials request for the OAuth2 token endpoint. Grafana's default
+/// headers are not sent to it.
+pub(crate) fn token_request(credentials: &ClientCredentials) -> Result<(Parts, EncodedBody)> {
+    let body = EncodedBody {
+        bytes: credentials.token_form()?.into_bytes(),
+        content_type: HeaderValue::from_static("application/x-www-form-urlencoded"),
+    };
+    let mut headers = HeaderMap::new();
+    headers.insert(http::header::CONTENT_TYPE, body.content_type.clone());
+    headers.insert(
+        http::header::ACCEPT,
+        HeaderValue::from_static("application/json"),
+    );
+    if let Some((client_id, client_secret)) = credentials.basic_credentials() {
+        Auth::basic(client_id, client_secret).apply(&mut headers)?;
+    }
+
+    let (mut parts, ()) = http::Request::builder()
+        .method(Method::POST)
+        .uri(credentials.token_url().as_str())
+        .body(())
+        .map_err(|e| Error::invalid_config(format!("invalid OAuth2 token request: {e}")))?
+        .into_parts();
+    parts.headers = headers;
+    Ok((parts, body))
+}
+
+/// Reads the access token from a token endpoint response. Every failure is an [`Error::Auth`]
+/// naming the token endpoint.
+pub(crate) fn finish_token(
+    ctx: RequestContext<'_>,
+    credentials: &ClientCredentials,
+    parts: &Parts,
+    status: StatusCode,
+    headers: &HeaderMap,
+    bytes: &[u8],
+    requested_at: Instant,
+) -> Result<String> {
+    if !status.is_success() {
+        let http = http_error(ctx, &parts.method, parts.uri.path(), status, headers, bytes);
+        let message = match http.message() {
+            Some(message) => format!("OAuth2 token request failed: {message}"),
+            None => "OAuth2 token request failed".to_owned(),
+        };
+        return Err(Error::Auth(Box::new(http.with_message(Some(message)))));
+    }
+
+    match serde_json::from_slice::<TokenResponse>(bytes) {
+        Ok(response) => Ok(credentials.store(response, requested_at)),
+        Err(e) => Err(token_error(
+            parts,
+            Some(status),
+            format!("invalid OAuth2 token response: {e}"),
+        )),
+    }
+}
+
+pub(crate) fn token_error(parts: &Parts, status: Option<StatusCode>, message: String) -> Error {
+    Error::Auth(Box::new(
+        HttpError::new(parts.method.clone(), parts.uri.path().to_owned(), status)
+            .with_message(Some(message)),
+    ))
+}
+
+pub(crate) fn notify_response(ctx: RequestContext<'_>, parts: &Parts, response: &ResponseBytes) {
+    for interceptor in ctx.interceptors.iter().rev() {
+        interceptor.on_response(parts, response);
+    }
+}
+
+pub(crate) fn notify_error(ctx: RequestContext<'_>, parts: &Parts, error: &Error) {
+    for interceptor in ctx.interceptors.iter().rev() {
+        interceptor.on_error(parts, error);
+    }
+}
+
+pub(crate) fn http_error(
+    ctx: RequestContext<'_>,
+    method: &Method,
+    path: &str,
+    status: StatusCode,
+    headers: &HeaderMap,
+    bytes: &[u8],
+) -> HttpError {
+    HttpError::new(method.clone(), path.to_owned(), Some(status))
+        .with_request_id(extract_request_id(headers))
+        .with_message(extract_message(bytes))
+        .with_body_snippet(capture_snippet(bytes, ctx.body_snippet))
+        .with_retry_after(extract_retry_after(headers))
+}
+
+pub(crate) fn decode_json<Response>(
+    ctx: RequestContext<'_>,
+    method: Method,
+    segments: &[&str],
+    response: &ResponseBytes,
+) -> Result<Response>
+where
+    Response: DeserializeOwned,
+{
+    let bytes = response.body();
+    serde_json::from_slice(bytes).map_err(|e| {
+        let url =
+            url_util::endpoint(ctx.base_url, segments).unwrap_or_else(|_| ctx.base_url.clone());
+        let http = HttpError::new(method, url.path().to_owned(), Some(response.status()))
+            .with_request_id(extract_request_id(response.headers()))
+            .with_body_snippet(capture_snippet(bytes, ctx.body_snippet));
+        Error::decode(http, e)
+    })
 }
 
 fn extract_request_id(headers: &HeaderMap) -> Option<String> {
@@ -105,7 +495,7 @@ fn capture_snippet(bytes: &[u8], config: &BodySnippetConfig) -> Option<String> {
     redact::redact_body_snippet(bytes, config.byte_limit)
 }
 
-fn extract_retry_after(headers: &HeaderMap) -> Option<Duration> {
+pub(crate) fn extract_retry_after(headers: &HeaderMap) -> Option<Duration> {
     let value = headers.get(http::header::RETRY_AFTER)?;
     let value = value.to_str().ok()?.trim();
     if value.is_empty() {
@@ -121,31 +511,3 @@ fn extract_retry_after(headers: &HeaderMap) -> Option<Duration> {
     let diff = when.duration_since(now).unwrap_or(Duration::ZERO);
     Some(diff)
 }
-
-fn compute_retry_delay(
-    retry_after: Option<Duration>,
-    retry: &RetryConfig,
-    attempt: usize,
-) -> Option<Duration> {
-    if let Some(delay) = retry_after {
-        return Some(delay);
-    }
-
-    if retry.base_delay.is_zero() {
-        return Some(Duration::ZERO);
-    }
-
-    let base_ms = retry.base_delay.as_millis().min(u128::from(u64::MAX)) as u64;
-    let max_ms = retry.max_delay.as_millis().min(u128::from(u64::MAX)) as u64;
-
-    let shift = attempt.min(32) as u32;
-    let exp_ms = base_ms.saturating_mul(1u64 << shift);
-    let cap_ms = exp_ms.min(max_ms);
-
-    if cap_ms == 0 {
-        return Some(Duration::ZERO);
-    }
-
-    let jitter_ms = fastrand::u64(0..=cap_ms);
-    Some(Duration::from_millis(jitter_ms))
-}
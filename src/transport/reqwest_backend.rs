@@ -0,0 +1,199 @@
+//! The bundled reqwest backends.
+
+use std::time::Duration;
+
+use crate::{
+    Error, Result,
+    retry::TransportErrorKind,
+    transport::{TransportConfig, TransportError},
+};
+
+#[cfg(any(feature = "rustls", feature = "native-tls"))]
+use crate::transport::tls::TlsSetup;
+#[cfg(feature = "blocking")]
+use crate::transport::{BlockingHttpTransport, BlockingResponseBody};
+#[cfg(feature = "async")]
+use crate::transport::{BoxFuture, HttpTransport, ResponseBody};
+
+/// Applies the builder's transport settings; the async and blocking reqwest builders share
+/// method names but no trait.
+macro_rules! configure {
+    ($builder:expr, $config:expr) => {{
+        let config: &TransportConfig = $config;
+        let builder = $builder
+            .timeout(config.timeout)
+            .connect_timeout(config.connect_timeout)
+            .no_proxy();
+        let builder = config
+            .reqwest_proxies()?
+            .into_iter()
+            .fold(builder, |builder, proxy| builder.proxy(proxy));
+        #[cfg(unix)]
+        let builder = match &config.unix_socket {
+            Some(path) => builder.unix_socket(path.clone()),
+            None => builder,
+        };
+        #[cfg(any(feature = "rustls", feature = "native-tls"))]
+        let builder = match config.tls.setup()? {
+            TlsSetup::Reqwest {
+                roots,
+                built_in_roots,
+                identity,
+            } => {
+                let builder = if built_in_roots {
+                    builder.tls_certs_merge(roots)
+                } else {
+                    builder.tls_certs_only(roots)
+                };
+                match identity {
+                    Some(identity) => builder.identity(identity),
+                    None => builder,
+                }
+            }
+            #[cfg(feature = "rustls")]
+            TlsSetup::Rustls(tls) => builder.tls_backend_preconfigured(*tls),
+        };
+        builder
+    }};
+}
+
+/// [`HttpTransport`] over a `reqwest::Client`; the default for [`Client`](crate::Client).
+#[cfg(feature = "async")]
+#[derive(Clone, Debug)]
+pub struct ReqwestTransport {
+    client: reqwest::Client,
+}
+
+#[cfg(feature = "async")]
+impl ReqwestTransport {
+    /// Wraps a preconfigured client. The client builder's timeout, TLS, proxy and unix socket
+    /// settings do not apply to it; per-attempt timeouts still do.
+    pub fn new(client: reqwest::Client) -> Self {
+        Self { client }
+    }
+
+    /// A `reqwest::ClientBuilder` ready to build; with `rustls` this installs the crypto
+    /// provider reqwest needs.
+    pub fn client_builder() -> reqwest::ClientBuilder {
+        install_crypto_provider();
+        reqwest::Client::builder()
+    }
+
+    pub(crate) fn from_config(config: &TransportConfig) -> Result<Self> {
+        let client = configure!(Self::client_builder(), config)
+            .build()
+            .map_err(|e| Error::transport("failed to build HTTP client", e))?;
+        Ok(Self::new(client))
+    }
+}
+
+#[cfg(feature = "async")]
+impl HttpTransport for ReqwestTransport {
+    fn send(
+        &self,
+        request: http::Request<Vec<u8>>,
+        timeout: Duration,
+    ) -> BoxFuture<'_, std::result::Result<http::Response<ResponseBody>, TransportError>> {
+        Box::pin(async move {
+            let (parts, body) = request.into_parts();
+            let mut request = reqwest::Request::new(parts.method, request_url(&parts.uri)?);
+            *request.headers_mut() = parts.headers;
+            if !body.is_empty() {
+                *request.body_mut() = Some(body.into());
+            }
+            *request.timeout_mut() = Some(timeout);
+            let response = self
+                .client
+                .execute(request)
+                .await
+                .map_err(transport_error)?;
+
+            let mut converted = http::Response::new(ResponseBody::empty());
+            *converted.status_mut() = response.status();
+            *converted.version_mut() = response.version();
+            *converted.headers_mut() = response.headers().clone();
+            *converted.body_mut() = ResponseBody::from_stream(response.bytes_stream());
+            Ok(converted)
+        })
+    }
+}
+
+/// [`BlockingHttpTransport`] over a `reqwest::blocking::Client`; the default for
+/// [`BlockingClient`](crate::BlockingClient).
+#[cfg(feature = "blocking")]
+#[derive(Clone, Debug)]
+pub struct ReqwestBlockingTransport {
+    client: reqwest::blocking::Client,
+}
+
+#[cfg(feature = "blocking")]
+impl ReqwestBlockingTransport {
+    /// Wraps a preconfigured client. The client builder's timeout, TLS, proxy and unix socket
+    /// settings do not apply to it; per-attempt timeouts still do.
+    pub fn new(client: reqwest::blocking::Client) -> Self {
+        Self { client }
+    }
+
+    /// A `reqwest::blocking::ClientBuilder` ready to build; with `rustls` this installs the
+    /// crypto provider reqwest needs.
+    pub fn client_builder() -> reqwest::blocking::ClientBuilder {
+        install_crypto_provider();
+        reqwest::blocking::Client::builder()
+    }
+
+    pub(crate) fn from_config(config: &TransportConfig) -> Result<Self> {
+        let client = configure!(Self::client_builder(), config)
+            .build()
+            .map_err(|e| Error::transport("failed to build HTTP client", e))?;
+        Ok(Self::new(client))
+    }
+}
+
+#[cfg(feature = "blocking")]
+impl BlockingHttpTransport for ReqwestBlockingTransport {
+    fn send(
+        &self,
+        request: http::Request<Vec<u8>>,
+        timeout: Duration,
+    ) -> std::result::Result<http::Response<BlockingResponseBody>, TransportError> {
+        let (parts, body) = request.into_parts();
+        let mut request = reqwest::blocking::Request::new(parts.method, request_url(&parts.uri)?);
+        *request.headers_mut() = parts.headers;
+        if !body.is_empty() {
+            *request.body_mut() = Some(body.into());
+        }
+        *request.timeout_mut() = Some(timeout);
+        let response = self.client.execute(request).map_err(transport_error)?;
+
+        let mut converted = http::Response::new(BlockingResponseBody::empty());
+        *converted.status_mut() = response.status();
+        *converted.version_mut() = response.version();
+        *converted.headers_mut() = response.headers().clone();
+        *converted.body_mut() = BlockingResponseBody::from_reader(response);
+        Ok(converted)
+    }
+}
+
+fn install_crypto_provider() {
+    #[cfg(feature = "rustls")]
+    if rustls::crypto::CryptoProvider::get_default().is_none() {
+        let _ = rustls::crypto::ring::default_provider().install_default();
+    }
+}
+
+fn request_url(uri: &http::Uri) -> std::result::Result<url::Url, TransportError> {
+    url::Url::parse(&uri.to_string()).map_err(|e| TransportError::new(TransportErrorKind::Other, e))
+}
+
+fn transport_error(err: reqwest::Error) -> TransportError {
+    let kind = if err.is_timeout() {
+        TransportErrorKind::Timeout
+    } else if err.is_connect() {
+        TransportErrorKind::Connect
+    } else if err.is_body() || err.is_decode() {
+        TransportErrorKind::Body
+    } else {
+        TransportErrorKind::Other
+    };
+    TransportError::new(kind, err)
+}
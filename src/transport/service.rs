@@ -0,0 +1,158 @@
+//! `tower` view of the async transport.
+
+use std::{
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use bytes::Bytes;
+use tower::{Layer, Service, timeout::error::Elapsed};
+
+use crate::{
+    Result,
+    error::BoxError,
+    retry::TransportErrorKind,
+    transport::{
+        BoxFuture, HttpTransport, ReqwestTransport, ResponseBody, TransportConfig, TransportError,
+    },
+};
+
+/// Per-attempt timeout the client attaches to each request's extensions. Layers may read or
+/// replace it; [`TransportService`] passes it on to the backend.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct AttemptTimeout(pub Duration);
+
+/// An [`HttpTransport`] as a `tower::Service`. This is the innermost service of the stack
+/// given to [`ClientBuilder::layer`](crate::client::ClientBuilder::layer).
+#[derive(Clone)]
+pub struct TransportService {
+    backend: Arc<dyn HttpTransport>,
+    timeout: Duration,
+}
+
+impl TransportService {
+    /// `timeout` applies to requests that carry no [`AttemptTimeout`].
+    pub fn new(backend: impl HttpTransport, timeout: Duration) -> Self {
+        Self {
+            backend: Arc::new(backend),
+            timeout,
+        }
+    }
+}
+
+impl std::fmt::Debug for TransportService {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TransportService")
+            .field("timeout", &self.timeout)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Service<http::Request<Bytes>> for TransportService {
+    type Response = http::Response<ResponseBody>;
+    type Error = TransportError;
+    type Future = BoxFuture<'static, std::result::Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<std::result::Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: http::Request<Bytes>) -> Self::Future {
+        let backend = Arc::clone(&self.backend);
+        let timeout = request
+            .extensions()
+            .get::<AttemptTimeout>()
+            .map_or(self.timeout, |timeout| timeout.0);
+        Box::pin(async move { backend.send(request.map(Vec::from), timeout).await })
+    }
+}
+
+/// Type-erased layer application, kept on the builder until `build`.
+pub(crate) type BoxLayer = Box<dyn FnOnce(TransportService) -> Arc<dyn HttpTransport> + Send>;
+
+pub(crate) fn box_layer<L>(layer: L) -> BoxLayer
+where
+    L: Layer<TransportService> + Send + 'static,
+    L::Service: Service<http::Request<Bytes>, Response = http::Response<ResponseBody>>
+        + Clone
+        + Send
+        + 'static,
+    <L::Service as Service<http::Request<Bytes>>>::Error: Into<BoxError>,
+    <L::Service as Service<http::Request<Bytes>>>::Future: Send,
+{
+    Box::new(move |inner| {
+        Arc::new(Layered {
+            service: Mutex::new(layer.layer(inner)),
+        })
+    })
+}
+
+/// Wraps the configured backend (or the default reqwest one) in the builder's layers. The first
+/// layer added is the outermost, as with `tower::ServiceBuilder`.
+pub(crate) fn apply_layers(
+    config: &TransportConfig,
+    backend: Option<Arc<dyn HttpTransport>>,
+    layers: Vec<BoxLayer>,
+) -> Result<Option<Arc<dyn HttpTransport>>> {
+    if layers.is_empty() {
+        return Ok(backend);
+    }
+    let mut backend = match backend {
+        Some(backend) => backend,
+        None => Arc::new(ReqwestTransport::from_config(config)?),
+    };
+    for layer in layers.into_iter().rev() {
+        let inner = TransportService {
+            backend,
+            timeout: config.timeout,
+        };
+        backend = layer(inner);
+    }
+    Ok(Some(backend))
+}
+
+struct Layered<S> {
+    service: Mutex<S>,
+}
+
+impl<S> HttpTransport for Layered<S>
+where
+    S: Service<http::Request<Bytes>, Response = http::Response<ResponseBody>>
+        + Clone
+        + Send
+        + 'static,
+    S::Error: Into<BoxError>,
+    S::Future: Send,
+{
+    fn send(
+        &self,
+        request: http::Request<Vec<u8>>,
+        timeout: Duration,
+    ) -> BoxFuture<'_, std::result::Result<http::Response<ResponseBody>, TransportError>> {
+        let mut service = self
+            .service
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone();
+        let mut request = request.map(Bytes::from);
+        request.extensions_mut().insert(AttemptTimeout(timeout));
+        Box::pin(async move {
+            std::future::poll_fn(|cx| service.poll_ready(cx))
+                .await
+                .map_err(layer_error)?;
+            service.call(request).await.map_err(layer_error)
+        })
+    }
+}
+
+/// Keeps the kind of errors raised by the backend; a `tower` timeout counts as a timeout so the
+/// retry policy treats it like one.
+fn layer_error(err: impl Into<BoxError>) -> TransportError {
+    let err = err.into();
+    match err.downcast::<TransportError>() {
+        Ok(err) => *err,
+        Err(err) if err.is::<Elapsed>() => TransportError::new(TransportErrorKind::Timeout, err),
+        Err(err) => TransportError::new(TransportErrorKind::Other, err),
+    }
+}
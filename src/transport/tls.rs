@@ -0,0 +1,308 @@
+//! TLS settings shared by the async and blocking transports.
+//!
+//! Extra roots and client identities are handed to reqwest, which supports them on both TLS
+//! backends. SPKI pinning needs a custom certificate verifier, so pinned clients get a
+//! preconfigured rustls config instead. native-tls offers no such hook: the peer certificate is
+//! only visible once a request went out, so pinning is refused rather than checked too late.
+
+use base64::Engine as _;
+
+use crate::{Error, Result};
+
+#[derive(Clone, Default)]
+pub(crate) struct TlsConfig {
+    root_certificates: Vec<Vec<u8>>,
+    identity: Option<ClientIdentity>,
+    built_in_roots_disabled: bool,
+    spki_pins: Vec<[u8; 32]>,
+}
+
+#[derive(Clone)]
+struct ClientIdentity {
+    cert_chain: Vec<u8>,
+    private_key: Vec<u8>,
+}
+
+/// How a transport should configure its reqwest client builder.
+pub(crate) enum TlsSetup {
+    Reqwest {
+        roots: Vec<reqwest::Certificate>,
+        built_in_roots: bool,
+        identity: Option<reqwest::Identity>,
+    },
+    #[cfg(feature = "rustls")]
+    Rustls(Box<rustls::ClientConfig>),
+}
+
+impl TlsConfig {
+    pub(crate) fn add_root_certificate_pem(&mut self, pem: &[u8]) -> Result<()> {
+        let certificates = reqwest::Certificate::from_pem_bundle(pem)
+            .map_err(|e| Error::invalid_config(format!("invalid root certificate PEM: {e}")))?;
+        if certificates.is_empty() {
+            return Err(Error::invalid_config(
+                "root certificate PEM contains no certificates",
+            ));
+        }
+        self.root_certificates.push(pem.to_vec());
+        Ok(())
+    }
+
+    pub(crate) fn set_client_identity_pem(
+        &mut self,
+        cert_chain: &[u8],
+        private_key: &[u8],
+    ) -> Result<()> {
+        let identity = ClientIdentity {
+            cert_chain: cert_chain.to_vec(),
+            private_key: private_key.to_vec(),
+        };
+        identity.to_reqwest()?;
+        self.identity = Some(identity);
+        Ok(())
+    }
+
+    pub(crate) fn set_built_in_roots(&mut self, enabled: bool) {
+        self.built_in_roots_disabled = !enabled;
+    }
+
+    /// Accepts a base64 SHA-256 digest, optionally prefixed with `sha256//` as curl spells it.
+    pub(crate) fn add_spki_pin(&mut self, pin: &str) -> Result<()> {
+        let encoded = pin.trim();
+        let encoded = encoded
+            .strip_prefix("sha256//")
+            .or_else(|| encoded.strip_prefix("sha256/"))
+            .unwrap_or(encoded);
+        let digest = base64::engine::general_purpose::STANDARD
+            .decode(encoded)
+            .map_err(|e| Error::invalid_config(format!("invalid SPKI pin: {e}")))?;
+        let digest = <[u8; 32]>::try_from(digest).map_err(|_| {
+            Error::invalid_config("SPKI pin must be a base64-encoded SHA-256 digest")
+        })?;
+        self.spki_pins.push(digest);
+        Ok(())
+    }
+
+    pub(crate) fn setup(&self) -> Result<TlsSetup> {
+        if self.built_in_roots_disabled && self.root_certificates.is_empty() {
+            return Err(Error::invalid_config(
+                "built-in root certificates are disabled but no root certificates were added",
+            ));
+        }
+
+        if !self.spki_pins.is_empty() {
+            #[cfg(feature = "rustls")]
+            return Ok(TlsSetup::Rustls(Box::new(self.pinned_rustls_config()?)));
+
+            #[cfg(not(feature = "rustls"))]
+            return Err(Error::invalid_config(
+                "SPKI pinning requires the rustls feature; native-tls cannot check the key during the handshake",
+            ));
+        }
+
+        let mut roots = Vec::new();
+        for pem in &self.root_certificates {
+            roots.extend(reqwest::Certificate::from_pem_bundle(pem).map_err(|e| {
+                Error::invalid_config(format!("invalid root certificate PEM: {e}"))
+            })?);
+        }
+
+        Ok(TlsSetup::Reqwest {
+            roots,
+            built_in_roots: !self.built_in_roots_disabled,
+            identity: self
+                .identity
+                .as_ref()
+                .map(ClientIdentity::to_reqwest)
+                .transpose()?,
+        })
+    }
+
+    #[cfg(feature = "rustls")]
+    fn pinned_rustls_config(&self) -> Result<rustls::ClientConfig> {
+        use std::sync::Arc;
+
+        use rustls::{
+            client::{WebPkiServerVerifier, danger::ServerCertVerifier},
+            pki_types::{CertificateDer, PrivateKeyDer, pem::PemObject},
+        };
+
+        let provider = Arc::new(rustls::crypto::ring::default_provider());
+
+        let roots = self
+            .root_certificates
+            .iter()
+            .flat_map(|pem| CertificateDer::pem_slice_iter(pem))
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(|e| Error::invalid_config(format!("invalid root certificate PEM: {e}")))?;
+
+        let verifier: Arc<dyn ServerCertVerifier> = if self.built_in_roots_disabled {
+            let mut store = rustls::RootCertStore::empty();
+            for root in roots {
+                store
+                    .add(root)
+                    .map_err(|e| Error::invalid_config(format!("invalid root certificate: {e}")))?;
+            }
+            WebPkiServerVerifier::builder_with_provider(Arc::new(store), Arc::clone(&provider))
+                .build()
+                .map_err(|e| Error::invalid_config(format!("failed to configure TLS: {e}")))?
+        } else {
+            Arc::new(platform_verifier(roots, Arc::clone(&provider))?)
+        };
+
+        let builder = rustls::ClientConfig::builder_with_provider(provider)
+            .with_safe_default_protocol_versions()
+            .map_err(|e| Error::invalid_config(format!("failed to configure TLS: {e}")))?
+            .dangerous()
+            .with_custom_certificate_verifier(Arc::new(pinning::PinnedVerifier::new(
+                verifier,
+                self.spki_pins.clone(),
+            )));
+
+        let mut config = match &self.identity {
+            Some(identity) => {
+                let cert_chain = CertificateDer::pem_slice_iter(&identity.cert_chain)
+                    .collect::<std::result::Result<Vec<_>, _>>()
+                    .map_err(|e| {
+                        Error::invalid_config(format!("invalid client certificate PEM: {e}"))
+                    })?;
+                let private_key = PrivateKeyDer::from_pem_slice(&identity.private_key)
+                    .map_err(|e| Error::invalid_config(format!("invalid client key PEM: {e}")))?;
+                builder
+                    .with_client_auth_cert(cert_chain, private_key)
+                    .map_err(|e| Error::invalid_config(format!("invalid client identity: {e}")))?
+            }
+            None => builder.with_no_client_auth(),
+        };
+        // What reqwest advertises itself without its `http2` feature, which this crate does not
+        // enable: offering `h2` would let servers pick a protocol the client cannot speak.
+        config.alpn_protocols = vec![b"http/1.1".to_vec()];
+
+        Ok(config)
+    }
+}
+
+#[cfg(all(feature = "rustls", not(target_os = "android")))]
+fn platform_verifier(
+    roots: Vec<rustls::pki_types::CertificateDer<'static>>,
+    provider: std::sync::Arc<rustls::crypto::CryptoProvider>,
+) -> Result<rustls_platform_verifier::Verifier> {
+    rustls_platform_verifier::Verifier::new_with_extra_roots(roots, provider)
+        .map_err(|e| Error::invalid_config(format!("failed to configure TLS: {e}")))
+}
+
+#[cfg(all(feature = "rustls", target_os = "android"))]
+fn platform_verifier(
+    roots: Vec<rustls::pki_types::CertificateDer<'static>>,
+    provider: std::sync::Arc<rustls::crypto::CryptoProvider>,
+) -> Result<rustls_platform_verifier::Verifier> {
+    if !roots.is_empty() {
+        return Err(Error::invalid_config(
+            "extra root certificates with built-in roots are not supported on Android",
+        ));
+    }
+    rustls_platform_verifier::Verifier::new(provider)
+        .map_err(|e| Error::invalid_config(format!("failed to configure TLS: {e}")))
+}
+
+impl ClientIdentity {
+    // native-tls wants the certificate and PKCS#8 key separately; rustls reads one PEM with both.
+    #[cfg(feature = "native-tls")]
+    fn to_reqwest(&self) -> Result<reqwest::Identity> {
+        reqwest::Identity::from_pkcs8_pem(&self.cert_chain, &self.private_key)
+            .map_err(|e| Error::invalid_config(format!("invalid client identity: {e}")))
+    }
+
+    #[cfg(not(feature = "native-tls"))]
+    fn to_reqwest(&self) -> Result<reqwest::Identity> {
+        let mut pem = self.cert_chain.clone();
+        pem.push(b'\n');
+        pem.extend_from_slice(&self.private_key);
+        reqwest::Identity::from_pem(&pem)
+            .map_err(|e| Error::invalid_config(format!("invalid client identity: {e}")))
+    }
+}
+
+#[cfg(feature = "rustls")]
+mod pinning {
+    use std::sync::Arc;
+
+    use rustls::{
+        CertificateError, DigitallySignedStruct, SignatureScheme,
+        client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier},
+        pki_types::{CertificateDer, ServerName, UnixTime},
+    };
+
+    /// Runs normal chain validation, then requires a pinned key somewhere in the presented chain.
+    #[derive(Debug)]
+    pub(super) struct PinnedVerifier {
+        inner: Arc<dyn ServerCertVerifier>,
+        pins: Vec<[u8; 32]>,
+    }
+
+    impl PinnedVerifier {
+        pub(super) fn new(inner: Arc<dyn ServerCertVerifier>, pins: Vec<[u8; 32]>) -> Self {
+            Self { inner, pins }
+        }
+
+        fn is_pinned(&self, cert: &CertificateDer<'_>) -> bool {
+            let Ok(parsed) = webpki::EndEntityCert::try_from(cert) else {
+                return false;
+            };
+            let spki = parsed.subject_public_key_info();
+            let digest = ring::digest::digest(&ring::digest::SHA256, spki.as_ref());
+            self.pins.iter().any(|pin| pin == digest.as_ref())
+        }
+    }
+
+    impl ServerCertVerifier for PinnedVerifier {
+        fn verify_server_cert(
+            &self,
+            end_entity: &CertificateDer<'_>,
+            intermediates: &[CertificateDer<'_>],
+            server_name: &ServerName<'_>,
+            ocsp_response: &[u8],
+            now: UnixTime,
+        ) -> Result<ServerCertVerified, rustls::Error> {
+            let verified = self.inner.verify_server_cert(
+                end_entity,
+                intermediates,
+                server_name,
+                ocsp_response,
+                now,
+            )?;
+
+            if std::iter::once(end_entity)
+                .chain(intermediates)
+                .any(|cert| self.is_pinned(cert))
+            {
+                Ok(verified)
+            } else {
+                Err(rustls::Error::InvalidCertificate(
+                    CertificateError::ApplicationVerificationFailure,
+                ))
+            }
+        }
+
+        fn verify_tls12_signature(
+            &self,
+            message: &[u8],
+            cert: &CertificateDer<'_>,
+            dss: &DigitallySignedStruct,
+        ) -> Result<HandshakeSignatureValid, rustls::Error> {
+            self.inner.verify_tls12_signature(message, cert, dss)
+        }
+
+        fn verify_tls13_signature(
+            &self,
+            message: &[u8],
+            cert: &CertificateDer<'_>,
+            dss: &DigitallySignedStruct,
+        ) -> Result<HandshakeSignatureValid, rustls::Error> {
+            self.inner.verify_tls13_signature(message, cert, dss)
+        }
+
+        fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+            self.inner.supported_verify_schemes()
+        }
+    }
+}
@@ -0,0 +1,249 @@
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{Deserialize, Serialize};
+
+use super::ProvisionedAlertRule;
+
+/// `{"status": "success", "data": ...}` envelope of the Prometheus-compatible API.
+#[derive(Clone, Debug, Deserialize)]
+pub(crate) struct PrometheusResponse<T> {
+    pub(crate) data: T,
+}
+
+/// Evaluation state of Grafana-managed rules, from `/prometheus/grafana/api/v1/rules`.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleDiscovery {
+    #[serde(default)]
+    pub groups: Vec<RuleGroupState>,
+    /// Rule counts by state and health, e.g. `firing` or `error`.
+    #[serde(default)]
+    pub totals: BTreeMap<String, i64>,
+}
+
+impl RuleDiscovery {
+    pub fn rules(&self) -> impl Iterator<Item = &RuleState> {
+        self.groups.iter().flat_map(|group| &group.rules)
+    }
+
+    /// Rules whose last evaluation failed or returned no data.
+    pub fn unhealthy(&self) -> impl Iterator<Item = &RuleState> {
+        self.rules()
+            .filter(|rule| rule.is_error() || rule.is_no_data())
+    }
+
+    /// Pairs every rule with the definition of the same UID.
+    pub fn with_definitions(
+        self,
+        definitions: impl IntoIterator<Item = ProvisionedAlertRule>,
+    ) -> Vec<ManagedRuleState> {
+        let mut by_uid: HashMap<_, _> = definitions
+            .into_iter()
+            .filter_map(|rule| Some((rule.uid.clone()?, rule)))
+            .collect();
+        self.groups
+            .into_iter()
+            .flat_map(|group| group.rules)
+            .map(|state| ManagedRuleState {
+                definition: by_uid.remove(&state.uid),
+                state,
+            })
+            .collect()
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleGroupState {
+    pub name: String,
+    /// Folder title.
+    #[serde(default)]
+    pub file: String,
+    #[serde(default)]
+    pub folder_uid: Option<String>,
+    #[serde(default)]
+    pub rules: Vec<RuleState>,
+    /// Evaluation interval in seconds.
+    #[serde(default)]
+    pub interval: f64,
+    #[serde(default)]
+    pub last_evaluation: Option<String>,
+    /// Seconds the last evaluation took.
+    #[serde(default)]
+    pub evaluation_time: f64,
+    #[serde(default)]
+    pub totals: BTreeMap<String, i64>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleState {
+    /// Grafana rule UID, as used by the provisioning API.
+    #[serde(default)]
+    pub uid: String,
+    pub name: String,
+    #[serde(default)]
+    pub folder_uid: Option<String>,
+    #[serde(default)]
+    pub query: String,
+    #[serde(rename = "type")]
+    pub kind: RuleKind,
+    /// Unset for recording rules.
+    #[serde(default)]
+    pub state: Option<RuleEvalState>,
+    pub health: RuleHealth,
+    #[serde(default)]
+    pub last_error: Option<String>,
+    /// Pending period in seconds.
+    #[serde(default)]
+    pub duration: Option<f64>,
+    #[serde(default)]
+    pub labels: BTreeMap<String, String>,
+    #[serde(default)]
+    pub annotations: BTreeMap<String, String>,
+    #[serde(default)]
+    pub alerts: Vec<AlertInstance>,
+    /// Alert instance counts by state, e.g. `alerting` or `nodata`.
+    #[serde(default)]
+    pub totals: BTreeMap<String, i64>,
+    #[serde(default)]
+    pub last_evaluation: Option<String>,
+    #[serde(default)]
+    pub evaluation_time: f64,
+    #[serde(default)]
+    pub is_paused: bool,
+}
+
+impl RuleState {
+    pub fn is_firing(&self) -> bool {
+        self.state == Some(RuleEvalState::Firing)
+    }
+
+    /// The last evaluation failed; see `last_error`.
+    pub fn is_error(&self) -> bool {
+        self.health == RuleHealth::Error
+            || self.last_error.as_deref().is_some_and(|e| !e.is_empty())
+    }
+
+    pub fn is_no_data(&self) -> bool {
+        self.health == RuleHealth::NoData
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RuleKind {
+    Alerting,
+    Recording,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RuleEvalState {
+    Inactive,
+    Pending,
+    Firing,
+    Recovering,
+    #[serde(other)]
+    Unknown,
+}
+
+impl RuleEvalState {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Inactive => "inactive",
+            Self::Pending => "pending",
+            Self::Firing => "firing",
+            Self::Recovering => "recovering",
+            Self::Unknown => "unknown",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RuleHealth {
+    Ok,
+    Error,
+    NoData,
+    #[serde(other)]
+    Unknown,
+}
+
+impl RuleHealth {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Ok => "ok",
+            Self::Error => "error",
+            Self::NoData => "nodata",
+            Self::Unknown => "unknown",
+        }
+    }
+}
+
+/// One alert instance (label set) of a rule.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AlertInstance {
+    #[serde(default)]
+    pub labels: BTreeMap<String, String>,
+    #[serde(default)]
+    pub annotations: BTreeMap<String, String>,
+    /// `Normal`, `Alerting`, `Pending`, `NoData` or `Error`, optionally followed by a reason such
+    /// as `Normal (MissingSeries)`.
+    pub state: String,
+    #[serde(default)]
+    pub active_at: Option<String>,
+    #[serde(default)]
+    pub value: String,
+}
+
+impl AlertInstance {
+    pub fn is_firing(&self) -> bool {
+        self.state.starts_with("Alerting")
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+pub(crate) struct AlertInstances {
+    #[serde(default)]
+    pub(crate) alerts: Vec<AlertInstance>,
+}
+
+/// Filters for [`RuleDiscovery`]; every set field must match.
+#[derive(Clone, Debug, Default)]
+pub struct RuleStateFilter {
+    pub folder_uid: Option<String>,
+    /// Group name; needs `folder_uid`.
+    pub rule_group: Option<String>,
+    pub rule_uid: Option<String>,
+    /// Rules in any of these states.
+    pub states: Vec<RuleEvalState>,
+    /// Rules with any of these health values.
+    pub health: Vec<RuleHealth>,
+}
+
+impl RuleStateFilter {
+    pub(crate) fn query(&self) -> Vec<(&'static str, &str)> {
+        let mut query = Vec::new();
+        for (name, value) in [
+            ("folder_uid", &self.folder_uid),
+            ("rule_group", &self.rule_group),
+            ("rule_uid", &self.rule_uid),
+        ] {
+            if let Some(value) = value {
+                query.push((name, value.as_str()));
+            }
+        }
+        query.extend(self.states.iter().map(|state| ("state", state.as_str())));
+        query.extend(self.health.iter().map(|health| ("health", health.as_str())));
+        query
+    }
+}
+
+/// A rule's evaluation state with its provisioning definition, when one was found.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ManagedRuleState {
+    pub state: RuleState,
+    pub definition: Option<ProvisionedAlertRule>,
+}
@@ -0,0 +1,404 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Alert rule as managed by the provisioning API (`/v1/provisioning/alert-rules`).
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProvisionedAlertRule {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<i64>,
+    /// Assigned by Grafana when empty on create.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uid: Option<String>,
+    #[serde(rename = "orgID", default)]
+    pub org_id: i64,
+    #[serde(rename = "folderUID")]
+    pub folder_uid: String,
+    pub rule_group: String,
+    pub title: String,
+    /// `ref_id` of the query or expression that decides whether the rule fires.
+    pub condition: String,
+    pub data: Vec<AlertQuery>,
+    /// Last change, as reported by Grafana; ignored on writes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated: Option<String>,
+    pub no_data_state: NoDataState,
+    pub exec_err_state: ExecErrState,
+    /// Pending period, e.g. `5m`.
+    #[serde(rename = "for", default, skip_serializing_if = "Option::is_none")]
+    pub pending_period: Option<String>,
+    #[serde(
+        rename = "keep_firing_for",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub keep_firing_for: Option<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub annotations: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub labels: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provenance: Option<String>,
+    #[serde(default)]
+    pub is_paused: bool,
+    #[serde(
+        rename = "notification_settings",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub notification_settings: Option<AlertRuleNotificationSettings>,
+    /// Makes this a recording rule.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub record: Option<RecordingRule>,
+    #[serde(
+        rename = "missing_series_evals_to_resolve",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub missing_series_evals_to_resolve: Option<i64>,
+}
+
+impl ProvisionedAlertRule {
+    pub fn new(
+        folder_uid: impl Into<String>,
+        rule_group: impl Into<String>,
+        title: impl Into<String>,
+        condition: impl Into<String>,
+        data: Vec<AlertQuery>,
+    ) -> Self {
+        Self {
+            id: None,
+            uid: None,
+            org_id: 0,
+            folder_uid: folder_uid.into(),
+            rule_group: rule_group.into(),
+            title: title.into(),
+            condition: condition.into(),
+            data,
+            updated: None,
+            no_data_state: NoDataState::NoData,
+            exec_err_state: ExecErrState::Error,
+            pending_period: None,
+            keep_firing_for: None,
+            annotations: BTreeMap::new(),
+            labels: BTreeMap::new(),
+            provenance: None,
+            is_paused: false,
+            notification_settings: None,
+            record: None,
+            missing_series_evals_to_resolve: None,
+        }
+    }
+}
+
+/// One query or expression of an alert rule.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AlertQuery {
+    pub ref_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub query_type: Option<String>,
+    #[serde(default)]
+    pub relative_time_range: RelativeTimeRange,
+    /// `__expr__` for server-side expressions.
+    pub datasource_uid: String,
+    /// Datasource-specific query model.
+    pub model: serde_json::Value,
+}
+
+impl AlertQuery {
+    pub fn new(
+        ref_id: impl Into<String>,
+        datasource_uid: impl Into<String>,
+        model: serde_json::Value,
+    ) -> Self {
+        Self {
+            ref_id: ref_id.into(),
+            query_type: None,
+            relative_time_range: RelativeTimeRange::default(),
+            datasource_uid: datasource_uid.into(),
+            model,
+        }
+    }
+}
+
+/// Query window in seconds before evaluation time, e.g. `from: 600, to: 0` for the last ten
+/// minutes.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct RelativeTimeRange {
+    pub from: i64,
+    pub to: i64,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum NoDataState {
+    Alerting,
+    NoData,
+    #[serde(rename = "OK")]
+    Ok,
+    KeepLast,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum ExecErrState {
+    Alerting,
+    Error,
+    #[serde(rename = "OK")]
+    Ok,
+    KeepLast,
+}
+
+/// Simplified routing: sends the rule's alerts straight to a contact point.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct AlertRuleNotificationSettings {
+    pub receiver: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub group_by: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group_wait: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group_interval: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repeat_interval: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub mute_time_intervals: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub active_time_intervals: Vec<String>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct RecordingRule {
+    /// Name of the recorded metric.
+    pub metric: String,
+    /// `ref_id` of the query whose result is recorded.
+    pub from: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_datasource_uid: Option<String>,
+}
+
+/// Rules of a folder evaluated together at one interval.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AlertRuleGroup {
+    pub title: String,
+    pub folder_uid: String,
+    /// Evaluation interval in seconds.
+    pub interval: i64,
+    #[serde(default)]
+    pub rules: Vec<ProvisionedAlertRule>,
+}
+
+impl AlertRuleGroup {
+    pub fn new(title: impl Into<String>, folder_uid: impl Into<String>, interval: i64) -> Self {
+        Self {
+            title: title.into(),
+            folder_uid: folder_uid.into(),
+            interval,
+            rules: Vec::new(),
+        }
+    }
+}
+
+/// A contact point integration (`/v1/provisioning/contact-points`).
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContactPoint {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uid: Option<String>,
+    pub name: String,
+    /// Integration type such as `email`, `slack` or `webhook`.
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// Integration settings; secure fields come back redacted.
+    #[serde(default)]
+    pub settings: serde_json::Value,
+    #[serde(default)]
+    pub disable_resolve_message: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provenance: Option<String>,
+}
+
+impl ContactPoint {
+    pub fn new(
+        name: impl Into<String>,
+        kind: impl Into<String>,
+        settings: serde_json::Value,
+    ) -> Self {
+        Self {
+            uid: None,
+            name: name.into(),
+            kind: kind.into(),
+            settings,
+            disable_resolve_message: false,
+            provenance: None,
+        }
+    }
+}
+
+/// A node of the notification policy tree; the root is what `/v1/provisioning/policies` reads
+/// and replaces.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Route {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub receiver: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub group_by: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub object_matchers: Vec<ObjectMatcher>,
+    #[serde(rename = "continue", default)]
+    pub continue_matching: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group_wait: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group_interval: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repeat_interval: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub mute_time_intervals: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub active_time_intervals: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub routes: Vec<Route>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provenance: Option<String>,
+}
+
+impl Route {
+    pub fn new(receiver: impl Into<String>) -> Self {
+        Self {
+            receiver: Some(receiver.into()),
+            ..Self::default()
+        }
+    }
+}
+
+/// Label matcher of a policy, sent as `["name", "=", "value"]`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ObjectMatcher {
+    pub name: String,
+    pub op: MatchType,
+    pub value: String,
+}
+
+impl ObjectMatcher {
+    pub fn new(name: impl Into<String>, op: MatchType, value: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            op,
+            value: value.into(),
+        }
+    }
+}
+
+impl Serialize for ObjectMatcher {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        (&self.name, self.op, &self.value).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for ObjectMatcher {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let (name, op, value) = <(String, MatchType, String)>::deserialize(deserializer)?;
+        Ok(Self { name, op, value })
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum MatchType {
+    #[serde(rename = "=")]
+    Equal,
+    #[serde(rename = "!=")]
+    NotEqual,
+    #[serde(rename = "=~")]
+    Regex,
+    #[serde(rename = "!~")]
+    NotRegex,
+}
+
+/// Named set of time intervals that mutes (or activates) notification policies.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct MuteTimeInterval {
+    pub name: String,
+    #[serde(default)]
+    pub time_intervals: Vec<TimeInterval>,
+    /// Current version; sent back on update and delete so concurrent changes are rejected with
+    /// [`Error::Conflict`](crate::Error::Conflict).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provenance: Option<String>,
+}
+
+impl MuteTimeInterval {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            ..Self::default()
+        }
+    }
+}
+
+/// Every field narrows the interval; empty fields match everything.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct TimeInterval {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub times: Vec<TimeRange>,
+    /// Days such as `monday` or ranges such as `monday:friday`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub weekdays: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub days_of_month: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub months: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub years: Vec<String>,
+    /// IANA time zone, UTC when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub location: Option<String>,
+}
+
+/// Time of day as `HH:MM`.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct TimeRange {
+    pub start_time: String,
+    pub end_time: String,
+}
+
+/// Notification template group (`/v1/provisioning/templates`).
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct NotificationTemplate {
+    pub name: String,
+    pub template: String,
+    /// Current version; sent back on update and delete so concurrent changes are rejected with
+    /// [`Error::Conflict`](crate::Error::Conflict).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provenance: Option<String>,
+}
+
+impl NotificationTemplate {
+    pub fn new(name: impl Into<String>, template: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            template: template.into(),
+            ..Self::default()
+        }
+    }
+
+    /// Body of `PUT /v1/provisioning/templates/{name}`, which names the template in the path.
+    pub(crate) fn content(&self) -> NotificationTemplateContent<'_> {
+        NotificationTemplateContent {
+            template: &self.template,
+            version: self.version.as_deref(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub(crate) struct NotificationTemplateContent<'a> {
+    template: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    version: Option<&'a str>,
+}
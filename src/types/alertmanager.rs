@@ -0,0 +1,299 @@
+use std::{
+    collections::BTreeMap,
+    fmt,
+    time::{Duration, SystemTime},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::util::time::{rfc3339, saturating_add};
+
+/// Label matcher of the Alertmanager v2 API, used by silences and alert filters.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Matcher {
+    pub name: String,
+    pub value: String,
+    #[serde(default)]
+    pub is_regex: bool,
+    #[serde(default = "default_true")]
+    pub is_equal: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl Matcher {
+    /// `name="value"`
+    pub fn equal(name: impl Into<String>, value: impl Into<String>) -> Self {
+        Self::with(name, value, false, true)
+    }
+
+    /// `name!="value"`
+    pub fn not_equal(name: impl Into<String>, value: impl Into<String>) -> Self {
+        Self::with(name, value, false, false)
+    }
+
+    /// `name=~"value"`
+    pub fn regex(name: impl Into<String>, value: impl Into<String>) -> Self {
+        Self::with(name, value, true, true)
+    }
+
+    /// `name!~"value"`
+    pub fn not_regex(name: impl Into<String>, value: impl Into<String>) -> Self {
+        Self::with(name, value, true, false)
+    }
+
+    fn with(
+        name: impl Into<String>,
+        value: impl Into<String>,
+        is_regex: bool,
+        is_equal: bool,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            value: value.into(),
+            is_regex,
+            is_equal,
+        }
+    }
+}
+
+/// The matcher in the `filter` syntax of the Alertmanager API, e.g. `severity=~"crit|warn"`.
+impl fmt::Display for Matcher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let op = match (self.is_equal, self.is_regex) {
+            (true, false) => "=",
+            (false, false) => "!=",
+            (true, true) => "=~",
+            (false, true) => "!~",
+        };
+        write!(f, "{}{op}\"", self.name)?;
+        for c in self.value.chars() {
+            match c {
+                '"' => f.write_str("\\\"")?,
+                '\\' => f.write_str("\\\\")?,
+                '\n' => f.write_str("\\n")?,
+                c => write!(f, "{c}")?,
+            }
+        }
+        f.write_str("\"")
+    }
+}
+
+/// A silence to create or update. Times are RFC 3339.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Silence {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    pub matchers: Vec<Matcher>,
+    pub starts_at: String,
+    pub ends_at: String,
+    pub created_by: String,
+    pub comment: String,
+}
+
+impl Silence {
+    pub fn new(
+        matchers: Vec<Matcher>,
+        starts_at: impl Into<String>,
+        ends_at: impl Into<String>,
+        created_by: impl Into<String>,
+        comment: impl Into<String>,
+    ) -> Self {
+        Self {
+            id: None,
+            matchers,
+            starts_at: starts_at.into(),
+            ends_at: ends_at.into(),
+            created_by: created_by.into(),
+            comment: comment.into(),
+        }
+    }
+
+    /// A silence starting now and lasting `duration`, ending no later than the year 9999.
+    pub fn for_duration(
+        matchers: Vec<Matcher>,
+        duration: Duration,
+        created_by: impl Into<String>,
+        comment: impl Into<String>,
+    ) -> Self {
+        let now = SystemTime::now();
+        Self::new(
+            matchers,
+            rfc3339(now),
+            rfc3339(saturating_add(now, duration)),
+            created_by,
+            comment,
+        )
+    }
+}
+
+/// A silence as returned by Alertmanager.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GettableSilence {
+    pub id: String,
+    pub status: SilenceStatus,
+    #[serde(default)]
+    pub updated_at: Option<String>,
+    pub matchers: Vec<Matcher>,
+    pub starts_at: String,
+    pub ends_at: String,
+    #[serde(default)]
+    pub created_by: String,
+    #[serde(default)]
+    pub comment: String,
+}
+
+impl GettableSilence {
+    pub fn is_active(&self) -> bool {
+        self.status.state == SilenceState::Active
+    }
+
+    /// The editable part, e.g. to extend `ends_at` and pass to `update_silence`.
+    pub fn to_silence(&self) -> Silence {
+        Silence {
+            id: Some(self.id.clone()),
+            matchers: self.matchers.clone(),
+            starts_at: self.starts_at.clone(),
+            ends_at: self.ends_at.clone(),
+            created_by: self.created_by.clone(),
+            comment: self.comment.clone(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct SilenceStatus {
+    pub state: SilenceState,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SilenceState {
+    Active,
+    Pending,
+    Expired,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub(crate) struct SilenceCreated {
+    #[serde(rename = "silenceID")]
+    pub(crate) silence_id: String,
+}
+
+/// An alert as returned by Alertmanager.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GettableAlert {
+    #[serde(default)]
+    pub labels: BTreeMap<String, String>,
+    #[serde(default)]
+    pub annotations: BTreeMap<String, String>,
+    pub starts_at: String,
+    #[serde(default)]
+    pub ends_at: Option<String>,
+    #[serde(default)]
+    pub updated_at: Option<String>,
+    #[serde(default, rename = "generatorURL")]
+    pub generator_url: Option<String>,
+    pub fingerprint: String,
+    pub status: AlertStatus,
+    #[serde(default)]
+    pub receivers: Vec<Receiver>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AlertStatus {
+    pub state: AlertState,
+    #[serde(default)]
+    pub silenced_by: Vec<String>,
+    #[serde(default)]
+    pub inhibited_by: Vec<String>,
+    /// Mute or active time intervals currently muting the alert.
+    #[serde(default)]
+    pub muted_by: Vec<String>,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AlertState {
+    Unprocessed,
+    Active,
+    Suppressed,
+}
+
+/// Alerts grouped as by the notification policy that routed them.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AlertGroup {
+    #[serde(default)]
+    pub labels: BTreeMap<String, String>,
+    pub receiver: Receiver,
+    #[serde(default)]
+    pub alerts: Vec<GettableAlert>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Receiver {
+    pub name: String,
+}
+
+/// Filters for listing alerts; unset fields use Alertmanager's defaults, which include every
+/// state.
+#[derive(Clone, Debug, Default)]
+pub struct AlertFilter {
+    pub active: Option<bool>,
+    pub silenced: Option<bool>,
+    pub inhibited: Option<bool>,
+    pub unprocessed: Option<bool>,
+    /// Alerts must match all of these.
+    pub matchers: Vec<Matcher>,
+    /// Regex on the receiver name.
+    pub receiver: Option<String>,
+}
+
+impl AlertFilter {
+    pub fn matcher(mut self, matcher: Matcher) -> Self {
+        self.matchers.push(matcher);
+        self
+    }
+
+    /// Only active alerts: neither silenced nor inhibited.
+    pub fn firing() -> Self {
+        Self {
+            active: Some(true),
+            silenced: Some(false),
+            inhibited: Some(false),
+            ..Self::default()
+        }
+    }
+
+    pub(crate) fn query(&self) -> Vec<(&'static str, String)> {
+        let mut query = Vec::new();
+        for (name, value) in [
+            ("active", self.active),
+            ("silenced", self.silenced),
+            ("inhibited", self.inhibited),
+            ("unprocessed", self.unprocessed),
+        ] {
+            if let Some(value) = value {
+                query.push((name, value.to_string()));
+            }
+        }
+        query.extend(filter_query(&self.matchers));
+        if let Some(receiver) = &self.receiver {
+            query.push(("receiver", receiver.clone()));
+        }
+        query
+    }
+}
+
+pub(crate) fn filter_query(matchers: &[Matcher]) -> impl Iterator<Item = (&'static str, String)> {
+    matchers
+        .iter()
+        .map(|matcher| ("filter", matcher.to_string()))
+}
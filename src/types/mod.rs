@@ -1,5 +1,8 @@
 //! Data types (request/response models).
 
+mod alert_state;
+mod alerting;
+mod alertmanager;
 mod common;
 mod dashboards;
 mod datasources;
@@ -9,9 +12,13 @@ pub mod openapi;
 mod org;
 mod search;
 mod service_accounts;
+mod state_history;
 mod teams;
 mod user;
 
+pub use alert_state::*;
+pub use alerting::*;
+pub use alertmanager::*;
 pub use common::{
     DashboardUid, DatasourceId, FolderUid, OrgId, ServiceAccountId, SuccessResponse, TeamId,
     TokenId, UserId,
@@ -23,5 +30,6 @@ pub use health::HealthResponse;
 pub use org::*;
 pub use search::*;
 pub use service_accounts::*;
+pub use state_history::*;
 pub use teams::*;
 pub use user::*;
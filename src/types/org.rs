@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+use crate::types::OrgId;
+
 #[derive(Clone, Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Address {
@@ -19,6 +21,13 @@ pub struct OrgDetails {
     pub name: Option<String>,
 }
 
+/// An entry of `GET /orgs`.
+#[derive(Clone, Debug, Deserialize)]
+pub struct OrgSummary {
+    pub id: OrgId,
+    pub name: String,
+}
+
 #[derive(Clone, Debug, Default, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct UpdateOrgRequest {
@@ -0,0 +1,292 @@
+use std::{
+    cmp::Ordering,
+    collections::BTreeMap,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Deserializer, de::Error as _};
+
+use crate::Error;
+
+/// Filters for `/v1/rules/history`.
+#[derive(Clone, Debug, Default)]
+pub struct StateHistoryQuery {
+    pub rule_uid: Option<String>,
+    /// Instances whose labels include all of these.
+    pub labels: BTreeMap<String, String>,
+    /// Start of the range, second precision.
+    pub from: Option<SystemTime>,
+    /// End of the range, second precision; now when unset.
+    pub to: Option<SystemTime>,
+    pub dashboard_uid: Option<String>,
+    pub panel_id: Option<i64>,
+    /// Most transitions returned by one request; Grafana's default applies when unset, except
+    /// in chunked fetches, which send 1000 so they can tell when a window was cut off.
+    pub limit: Option<u32>,
+}
+
+/// `limit` sent by chunked fetches when the query sets none.
+const CHUNK_LIMIT: u32 = 1000;
+
+impl StateHistoryQuery {
+    pub fn rule(uid: impl Into<String>) -> Self {
+        Self {
+            rule_uid: Some(uid.into()),
+            ..Self::default()
+        }
+    }
+
+    pub fn label(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.labels.insert(name.into(), value.into());
+        self
+    }
+
+    pub fn range(mut self, from: SystemTime, to: SystemTime) -> Self {
+        self.from = Some(from);
+        self.to = Some(to);
+        self
+    }
+
+    pub fn limit(mut self, limit: u32) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    pub(crate) fn query(&self) -> Vec<(String, String)> {
+        let mut query = Vec::new();
+        if let Some(uid) = &self.rule_uid {
+            query.push(("ruleUID".to_owned(), uid.clone()));
+        }
+        for (name, time) in [("from", self.from), ("to", self.to)] {
+            if let Some(time) = time {
+                query.push((name.to_owned(), unix_secs(time).to_string()));
+            }
+        }
+        if let Some(uid) = &self.dashboard_uid {
+            query.push(("dashboardUID".to_owned(), uid.clone()));
+        }
+        if let Some(panel_id) = self.panel_id {
+            query.push(("panelID".to_owned(), panel_id.to_string()));
+        }
+        if let Some(limit) = self.limit {
+            query.push(("limit".to_owned(), limit.to_string()));
+        }
+        for (name, value) in &self.labels {
+            query.push((format!("labels_{name}"), value.clone()));
+        }
+        query
+    }
+
+    /// This query cut into windows of at most `chunk`, last window first.
+    pub(crate) fn windows(&self, chunk: Duration) -> crate::Result<Vec<Self>> {
+        let from = self
+            .from
+            .ok_or_else(|| Error::invalid_config("state history chunks need a `from` time"))?;
+        if chunk < Duration::from_secs(1) {
+            return Err(Error::invalid_config(
+                "state history chunks must be at least one second",
+            ));
+        }
+        if self.limit == Some(0) {
+            return Err(Error::invalid_config(
+                "state history chunks need a `limit` above zero",
+            ));
+        }
+        let to = self.to.unwrap_or_else(SystemTime::now);
+        let query = Self {
+            limit: Some(self.limit.unwrap_or(CHUNK_LIMIT)),
+            ..self.clone()
+        };
+        let mut windows = Vec::new();
+        let mut start = from;
+        while start < to {
+            let end = start.checked_add(chunk).map_or(to, |end| end.min(to));
+            windows.push(query.window(start, end));
+            start = end;
+        }
+        windows.reverse();
+        Ok(windows)
+    }
+
+    /// The two halves of this window when `page` may have been cut off by `limit`.
+    pub(crate) fn split(&self, page: &[StateTransition]) -> Option<[Self; 2]> {
+        let limit = usize::try_from(self.limit?).ok()?;
+        let (from, to) = (self.from?, self.to?);
+        let span = to.duration_since(from).ok()?;
+        if page.len() < limit || span < Duration::from_secs(2) {
+            return None;
+        }
+        let middle = from + span / 2;
+        Some([self.window(middle, to), self.window(from, middle)])
+    }
+
+    fn window(&self, from: SystemTime, to: SystemTime) -> Self {
+        Self {
+            from: Some(from),
+            to: Some(to),
+            ..self.clone()
+        }
+    }
+}
+
+/// Oldest first, without the duplicates adjacent windows return at their shared second.
+pub(crate) fn merge_transitions(mut transitions: Vec<StateTransition>) -> Vec<StateTransition> {
+    // Every field `dedup` compares is part of the order, so equal rows always end up adjacent.
+    transitions.sort_by(|a, b| {
+        (a.time, &a.rule_uid, &a.labels, &a.to, &a.from)
+            .cmp(&(b.time, &b.rule_uid, &b.labels, &b.to, &b.from))
+            .then_with(|| cmp_values(&a.values, &b.values))
+            .then_with(|| {
+                (&a.error, &a.rule_title, &a.condition, &a.fingerprint).cmp(&(
+                    &b.error,
+                    &b.rule_title,
+                    &b.condition,
+                    &b.fingerprint,
+                ))
+            })
+    });
+    transitions.dedup();
+    transitions
+}
+
+fn cmp_values(a: &BTreeMap<String, f64>, b: &BTreeMap<String, f64>) -> Ordering {
+    a.len().cmp(&b.len()).then_with(|| {
+        a.iter()
+            .zip(b)
+            .map(|((a_ref, a_value), (b_ref, b_value))| {
+                a_ref.cmp(b_ref).then(a_value.total_cmp(b_value))
+            })
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or(Ordering::Equal)
+    })
+}
+
+fn unix_secs(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// One state change of one alert instance.
+#[derive(Clone, Debug, PartialEq)]
+pub struct StateTransition {
+    pub time: SystemTime,
+    /// Previous state, e.g. `Normal`, or `Normal (MissingSeries)` with a reason.
+    pub from: String,
+    /// New state, e.g. `Alerting`.
+    pub to: String,
+    /// Instance labels.
+    pub labels: BTreeMap<String, String>,
+    /// Query and expression results by `ref_id` at the time of the change.
+    pub values: BTreeMap<String, f64>,
+    /// Evaluation error that caused the change, if any.
+    pub error: Option<String>,
+    pub rule_uid: String,
+    pub rule_title: String,
+    pub condition: String,
+    pub fingerprint: String,
+}
+
+/// The transitions of a history response, decoded from its data frame (`time`, `line` and
+/// `labels` columns).
+pub(crate) struct StateHistoryFrame(pub(crate) Vec<StateTransition>);
+
+#[derive(Deserialize)]
+struct Frame {
+    schema: Schema,
+    #[serde(default)]
+    data: Option<FrameData>,
+}
+
+#[derive(Deserialize)]
+struct Schema {
+    #[serde(default)]
+    fields: Vec<Field>,
+}
+
+#[derive(Deserialize)]
+struct Field {
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct FrameData {
+    #[serde(default)]
+    values: Vec<Vec<serde_json::Value>>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Line {
+    #[serde(default)]
+    previous: String,
+    #[serde(default)]
+    current: String,
+    #[serde(default)]
+    error: Option<String>,
+    #[serde(default)]
+    values: Option<BTreeMap<String, Option<f64>>>,
+    #[serde(default)]
+    condition: String,
+    #[serde(default)]
+    fingerprint: String,
+    #[serde(default)]
+    rule_title: String,
+    #[serde(default, rename = "ruleUID")]
+    rule_uid: String,
+    #[serde(default)]
+    labels: BTreeMap<String, String>,
+}
+
+impl<'de> Deserialize<'de> for StateHistoryFrame {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let frame = Frame::deserialize(deserializer)?;
+        let Some(mut columns) = frame.data.map(|data| data.values) else {
+            return Ok(Self(Vec::new()));
+        };
+        let mut column = |name: &str| {
+            frame
+                .schema
+                .fields
+                .iter()
+                .position(|field| field.name == name)
+                .and_then(|index| columns.get_mut(index))
+                .map(std::mem::take)
+        };
+        let times = column("time").ok_or_else(|| D::Error::missing_field("time"))?;
+        let lines = column("line").ok_or_else(|| D::Error::missing_field("line"))?;
+        let labels = column("labels").unwrap_or_default();
+
+        let mut transitions = Vec::with_capacity(times.len());
+        for (row, (time, line)) in times.into_iter().zip(lines).enumerate() {
+            let millis = time
+                .as_u64()
+                .ok_or_else(|| D::Error::custom(format!("invalid time in row {row}")))?;
+            let line = Line::deserialize(line).map_err(D::Error::custom)?;
+            let labels = match labels.get(row) {
+                Some(value) if !value.is_null() => {
+                    BTreeMap::deserialize(value).map_err(D::Error::custom)?
+                }
+                _ => line.labels,
+            };
+            transitions.push(StateTransition {
+                time: UNIX_EPOCH + Duration::from_millis(millis),
+                from: line.previous,
+                to: line.current,
+                labels,
+                values: line
+                    .values
+                    .unwrap_or_default()
+                    .into_iter()
+                    .filter_map(|(ref_id, value)| Some((ref_id, value?)))
+                    .collect(),
+                error: line.error.filter(|error| !error.is_empty()),
+                rule_uid: line.rule_uid,
+                rule_title: line.rule_title,
+                condition: line.condition,
+                fingerprint: line.fingerprint,
+            });
+        }
+        Ok(Self(transitions))
+    }
+}
@@ -0,0 +1,154 @@
+//! Single-flight credential fetches: one request logs in or fetches a token while the others
+//! wait for its result instead of sending their own.
+
+use std::sync::{Condvar, Mutex, MutexGuard, PoisonError};
+
+use crate::{Error, Result, error::HttpError};
+
+/// A cached credential and the fetch that replaces it, shared by every clone of a client.
+pub(crate) struct Flight<T> {
+    state: Mutex<FlightState<T>>,
+    /// Signalled whenever a fetch finishes.
+    fetched: Condvar,
+    #[cfg(feature = "async")]
+    fetched_async: tokio::sync::Notify,
+}
+
+pub(crate) struct FlightState<T> {
+    pub(crate) value: Option<T>,
+    fetching: bool,
+    /// Finished fetches, so waiters can tell that theirs is done.
+    fetches: u64,
+    /// Error of the last fetch, handed to the requests that waited for it.
+    failure: Option<HttpError>,
+}
+
+pub(crate) enum Cached<'a, T> {
+    Fresh(String),
+    /// Fetch a new value. `current` is still valid and may be used if the fetch fails.
+    Fetch {
+        current: Option<String>,
+        claim: Claim<'a, T>,
+    },
+    /// Another request is fetching; wait for it, then ask again.
+    Wait(Pending),
+}
+
+#[derive(Clone, Copy)]
+pub(crate) struct Pending {
+    fetches: u64,
+}
+
+/// Held by the one request fetching. Dropping it, once the fetch finished or was abandoned,
+/// wakes the requests waiting for it.
+pub(crate) struct Claim<'a, T> {
+    flight: &'a Flight<T>,
+}
+
+impl<T> Default for Flight<T> {
+    fn default() -> Self {
+        Self {
+            state: Mutex::new(FlightState {
+                value: None,
+                fetching: false,
+                fetches: 0,
+                failure: None,
+            }),
+            fetched: Condvar::new(),
+            #[cfg(feature = "async")]
+            fetched_async: tokio::sync::Notify::new(),
+        }
+    }
+}
+
+impl<T> Flight<T> {
+    pub(crate) fn lock(&self) -> MutexGuard<'_, FlightState<T>> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Waits for the running fetch when there is one, otherwise claims the next fetch.
+    pub(crate) fn fetch_or_wait(
+        &self,
+        state: &mut FlightState<T>,
+        current: Option<String>,
+    ) -> Cached<'_, T> {
+        if state.fetching {
+            return Cached::Wait(Pending {
+                fetches: state.fetches,
+            });
+        }
+        state.fetching = true;
+        state.failure = None;
+        Cached::Fetch {
+            current,
+            claim: Claim { flight: self },
+        }
+    }
+
+    /// Blocks until the fetch `pending` waits for has finished, and returns its error if it
+    /// failed.
+    #[cfg(feature = "blocking-core")]
+    pub(crate) fn wait(&self, pending: Pending) -> Result<()> {
+        let mut state = self.lock();
+        loop {
+            if let Some(outcome) = state.outcome(pending) {
+                return outcome;
+            }
+            state = self
+                .fetched
+                .wait(state)
+                .unwrap_or_else(PoisonError::into_inner);
+        }
+    }
+
+    /// Async version of [`wait`](Self::wait).
+    #[cfg(feature = "async")]
+    pub(crate) async fn wait_async(&self, pending: Pending) -> Result<()> {
+        loop {
+            let mut notified = std::pin::pin!(self.fetched_async.notified());
+            notified.as_mut().enable();
+            if let Some(outcome) = self.lock().outcome(pending) {
+                return outcome;
+            }
+            notified.await;
+        }
+    }
+}
+
+impl<T> FlightState<T> {
+    pub(crate) fn is_fetching(&self) -> bool {
+        self.fetching
+    }
+
+    /// `None` while the fetch `pending` waits for is still running.
+    fn outcome(&self, pending: Pending) -> Option<Result<()>> {
+        if self.fetches == pending.fetches {
+            return None;
+        }
+        Some(match &self.failure {
+            Some(error) => Err(Error::Auth(Box::new(error.clone()))),
+            None => Ok(()),
+        })
+    }
+}
+
+impl<T> Claim<'_, T> {
+    /// Hands the error of a failed fetch to the requests waiting for it.
+    pub(crate) fn fail(&self, error: &Error) {
+        if let Error::Auth(error) = error {
+            self.flight.lock().failure = Some(error.as_ref().clone());
+        }
+    }
+}
+
+impl<T> Drop for Claim<'_, T> {
+    fn drop(&mut self) {
+        let mut state = self.flight.lock();
+        state.fetching = false;
+        state.fetches += 1;
+        drop(state);
+        self.flight.fetched.notify_all();
+        #[cfg(feature = "async")]
+        self.flight.fetched_async.notify_waiters();
+    }
+}
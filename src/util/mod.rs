@@ -1,2 +1,4 @@
+pub(crate) mod flight;
 pub(crate) mod redact;
+pub(crate) mod time;
 pub(crate) mod url;
@@ -16,24 +16,55 @@ pub(crate) fn redact_body_snippet(bytes: &[u8], byte_limit: usize) -> Option<Str
     Some(truncate_utf8_bytes(&redact_text(&text), byte_limit))
 }
 
-fn redact_json_value(value: &mut Value) {
+/// JSON body as it may be written to disk, with credential fields masked. `None` when `bytes` is
+/// not JSON or holds nothing to mask, so the body can be kept byte for byte.
+#[cfg(feature = "cassette")]
+pub(crate) fn redact_json_body(bytes: &[u8]) -> Option<Vec<u8>> {
+    let mut value = serde_json::from_slice::<Value>(bytes).ok()?;
+    if !redact_json_value(&mut value) {
+        return None;
+    }
+    serde_json::to_vec(&value).ok()
+}
+
+/// Header value as it may be written to disk: credentials and cookies are replaced wholesale,
+/// other values only have inline bearer/basic tokens masked.
+#[cfg(feature = "cassette")]
+pub(crate) fn redact_header_value(name: &str, value: &str) -> String {
+    let name = name.to_ascii_lowercase();
+    if should_redact_key(&name)
+        || matches!(
+            name.as_str(),
+            "set-cookie" | "proxy-authorization" | "x-api-key" | "x-grafana-api-key"
+        )
+    {
+        return "<redacted>".to_owned();
+    }
+    redact_inline_token(value)
+}
+
+/// Masks credential fields in place; returns whether any were found.
+fn redact_json_value(value: &mut Value) -> bool {
+    let mut redacted = false;
     match value {
         Value::Object(map) => {
             for (key, value) in map.iter_mut() {
                 if should_redact_key(key) {
                     *value = Value::String("<redacted>".to_owned());
+                    redacted = true;
                 } else {
-                    redact_json_value(value);
+                    redacted |= redact_json_value(value);
                 }
             }
         }
         Value::Array(items) => {
             for item in items.iter_mut() {
-                redact_json_value(item);
+                redacted |= redact_json_value(item);
             }
         }
         _ => {}
     }
+    redacted
 }
 
 fn should_redact_key(key: &str) -> bool {
@@ -49,6 +80,7 @@ fn should_redact_key(key: &str) -> bool {
             | "refresh_token"
             | "api_key"
             | "apikey"
+            | "key"
     )
 }
 
@@ -24,3 +24,71 @@ pub(crate) fn endpoint(base_url: &Url, segments: &[&str]) -> Result<Url> {
 
     Ok(url)
 }
+
+/// Parses and validates a Grafana root URL as accepted by the client builders.
+pub(crate) fn parse_base_url(base_url: &str) -> Result<Url> {
+    let base_url = Url::parse(base_url)
+        .map_err(|e| Error::invalid_config(format!("invalid base_url: {e}")))?;
+
+    if base_url.cannot_be_a_base() {
+        return Err(Error::invalid_config("base_url must be hierarchical"));
+    }
+    if !matches!(base_url.scheme(), "http" | "https") {
+        return Err(Error::invalid_config(
+            "base_url scheme must be http or https",
+        ));
+    }
+    if base_url.host_str().is_none() {
+        return Err(Error::invalid_config("base_url must include a host"));
+    }
+    if base_url.query().is_some() || base_url.fragment().is_some() {
+        return Err(Error::invalid_config(
+            "base_url must not include query or fragment",
+        ));
+    }
+    Ok(base_url)
+}
+
+/// Grafana's `/login` endpoint next to the `/api` root.
+pub(crate) fn login_url(api_base_url: &Url) -> Result<Url> {
+    let mut url = api_base_url.clone();
+    {
+        let mut path = url.path_segments_mut().map_err(|_| {
+            Error::invalid_config("base_url must be a hierarchical URL (e.g. https://host/)")
+        })?;
+        path.pop_if_empty().pop().push("login");
+    }
+    Ok(url)
+}
+
+/// The `/api` root under `base_url`, unless the URL already ends in it.
+pub(crate) fn api_base_url(base_url: &Url) -> Result<Url> {
+    match base_url
+        .path_segments()
+        .and_then(|mut segs| segs.rfind(|s| !s.is_empty()))
+    {
+        Some("api") => Ok(base_url.clone()),
+        _ => endpoint(base_url, &["api"]),
+    }
+}
+
+/// Decodes `%XX` escapes; invalid escapes are kept as they are and invalid UTF-8 is replaced.
+#[cfg(any(feature = "async", feature = "blocking", feature = "testing"))]
+pub(crate) fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        if bytes[index] == b'%'
+            && let Some(hex) = value.get(index + 1..index + 3)
+            && let Ok(byte) = u8::from_str_radix(hex, 16)
+        {
+            decoded.push(byte);
+            index += 3;
+            continue;
+        }
+        decoded.push(bytes[index]);
+        index += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
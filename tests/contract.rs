@@ -2,7 +2,7 @@
 
 use std::time::Duration;
 
-use grafana::{Auth, Client};
+use grafana::{Client, config::Context};
 
 fn run_async(test: impl std::future::Future<Output = ()>) {
     let runtime = tokio::runtime::Builder::new_current_thread()
@@ -13,19 +13,10 @@ fn run_async(test: impl std::future::Future<Output = ()>) {
 }
 
 fn contract_client() -> Option<Client> {
-    let base_url = std::env::var("GRAFANA_BASE_URL").ok()?;
+    let context = Context::from_env().ok()?;
 
-    let auth = if let Ok(token) = std::env::var("GRAFANA_TOKEN") {
-        Auth::bearer(token)
-    } else {
-        let username = std::env::var("GRAFANA_USERNAME").ok()?;
-        let password = std::env::var("GRAFANA_PASSWORD").ok()?;
-        Auth::basic(username, password)
-    };
-
-    let client = Client::builder(base_url)
+    let client = Client::builder_from_context(&context)
         .ok()?
-        .auth(auth)
         .timeout(Duration::from_secs(30))
         .connect_timeout(Duration::from_secs(10))
         .max_retries(3)